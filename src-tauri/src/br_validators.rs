@@ -0,0 +1,758 @@
+// VALIDADORES DE DOCUMENTOS BRASILEIROS
+// CPF/CNPJ com dígitos verificadores, boleto (linha digitável e código de barras,
+// bancário e de arrecadação) e PIX copia-e-cola (BR Code EMV com CRC16).
+
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// ================================
+// CPF / CNPJ
+// ================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxIdKind {
+    Cpf,
+    Cnpj,
+}
+
+impl TaxIdKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaxIdKind::Cpf => "cpf",
+            TaxIdKind::Cnpj => "cnpj",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaxId {
+    pub kind: TaxIdKind,
+    // Só dígitos
+    pub digits: String,
+    // 000.000.000-00 ou 00.000.000/0000-00
+    pub formatted: String,
+    // Papel no documento (emitente, tomador, pagador...), quando há rótulo antes do número
+    pub role: Option<String>,
+    // Posição (em bytes) da ocorrência no texto
+    pub position: usize,
+}
+
+fn only_digits(value: &str) -> Vec<u32> {
+    value.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn all_same(digits: &[u32]) -> bool {
+    digits.windows(2).all(|pair| pair[0] == pair[1])
+}
+
+// Dígito verificador no esquema módulo 11 usado por CPF e CNPJ
+fn tax_id_check_digit(digits: &[u32], weights: &[u32]) -> u32 {
+    let sum: u32 = digits.iter().zip(weights).map(|(d, w)| d * w).sum();
+    let rest = sum % 11;
+    if rest < 2 { 0 } else { 11 - rest }
+}
+
+pub fn is_valid_cpf(value: &str) -> bool {
+    let digits = only_digits(value);
+    if digits.len() != 11 || all_same(&digits) {
+        return false;
+    }
+
+    let first = tax_id_check_digit(&digits[..9], &[10, 9, 8, 7, 6, 5, 4, 3, 2]);
+    let second = tax_id_check_digit(&digits[..10], &[11, 10, 9, 8, 7, 6, 5, 4, 3, 2]);
+    digits[9] == first && digits[10] == second
+}
+
+pub fn is_valid_cnpj(value: &str) -> bool {
+    let digits = only_digits(value);
+    if digits.len() != 14 || all_same(&digits) {
+        return false;
+    }
+
+    let first = tax_id_check_digit(&digits[..12], &[5, 4, 3, 2, 9, 8, 7, 6, 5, 4, 3, 2]);
+    let second = tax_id_check_digit(&digits[..13], &[6, 5, 4, 3, 2, 9, 8, 7, 6, 5, 4, 3, 2]);
+    digits[12] == first && digits[13] == second
+}
+
+pub fn format_cpf(digits: &str) -> String {
+    format!("{}.{}.{}-{}", &digits[0..3], &digits[3..6], &digits[6..9], &digits[9..11])
+}
+
+pub fn format_cnpj(digits: &str) -> String {
+    format!("{}.{}.{}/{}-{}", &digits[0..2], &digits[2..5], &digits[5..8], &digits[8..12], &digits[12..14])
+}
+
+// Rótulo no texto -> papel. Comparado sem acentos e em minúsculas.
+const ROLE_LABELS: &[(&str, &str)] = &[
+    ("emitente", "emitente"),
+    ("emissor", "emitente"),
+    ("prestador", "prestador"),
+    ("tomador", "tomador"),
+    ("destinatario", "destinatario"),
+    ("remetente", "remetente"),
+    ("beneficiario", "beneficiario"),
+    ("cedente", "beneficiario"),
+    ("sacador", "sacador_avalista"),
+    ("sacado", "pagador"),
+    ("pagador", "pagador"),
+    ("contratante", "contratante"),
+    ("contratad", "contratado"),
+    ("locador", "locador"),
+    ("locatario", "locatario"),
+    ("fornecedor", "fornecedor"),
+    ("cliente", "cliente"),
+    ("transportador", "transportador"),
+];
+
+// Quanto texto antes do número é considerado ao procurar o rótulo
+const ROLE_WINDOW_CHARS: usize = 80;
+
+/// Minúsculas sem acentos, para comparar rótulos e palavras-chave
+pub fn fold(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'Á' | 'À' | 'Â' | 'Ã' => 'a',
+            'é' | 'ê' | 'É' | 'Ê' => 'e',
+            'í' | 'Í' => 'i',
+            'ó' | 'ô' | 'õ' | 'Ó' | 'Ô' | 'Õ' => 'o',
+            'ú' | 'ü' | 'Ú' | 'Ü' => 'u',
+            'ç' | 'Ç' => 'c',
+            other => other.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// Papel do rótulo (início de palavra, sem acentos) que termina por último em `window`;
+/// no empate vence o rótulo mais longo ("fim da vigencia" e não só "vigencia")
+pub fn closest_label<'a>(window: &str, labels: &[(&str, &'a str)]) -> Option<&'a str> {
+    let window = fold(window);
+
+    let mut best: Option<(usize, usize, &str)> = None;
+    for (label, role) in labels {
+        let found = window.match_indices(label).map(|(index, _)| index).filter(|&index| {
+            !window[..index].chars().next_back().is_some_and(char::is_alphanumeric)
+        }).last();
+        if let Some(index) = found {
+            let end = index + label.len();
+            let better = match best {
+                None => true,
+                Some((best_end, best_len, _)) => end > best_end || (end == best_end && label.len() > best_len),
+            };
+            if better {
+                best = Some((end, label.len(), role));
+            }
+        }
+    }
+    best.map(|(_, _, role)| role)
+}
+
+// Papel do rótulo mais próximo antes do número, sem voltar além da ocorrência anterior
+fn role_before(text: &str, start: usize, floor: usize) -> Option<String> {
+    let mut window: Vec<char> = text[floor..start].chars().rev().take(ROLE_WINDOW_CHARS).collect();
+    window.reverse();
+    closest_label(&window.into_iter().collect::<String>(), ROLE_LABELS).map(str::to_string)
+}
+
+/// Todos os CPFs e CNPJs com dígitos verificadores válidos, na ordem do texto.
+/// Repetições do mesmo número com o mesmo papel aparecem uma vez só.
+pub fn find_tax_ids(text: &str) -> Vec<TaxId> {
+    let candidates = match Regex::new(r"\b(?:\d{2}\.?\d{3}\.?\d{3}/?\d{4}-?\d{2}|\d{3}\.?\d{3}\.?\d{3}-?\d{2})\b") {
+        Ok(regex) => regex,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut found: Vec<TaxId> = Vec::new();
+    let mut floor = 0;
+    for candidate in candidates.find_iter(text) {
+        let digits: String = candidate.as_str().chars().filter(|c| c.is_ascii_digit()).collect();
+        let (kind, formatted) = match digits.len() {
+            11 if is_valid_cpf(&digits) => (TaxIdKind::Cpf, format_cpf(&digits)),
+            14 if is_valid_cnpj(&digits) => (TaxIdKind::Cnpj, format_cnpj(&digits)),
+            _ => continue,
+        };
+
+        let role = role_before(text, candidate.start(), floor);
+        floor = candidate.end();
+
+        if found.iter().any(|t| t.digits == digits && t.role == role) {
+            continue;
+        }
+        found.push(TaxId { kind, digits, formatted, role, position: candidate.start() });
+    }
+    found
+}
+
+// ================================
+// BOLETO
+// ================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BoletoKind {
+    // Cobrança bancária: linha de 47 dígitos
+    Bancario,
+    // Contas de consumo, tributos e multas: linha de 48 dígitos, começa com 8
+    Arrecadacao,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Boleto {
+    pub kind: BoletoKind,
+    // Código de barras (44 dígitos)
+    pub barcode: String,
+    // Linha digitável (47 ou 48 dígitos, sem pontuação)
+    pub digitable_line: String,
+    pub bank_code: Option<String>,
+    pub bank_name: Option<String>,
+    // Segmento da arrecadação (energia, telecomunicações...)
+    pub segment: Option<String>,
+    // Valor com ponto decimal ("1523.75"); ausente quando o boleto não traz valor
+    pub value: Option<String>,
+    pub due_factor: Option<u32>,
+    pub due_date: Option<NaiveDate>,
+}
+
+// Módulo 10 da FEBRABAN (pesos 2 e 1 da direita para a esquerda, somando os algarismos)
+fn mod10(digits: &[u32]) -> u32 {
+    let mut sum = 0;
+    for (i, d) in digits.iter().rev().enumerate() {
+        let product = d * if i % 2 == 0 { 2 } else { 1 };
+        sum += product / 10 + product % 10;
+    }
+    (10 - sum % 10) % 10
+}
+
+// Soma do módulo 11 com pesos 2..9 da direita para a esquerda
+fn mod11_sum(digits: &[u32]) -> u32 {
+    digits.iter().rev().enumerate().map(|(i, d)| d * (2 + (i as u32 % 8))).sum()
+}
+
+// DV geral do boleto bancário: 0, 10 e 11 viram 1
+fn bank_barcode_dv(digits: &[u32]) -> u32 {
+    match 11 - mod11_sum(digits) % 11 {
+        0 | 10 | 11 => 1,
+        dv => dv,
+    }
+}
+
+// DV módulo 11 da arrecadação: restos 0 e 1 dão 0
+fn collection_mod11(digits: &[u32]) -> u32 {
+    match mod11_sum(digits) % 11 {
+        0 | 1 => 0,
+        rest => 11 - rest,
+    }
+}
+
+fn digits_to_string(digits: &[u32]) -> String {
+    digits.iter().map(|d| char::from_digit(*d, 10).unwrap_or('0')).collect()
+}
+
+fn cents_to_value(cents: u64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+pub fn bank_name(code: &str) -> Option<&'static str> {
+    let name = match code {
+        "001" => "Banco do Brasil",
+        "033" => "Santander",
+        "041" => "Banrisul",
+        "070" => "BRB",
+        "077" => "Banco Inter",
+        "104" => "Caixa Econômica Federal",
+        "208" => "BTG Pactual",
+        "212" => "Banco Original",
+        "237" => "Bradesco",
+        "260" => "Nu Pagamentos",
+        "336" => "C6 Bank",
+        "341" => "Itaú",
+        "422" => "Safra",
+        "748" => "Sicredi",
+        "756" => "Sicoob",
+        _ => return None,
+    };
+    Some(name)
+}
+
+fn collection_segment(digit: u32) -> Option<&'static str> {
+    let name = match digit {
+        1 => "Prefeituras",
+        2 => "Saneamento",
+        3 => "Energia elétrica e gás",
+        4 => "Telecomunicações",
+        5 => "Órgãos governamentais",
+        6 => "Carnês e assemelhados",
+        7 => "Multas de trânsito",
+        9 => "Uso exclusivo do banco",
+        _ => return None,
+    };
+    Some(name)
+}
+
+const FACTOR_BASE: (i32, u32, u32) = (1997, 10, 7);
+// Em 22/02/2025 o fator de vencimento passou de 9999 para 1000
+const FACTOR_RESET: (i32, u32, u32) = (2025, 2, 22);
+
+/// Data de vencimento pelo fator de vencimento. Depois do reinício de 2025 cada fator
+/// corresponde a duas datas; vale a mais próxima de `reference`.
+pub fn due_date_from_factor(factor: u32, reference: NaiveDate) -> Option<NaiveDate> {
+    if factor == 0 {
+        return None;
+    }
+
+    let (y, m, d) = FACTOR_BASE;
+    let original = NaiveDate::from_ymd_opt(y, m, d)? + Duration::days(factor as i64);
+    if factor < 1000 {
+        return Some(original);
+    }
+
+    let (y, m, d) = FACTOR_RESET;
+    let restarted = NaiveDate::from_ymd_opt(y, m, d)? + Duration::days(factor as i64 - 1000);
+    if (restarted - reference).num_days().abs() < (original - reference).num_days().abs() {
+        Some(restarted)
+    } else {
+        Some(original)
+    }
+}
+
+fn bank_boleto(barcode: &[u32], digitable_line: String, reference: NaiveDate) -> Option<Boleto> {
+    let mut without_dv = barcode[..4].to_vec();
+    without_dv.extend_from_slice(&barcode[5..]);
+    if barcode[4] != bank_barcode_dv(&without_dv) {
+        return None;
+    }
+
+    let bank_code = digits_to_string(&barcode[0..3]);
+    let factor = barcode[5..9].iter().fold(0, |acc, d| acc * 10 + d);
+    let cents = barcode[9..19].iter().fold(0u64, |acc, d| acc * 10 + *d as u64);
+
+    Some(Boleto {
+        kind: BoletoKind::Bancario,
+        barcode: digits_to_string(barcode),
+        digitable_line,
+        bank_name: bank_name(&bank_code).map(str::to_string),
+        bank_code: Some(bank_code),
+        segment: None,
+        value: (cents > 0).then(|| cents_to_value(cents)),
+        due_factor: (factor > 0).then_some(factor),
+        due_date: due_date_from_factor(factor, reference),
+    })
+}
+
+// Posições da linha digitável bancária que formam o código de barras
+fn bank_line_to_barcode(line: &[u32]) -> Vec<u32> {
+    let mut barcode = Vec::with_capacity(44);
+    barcode.extend_from_slice(&line[0..4]);
+    barcode.extend_from_slice(&line[32..47]);
+    barcode.extend_from_slice(&line[4..9]);
+    barcode.extend_from_slice(&line[10..20]);
+    barcode.extend_from_slice(&line[21..31]);
+    barcode
+}
+
+fn bank_barcode_to_line(barcode: &[u32]) -> Vec<u32> {
+    let mut line = Vec::with_capacity(47);
+    for field in [[&barcode[0..4], &barcode[19..24]].concat(), barcode[24..34].to_vec(), barcode[34..44].to_vec()] {
+        line.extend_from_slice(&field);
+        line.push(mod10(&field));
+    }
+    line.extend_from_slice(&barcode[4..19]);
+    line
+}
+
+// Módulo usado pelos DVs da arrecadação, definido pelo terceiro dígito
+fn collection_check_digit(value_id: u32, digits: &[u32]) -> Option<u32> {
+    match value_id {
+        6 | 7 => Some(mod10(digits)),
+        8 | 9 => Some(collection_mod11(digits)),
+        _ => None,
+    }
+}
+
+fn collection_boleto(barcode: &[u32]) -> Option<Boleto> {
+    let value_id = barcode[2];
+    let mut without_dv = barcode[..3].to_vec();
+    without_dv.extend_from_slice(&barcode[4..]);
+    if barcode[3] != collection_check_digit(value_id, &without_dv)? {
+        return None;
+    }
+
+    let mut line = Vec::with_capacity(48);
+    for block in barcode.chunks(11) {
+        line.extend_from_slice(block);
+        line.push(collection_check_digit(value_id, block)?);
+    }
+
+    // Identificador 6 ou 8: os 11 dígitos seguintes são o valor efetivo em centavos
+    let cents = barcode[4..15].iter().fold(0u64, |acc, d| acc * 10 + *d as u64);
+    let value = (matches!(value_id, 6 | 8) && cents > 0).then(|| cents_to_value(cents));
+
+    Some(Boleto {
+        kind: BoletoKind::Arrecadacao,
+        barcode: digits_to_string(barcode),
+        digitable_line: digits_to_string(&line),
+        bank_code: None,
+        bank_name: None,
+        segment: collection_segment(barcode[1]).map(str::to_string),
+        value,
+        due_factor: None,
+        due_date: None,
+    })
+}
+
+/// Interpreta uma linha digitável (47 ou 48 dígitos) ou um código de barras (44),
+/// ignorando pontos e espaços. Devolve None se algum dígito verificador não bater.
+pub fn parse_boleto(input: &str, reference: NaiveDate) -> Option<Boleto> {
+    let digits = only_digits(input);
+    match digits.len() {
+        47 => {
+            let fields = [(0, 9), (10, 20), (21, 31)];
+            if fields.iter().any(|&(start, end)| digits[end] != mod10(&digits[start..end])) {
+                return None;
+            }
+            bank_boleto(&bank_line_to_barcode(&digits), digits_to_string(&digits), reference)
+        }
+        48 => {
+            if digits[0] != 8 {
+                return None;
+            }
+            let blocks: Vec<&[u32]> = digits.chunks(12).collect();
+            if blocks.iter().any(|block| collection_check_digit(digits[2], &block[..11]) != Some(block[11])) {
+                return None;
+            }
+            let barcode: Vec<u32> = blocks.iter().flat_map(|block| block[..11].iter().copied()).collect();
+            collection_boleto(&barcode)
+        }
+        44 if digits[0] == 8 => collection_boleto(&digits),
+        44 => {
+            let line = digits_to_string(&bank_barcode_to_line(&digits));
+            bank_boleto(&digits, line, reference)
+        }
+        _ => None,
+    }
+}
+
+/// Boletos válidos encontrados no texto, sem repetição do mesmo código de barras.
+pub fn find_boletos(text: &str, reference: NaiveDate) -> Vec<Boleto> {
+    let patterns = [
+        // Linha digitável bancária: 00000.00000 00000.000000 00000.000000 0 00000000000000
+        r"\b\d{5}\.?\d{5}\s*\d{5}\.?\d{6}\s*\d{5}\.?\d{6}\s*\d\s*\d{14}\b",
+        // Linha digitável de arrecadação: 4 blocos de 11 dígitos + DV
+        r"\b8\d{10}[-\s]?\d\s*\d{11}[-\s]?\d\s*\d{11}[-\s]?\d\s*\d{11}[-\s]?\d\b",
+        // Código de barras lido como texto
+        r"\b\d{44}\b",
+    ];
+
+    let mut boletos: Vec<Boleto> = Vec::new();
+    for pattern in patterns {
+        let Ok(regex) = Regex::new(pattern) else { continue };
+        for candidate in regex.find_iter(text) {
+            if let Some(boleto) = parse_boleto(candidate.as_str(), reference) {
+                if !boletos.iter().any(|b| b.barcode == boleto.barcode) {
+                    boletos.push(boleto);
+                }
+            }
+        }
+    }
+    boletos
+}
+
+// ================================
+// PIX (BR Code)
+// ================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PixPayload {
+    // Chave PIX (estático) ou None quando o QR dinâmico só traz a URL
+    pub key: Option<String>,
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub merchant_name: Option<String>,
+    pub merchant_city: Option<String>,
+    pub amount: Option<String>,
+    pub txid: Option<String>,
+    // Ponto de iniciação 12: QR de uso único
+    pub dynamic: bool,
+    pub payload: String,
+}
+
+/// CRC16-CCITT (polinômio 0x1021, valor inicial 0xFFFF), como pede o BR Code
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+// Lê campos ID(2) + tamanho(2) + valor. Para ao fechar o CRC (63) se `stop_at_crc`.
+fn parse_tlv(input: &str, stop_at_crc: bool) -> Option<(Vec<(String, String)>, usize)> {
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    while offset < input.len() {
+        let id = input.get(offset..offset + 2)?;
+        let len: usize = input.get(offset + 2..offset + 4)?.parse().ok()?;
+        if !id.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let value = input.get(offset + 4..offset + 4 + len)?;
+        offset += 4 + len;
+        fields.push((id.to_string(), value.to_string()));
+        if stop_at_crc && id == "63" {
+            return Some((fields, offset));
+        }
+    }
+    if stop_at_crc { None } else { Some((fields, offset)) }
+}
+
+fn field<'a>(fields: &'a [(String, String)], id: &str) -> Option<&'a str> {
+    fields.iter().find(|(field_id, _)| field_id == id).map(|(_, value)| value.as_str())
+}
+
+/// Interpreta um PIX copia-e-cola. Exige o GUI br.gov.bcb.pix e o CRC correto.
+pub fn parse_pix(payload: &str) -> Option<PixPayload> {
+    let payload = payload.trim();
+    let (fields, end) = parse_tlv(payload, true)?;
+    if end != payload.len() || field(&fields, "00") != Some("01") {
+        return None;
+    }
+
+    let crc = field(&fields, "63").filter(|crc| crc.len() == 4 && crc.is_ascii())?;
+    let expected = crc16_ccitt(&payload.as_bytes()[..payload.len() - 4]);
+    if !crc.eq_ignore_ascii_case(&format!("{:04X}", expected)) {
+        return None;
+    }
+
+    // Informações da conta do recebedor: IDs 26 a 51, com o GUI do PIX
+    let account = fields
+        .iter()
+        .filter(|(id, _)| id.as_str() >= "26" && id.as_str() <= "51")
+        .filter_map(|(_, value)| parse_tlv(value, false).map(|(sub, _)| sub))
+        .find(|sub| field(sub, "00").is_some_and(|gui| gui.eq_ignore_ascii_case("br.gov.bcb.pix")))?;
+
+    let owned = |value: Option<&str>| value.map(str::to_string);
+    let txid = field(&fields, "62")
+        .and_then(|value| parse_tlv(value, false))
+        .and_then(|(sub, _)| owned(field(&sub, "05")))
+        .filter(|txid| txid != "***");
+
+    Some(PixPayload {
+        key: owned(field(&account, "01")),
+        url: owned(field(&account, "25")),
+        description: owned(field(&account, "02")),
+        merchant_name: owned(field(&fields, "59")),
+        merchant_city: owned(field(&fields, "60")),
+        amount: owned(field(&fields, "54")),
+        txid,
+        dynamic: field(&fields, "01") == Some("12"),
+        payload: payload.to_string(),
+    })
+}
+
+/// Payloads PIX válidos no texto. Quebras de linha são ignoradas, porque o OCR
+/// costuma partir o código copia-e-cola em várias linhas.
+pub fn find_pix_payloads(text: &str) -> Vec<PixPayload> {
+    let joined: String = text.chars().filter(|c| *c != '\n' && *c != '\r').collect();
+
+    let mut payloads: Vec<PixPayload> = Vec::new();
+    for (start, _) in joined.match_indices("000201") {
+        let Some((_, end)) = parse_tlv(&joined[start..], true) else { continue };
+        if let Some(pix) = parse_pix(&joined[start..start + end]) {
+            if !payloads.iter().any(|p| p.payload == pix.payload) {
+                payloads.push(pix);
+            }
+        }
+    }
+    payloads
+}
+
+// ================================
+// CAMPOS PARA extracted_fields
+// ================================
+
+/// Campos de tudo o que foi validado no texto. As chaves simples (`cnpj`, `cpf`,
+/// `boleto_*`, `pix_*`) trazem a primeira ocorrência; `documentos`, `boletos` e `pix`
+/// trazem a lista completa em JSON. `reference` desempata o fator de vencimento.
+pub fn document_fields(text: &str, reference: NaiveDate) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let mut insert = |key: &str, value: Option<String>| {
+        if let Some(value) = value {
+            fields.entry(key.to_string()).or_insert(value);
+        }
+    };
+
+    let tax_ids = find_tax_ids(text);
+    for tax_id in &tax_ids {
+        insert(tax_id.kind.as_str(), Some(tax_id.formatted.clone()));
+        if let Some(role) = &tax_id.role {
+            insert(&format!("{}_{}", role, tax_id.kind.as_str()), Some(tax_id.formatted.clone()));
+        }
+    }
+
+    let boletos = find_boletos(text, reference);
+    if let Some(boleto) = boletos.first() {
+        insert("boleto_linha_digitavel", Some(boleto.digitable_line.clone()));
+        insert("boleto_codigo_barras", Some(boleto.barcode.clone()));
+        insert("boleto_banco", boleto.bank_code.clone());
+        insert("boleto_banco_nome", boleto.bank_name.clone());
+        insert("boleto_segmento", boleto.segment.clone());
+        insert("boleto_valor", boleto.value.clone());
+        insert("boleto_vencimento", boleto.due_date.map(|date| date.format("%Y-%m-%d").to_string()));
+    }
+
+    let payloads = find_pix_payloads(text);
+    if let Some(pix) = payloads.first() {
+        insert("pix_chave", pix.key.clone());
+        insert("pix_url", pix.url.clone());
+        insert("pix_valor", pix.amount.clone());
+        insert("pix_recebedor", pix.merchant_name.clone());
+        insert("pix_cidade", pix.merchant_city.clone());
+        insert("pix_txid", pix.txid.clone());
+    }
+
+    if !tax_ids.is_empty() {
+        insert("documentos", serde_json::to_string(&tax_ids).ok());
+    }
+    if !boletos.is_empty() {
+        insert("boletos", serde_json::to_string(&boletos).ok());
+    }
+    if !payloads.is_empty() {
+        insert("pix", serde_json::to_string(&payloads).ok());
+    }
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIX: &str = "00020126400014br.gov.bcb.pix0118fulano@example.com5204000053039865406150.005802BR5913FULANO DE TAL6009SAO PAULO62130509PEDIDO12363048332";
+
+    fn reference() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+    }
+
+    #[test]
+    fn test_cpf_cnpj_check_digits() {
+        assert!(is_valid_cpf("529.982.247-25"));
+        assert!(is_valid_cpf("12345678909"));
+        assert!(!is_valid_cpf("529.982.247-24"));
+        assert!(!is_valid_cpf("111.111.111-11"));
+        assert!(!is_valid_cpf("1234567890"));
+
+        assert!(is_valid_cnpj("11.222.333/0001-81"));
+        assert!(is_valid_cnpj("12345678000195"));
+        assert!(!is_valid_cnpj("11.222.333/0001-82"));
+        assert!(!is_valid_cnpj("00000000000000"));
+    }
+
+    #[test]
+    fn test_find_tax_ids_with_roles() {
+        let text = "PRESTADOR DE SERVIÇOS\nEmpresa Exemplo LTDA\nCNPJ: 11.222.333/0001-81\n\
+                    TOMADOR DE SERVIÇOS\nMaria da Silva CPF 529.982.247-25\n\
+                    Telefone 12345678901\nOutro CNPJ 11222333000181";
+        let found = find_tax_ids(text);
+
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].kind, TaxIdKind::Cnpj);
+        assert_eq!(found[0].formatted, "11.222.333/0001-81");
+        assert_eq!(found[0].role.as_deref(), Some("prestador"));
+        assert_eq!(found[1].kind, TaxIdKind::Cpf);
+        assert_eq!(found[1].role.as_deref(), Some("tomador"));
+        // O rótulo do tomador não se estende além da ocorrência seguinte
+        assert_eq!(found[2].digits, "11222333000181");
+        assert_eq!(found[2].role, None);
+    }
+
+    #[test]
+    fn test_parse_bank_boleto_line_and_barcode() {
+        let boleto = parse_boleto("34191.09123 34567.812341 56789.012301 6 11430000152375", reference()).unwrap();
+        assert_eq!(boleto.kind, BoletoKind::Bancario);
+        assert_eq!(boleto.barcode, "34196114300001523751091234567812345678901230");
+        assert_eq!(boleto.bank_code.as_deref(), Some("341"));
+        assert_eq!(boleto.bank_name.as_deref(), Some("Itaú"));
+        assert_eq!(boleto.value.as_deref(), Some("1523.75"));
+        assert_eq!(boleto.due_factor, Some(1143));
+        assert_eq!(boleto.due_date, NaiveDate::from_ymd_opt(2025, 7, 15));
+
+        let from_barcode = parse_boleto("34196114300001523751091234567812345678901230", reference()).unwrap();
+        assert_eq!(from_barcode.digitable_line, boleto.digitable_line);
+
+        // Um dígito trocado invalida a linha
+        assert!(parse_boleto("34191.09123 34567.812341 56789.012301 6 11430000152376", reference()).is_none());
+    }
+
+    #[test]
+    fn test_due_factor_before_reset() {
+        let old_reference = NaiveDate::from_ymd_opt(2015, 1, 1).unwrap();
+        assert_eq!(due_date_from_factor(6300, old_reference), NaiveDate::from_ymd_opt(2015, 1, 6));
+        assert_eq!(due_date_from_factor(0, old_reference), None);
+    }
+
+    #[test]
+    fn test_collection_boleto_round_trip() {
+        // Monta um código de arrecadação com DV módulo 10 e confere a linha de 48 dígitos
+        let mut barcode = only_digits("83600000001234500481000123456789012345678901");
+        let mut without_dv = barcode[..3].to_vec();
+        without_dv.extend_from_slice(&barcode[4..]);
+        barcode[3] = mod10(&without_dv);
+
+        let boleto = parse_boleto(&digits_to_string(&barcode), reference()).unwrap();
+        assert_eq!(boleto.kind, BoletoKind::Arrecadacao);
+        assert_eq!(boleto.segment.as_deref(), Some("Energia elétrica e gás"));
+        assert_eq!(boleto.value.as_deref(), Some("123.45"));
+        assert_eq!(boleto.digitable_line.len(), 48);
+
+        let from_line = parse_boleto(&boleto.digitable_line, reference()).unwrap();
+        assert_eq!(from_line.barcode, boleto.barcode);
+    }
+
+    #[test]
+    fn test_parse_pix_payload() {
+        let pix = parse_pix(PIX).unwrap();
+        assert_eq!(pix.key.as_deref(), Some("fulano@example.com"));
+        assert_eq!(pix.merchant_name.as_deref(), Some("FULANO DE TAL"));
+        assert_eq!(pix.merchant_city.as_deref(), Some("SAO PAULO"));
+        assert_eq!(pix.amount.as_deref(), Some("150.00"));
+        assert_eq!(pix.txid.as_deref(), Some("PEDIDO123"));
+        assert!(!pix.dynamic);
+
+        let broken = PIX.replace("150.00", "151.00");
+        assert!(parse_pix(&broken).is_none());
+    }
+
+    #[test]
+    fn test_find_pix_split_across_lines() {
+        let text = format!("Pague com PIX copia e cola:\n{}\n{}\nObrigado", &PIX[..60], &PIX[60..]);
+        let found = find_pix_payloads(&text);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].payload, PIX);
+        assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn test_document_fields() {
+        let text = format!(
+            "Beneficiário: Loja Exemplo CNPJ 11.222.333/0001-81\nPagador: Maria CPF 529.982.247-25\n\
+             34191.09123 34567.812341 56789.012301 6 11430000152375\n{}",
+            PIX
+        );
+        let fields = document_fields(&text, reference());
+
+        assert_eq!(fields["cnpj"], "11.222.333/0001-81");
+        assert_eq!(fields["beneficiario_cnpj"], "11.222.333/0001-81");
+        assert_eq!(fields["pagador_cpf"], "529.982.247-25");
+        assert_eq!(fields["boleto_banco"], "341");
+        assert_eq!(fields["boleto_valor"], "1523.75");
+        assert_eq!(fields["boleto_vencimento"], "2025-07-15");
+        assert_eq!(fields["pix_chave"], "fulano@example.com");
+        assert!(fields["documentos"].contains("\"role\":\"pagador\""));
+    }
+}
@@ -0,0 +1,413 @@
+// IMPORTAÇÃO EM LOTE DE UMA ÁRVORE DE DIRETÓRIOS OU DE UMA CAIXA DE E-MAIL (.mbox)
+// Percorre a pasta recursivamente (ou as mensagens do mbox), ignora tipos não suportados
+// e duplicatas, passa cada arquivo pelo pipeline de ingestão e devolve um relatório.
+// A trilha de auditoria recebe uma entrada BULK_IMPORT_FILE por arquivo e uma BULK_IMPORT final.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::database_sqlite::User;
+use crate::{email_parser, file_store, ingest, log_audit_event, normalize_tag_names, ocr_simple, AppState};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedFile {
+    pub file_path: String,
+    pub document_id: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportIssue {
+    pub file_path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub root_path: String,
+    pub total_files: usize,
+    pub imported: Vec<ImportedFile>,
+    pub skipped: Vec<ImportIssue>,
+    pub failed: Vec<ImportIssue>,
+    pub duration_ms: u128,
+}
+
+enum FileOutcome {
+    Imported(ImportedFile),
+    Skipped(String),
+    Failed(String),
+}
+
+// Todos os arquivos da árvore (ignorando arquivos e pastas ocultos), em ordem estável
+fn walk_directory(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("⚠️ Não foi possível ler {}: {:?}", dir.display(), e);
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with('.'))
+                .unwrap_or(false);
+            if hidden {
+                continue;
+            }
+
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+// "Contratos/2023/contrato.pdf" → ["Contratos", "2023"]
+fn subfolder_tags(root: &Path, path: &Path) -> Vec<String> {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let folders = relative.parent()
+        .map(|parent| {
+            parent.components()
+                .filter_map(|c| c.as_os_str().to_str())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    normalize_tag_names(folders)
+}
+
+async fn import_file(state: &AppState, user: &User, root: &Path, path: &Path, folders_as_tags: bool) -> FileOutcome {
+    if !ocr_simple::is_supported_file(path) {
+        return FileOutcome::Skipped("Tipo de arquivo não suportado".to_string());
+    }
+    if ocr_simple::is_non_nfe_xml(path) {
+        return FileOutcome::Skipped("XML não é uma NF-e/NFC-e".to_string());
+    }
+
+    // Verificar duplicata antes do OCR, que é a etapa mais cara
+    let file_hash = match file_store::hash_file(path) {
+        Ok(hash) => hash,
+        Err(e) => return FileOutcome::Failed(format!("Erro ao ler arquivo: {}", e)),
+    };
+    match state.db.find_active_document_by_hash(&user.id, &file_hash) {
+        Ok(Some(existing)) => return FileOutcome::Skipped(format!("Duplicata de '{}'", existing.name)),
+        Ok(None) => {}
+        Err(e) => return FileOutcome::Failed(format!("Erro ao verificar duplicatas: {:?}", e)),
+    }
+
+    let file_path = path.to_string_lossy().to_string();
+    let document = match ingest::ingest_file(state, user, &file_path, Some("reject"), Some(&file_hash)).await {
+        Ok(document) => document,
+        Err(e) => return FileOutcome::Failed(e),
+    };
+
+    let tags = if folders_as_tags { subfolder_tags(root, path) } else { vec![] };
+    if !tags.is_empty() {
+        if let Err(e) = state.db.set_document_tags(&user.id, &document.id, &tags) {
+            log::warn!("⚠️ Erro ao aplicar tags em {}: {:?}", document.id, e);
+        }
+    }
+
+    FileOutcome::Imported(ImportedFile {
+        file_path,
+        document_id: document.id,
+        tags,
+    })
+}
+
+#[tauri::command]
+pub async fn import_directory(
+    root_path: String,
+    folders_as_tags: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<ImportReport, String> {
+    // Copiar o usuário e liberar o lock: a importação pode levar minutos
+    let authenticated_user = state.authenticated_user.lock().await.clone();
+    if let Some(user) = authenticated_user.as_ref() {
+        let start_time = std::time::Instant::now();
+        let root = Path::new(&root_path);
+        if !root.is_dir() {
+            return Err(format!("Pasta não encontrada: {}", root_path));
+        }
+
+        let folders_as_tags = folders_as_tags.unwrap_or(false);
+        let files = walk_directory(root);
+        log::info!("📦 Importação em lote de {} ({} arquivos)", root_path, files.len());
+
+        let mut report = ImportReport {
+            root_path: root_path.clone(),
+            total_files: files.len(),
+            imported: vec![],
+            skipped: vec![],
+            failed: vec![],
+            duration_ms: 0,
+        };
+
+        for path in &files {
+            // Caixas de e-mail dentro da pasta: uma entrada no relatório por mensagem
+            if email_parser::is_mbox_file(path) {
+                let mbox_path = path.to_string_lossy().to_string();
+                let tags = if folders_as_tags { subfolder_tags(root, path) } else { vec![] };
+                let messages = match fs::read(path) {
+                    Ok(raw) => email_parser::split_mbox(&raw),
+                    Err(e) => {
+                        let outcome = FileOutcome::Failed(format!("Erro ao ler caixa de e-mail: {}", e));
+                        record_outcome(&state, user, &mut report, mbox_path, outcome).await;
+                        continue;
+                    }
+                };
+                if messages.is_empty() {
+                    let outcome = FileOutcome::Skipped("Nenhuma mensagem encontrada na caixa de e-mail".to_string());
+                    record_outcome(&state, user, &mut report, mbox_path, outcome).await;
+                    continue;
+                }
+                report.total_files += messages.len() - 1;
+                import_mailbox_messages(&state, user, &mbox_path, &messages, &tags, &mut report).await?;
+                continue;
+            }
+            
+            let outcome = import_file(&state, user, root, path, folders_as_tags).await;
+            record_outcome(&state, user, &mut report, path.to_string_lossy().to_string(), outcome).await;
+        }
+
+        finish_report(&state, user, &mut report, start_time, serde_json::json!({
+            "folders_as_tags": folders_as_tags,
+        })).await;
+
+        Ok(report)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Registrar o resultado de um arquivo no relatório e na trilha de auditoria
+async fn record_outcome(state: &AppState, user: &User, report: &mut ImportReport, file_path: String, outcome: FileOutcome) {
+    let (status, document_id, reason) = match &outcome {
+        FileOutcome::Imported(file) => ("imported", Some(file.document_id.clone()), None),
+        FileOutcome::Skipped(reason) => ("skipped", None, Some(reason.clone())),
+        FileOutcome::Failed(reason) => ("failed", None, Some(reason.clone())),
+    };
+
+    let _ = log_audit_event(
+        state,
+        &user.id,
+        &user.username,
+        "BULK_IMPORT_FILE",
+        "DOCUMENT",
+        document_id,
+        Path::new(&file_path).file_name().and_then(|n| n.to_str()).map(|n| n.to_string()),
+        None,
+        Some(serde_json::json!({
+            "file_path": file_path,
+            "status": status,
+            "reason": reason,
+        })),
+        !matches!(outcome, FileOutcome::Failed(_)),
+    ).await;
+
+    match outcome {
+        FileOutcome::Imported(file) => report.imported.push(file),
+        FileOutcome::Skipped(reason) => report.skipped.push(ImportIssue { file_path, reason }),
+        FileOutcome::Failed(reason) => report.failed.push(ImportIssue { file_path, reason }),
+    }
+}
+
+// Fechar o relatório com a entrada BULK_IMPORT final (details recebe os totais)
+async fn finish_report(state: &AppState, user: &User, report: &mut ImportReport, start_time: std::time::Instant, mut details: serde_json::Value) {
+    report.duration_ms = start_time.elapsed().as_millis();
+
+    if let Some(details) = details.as_object_mut() {
+        details.insert("root_path".to_string(), serde_json::json!(report.root_path));
+        details.insert("total_files".to_string(), serde_json::json!(report.total_files));
+        details.insert("imported".to_string(), serde_json::json!(report.imported.len()));
+        details.insert("skipped".to_string(), serde_json::json!(report.skipped.len()));
+        details.insert("failed".to_string(), serde_json::json!(report.failed.len()));
+        details.insert("duration_ms".to_string(), serde_json::json!(report.duration_ms));
+    }
+
+    let _ = log_audit_event(
+        state,
+        &user.id,
+        &user.username,
+        "BULK_IMPORT",
+        "SYSTEM",
+        None,
+        Some(report.root_path.clone()),
+        None,
+        Some(details),
+        report.failed.is_empty(),
+    ).await;
+
+    log::info!(
+        "✅ Importação concluída: {} importados, {} ignorados, {} com falha ({}ms)",
+        report.imported.len(),
+        report.skipped.len(),
+        report.failed.len(),
+        report.duration_ms
+    );
+}
+
+// ================================
+// CAIXA DE E-MAIL (.mbox)
+// ================================
+
+// Cada mensagem do mbox é gravada como .eml temporário e importada como um e-mail avulso
+// (com seus anexos); a proveniência aponta para "<mbox>#<número da mensagem>"
+async fn import_mailbox_message(state: &AppState, user: &User, mbox_path: &str, number: usize, message: &[u8], work_dir: &Path) -> FileOutcome {
+    let email = email_parser::parse_email(message);
+    let file_name = email.subject.as_deref()
+        .map(email_parser::safe_file_name)
+        .unwrap_or_else(|| format!("mensagem-{}", number));
+
+    let message_dir = work_dir.join(number.to_string());
+    let message_path = message_dir.join(format!("{}.eml", file_name));
+    if let Err(e) = fs::create_dir(&message_dir).and_then(|_| fs::write(&message_path, message)) {
+        return FileOutcome::Failed(format!("Erro ao extrair mensagem: {}", e));
+    }
+
+    let file_hash = match file_store::hash_file(&message_path) {
+        Ok(hash) => hash,
+        Err(e) => return FileOutcome::Failed(format!("Erro ao ler mensagem: {}", e)),
+    };
+    match state.db.find_active_document_by_hash(&user.id, &file_hash) {
+        Ok(Some(existing)) => return FileOutcome::Skipped(format!("Duplicata de '{}'", existing.name)),
+        Ok(None) => {}
+        Err(e) => return FileOutcome::Failed(format!("Erro ao verificar duplicatas: {:?}", e)),
+    }
+
+    let document = match ingest::ingest_file(state, user, &message_path.to_string_lossy(), Some("reject"), Some(&file_hash)).await {
+        Ok(document) => document,
+        Err(e) => return FileOutcome::Failed(e),
+    };
+
+    // Trocar o caminho temporário pela origem no mbox, também nos anexos
+    let origin = format!("{}#{}", mbox_path, number);
+    if let Err(e) = state.db.set_document_origin(&user.id, &document.id, None, &origin) {
+        log::warn!("⚠️ Erro ao registrar origem de {}: {:?}", document.id, e);
+    }
+    for attachment_id in &document.attachment_ids {
+        if let Ok(Some(attachment)) = state.db.get_document_by_id(&user.id, attachment_id) {
+            if attachment.parent_document_id.as_deref() == Some(document.id.as_str()) {
+                let attachment_origin = format!("{}#{}", origin, attachment.name);
+                let _ = state.db.set_document_origin(&user.id, attachment_id, Some(&document.id), &attachment_origin);
+            }
+        }
+    }
+
+    FileOutcome::Imported(ImportedFile {
+        file_path: origin,
+        document_id: document.id,
+        tags: vec![],
+    })
+}
+
+// Importar as mensagens de um mbox, registrando cada uma no relatório; `tags` vão para
+// as mensagens importadas (subpastas, na importação de diretório)
+async fn import_mailbox_messages(
+    state: &AppState,
+    user: &User,
+    mbox_path: &str,
+    messages: &[Vec<u8>],
+    tags: &[String],
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    let work_dir = tempfile::tempdir()
+        .map_err(|e| format!("Erro ao criar pasta temporária: {:?}", e))?;
+
+    for (index, message) in messages.iter().enumerate() {
+        let number = index + 1;
+        let mut outcome = import_mailbox_message(state, user, mbox_path, number, message, work_dir.path()).await;
+        if let FileOutcome::Imported(file) = &mut outcome {
+            if !tags.is_empty() {
+                match state.db.set_document_tags(&user.id, &file.document_id, tags) {
+                    Ok(()) => file.tags = tags.to_vec(),
+                    Err(e) => log::warn!("⚠️ Erro ao aplicar tags em {}: {:?}", file.document_id, e),
+                }
+            }
+        }
+        record_outcome(state, user, report, format!("{}#{}", mbox_path, number), outcome).await;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn import_mailbox(
+    mbox_path: String,
+    state: State<'_, AppState>,
+) -> Result<ImportReport, String> {
+    // Copiar o usuário e liberar o lock: a importação pode levar minutos
+    let authenticated_user = state.authenticated_user.lock().await.clone();
+    if let Some(user) = authenticated_user.as_ref() {
+        let start_time = std::time::Instant::now();
+        let raw = fs::read(&mbox_path)
+            .map_err(|e| format!("Erro ao ler caixa de e-mail {}: {}", mbox_path, e))?;
+        let messages = email_parser::split_mbox(&raw);
+        if messages.is_empty() {
+            return Err(format!("Nenhuma mensagem encontrada em {}", mbox_path));
+        }
+        log::info!("📬 Importação da caixa de e-mail {} ({} mensagens)", mbox_path, messages.len());
+
+        let mut report = ImportReport {
+            root_path: mbox_path.clone(),
+            total_files: messages.len(),
+            imported: vec![],
+            skipped: vec![],
+            failed: vec![],
+            duration_ms: 0,
+        };
+
+        import_mailbox_messages(&state, user, &mbox_path, &messages, &[], &mut report).await?;
+
+        finish_report(&state, user, &mut report, start_time, serde_json::json!({
+            "source": "mbox",
+        })).await;
+
+        Ok(report)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_directory_recursive_without_hidden() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("Contratos/2023")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("nota.pdf"), b"x").unwrap();
+        fs::write(root.join(".DS_Store"), b"x").unwrap();
+        fs::write(root.join("Contratos/2023/contrato.pdf"), b"x").unwrap();
+        fs::write(root.join(".git/config"), b"x").unwrap();
+
+        assert_eq!(walk_directory(root), vec![
+            root.join("Contratos/2023/contrato.pdf"),
+            root.join("nota.pdf"),
+        ]);
+    }
+
+    #[test]
+    fn test_subfolder_tags() {
+        let root = Path::new("/importar");
+        assert_eq!(subfolder_tags(root, &root.join("Contratos/2023/contrato.pdf")), vec!["Contratos", "2023"]);
+        assert!(subfolder_tags(root, &root.join("nota.pdf")).is_empty());
+    }
+}
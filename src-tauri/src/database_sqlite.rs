@@ -9,6 +9,11 @@ use std::thread;
 use sha2::{Sha256, Digest};
 use std::fmt::Write;
 
+use crate::date_extractor::{DateExtractor, DateKind, TypedDate};
+use crate::money_parser::{self, MonetaryAmount};
+use crate::near_duplicate;
+use crate::ocr_simple::WordBox;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: String,
@@ -32,6 +37,61 @@ pub struct Document {
     pub tags: Vec<String>,
     pub document_date: Option<String>,
     pub folder_slug: Option<String>,
+    pub original_path: Option<String>, // Caminho de origem (proveniência)
+    pub file_hash: Option<String>,     // SHA-256 do conteúdo armazenado
+    pub deleted_at: Option<DateTime<Utc>>, // Preenchido quando o documento está na lixeira
+    pub parent_document_id: Option<String>, // Documento de origem (ex.: e-mail de onde veio o anexo)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentVersion {
+    pub id: String,
+    pub document_id: String,
+    pub version_number: i64,
+    pub file_path: String,
+    pub file_hash: Option<String>,
+    pub file_size: i64,
+    pub file_type: String,
+    pub extracted_text: String,
+    pub ocr_result: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub user_id: String,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: String, // queued, running, completed, failed, cancelled
+    pub progress: f64,
+    pub stage: Option<String>,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub attempts: i64,
+    pub cancel_requested: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchFolder {
+    pub id: String,
+    pub user_id: String,
+    pub path: String,
+    pub include_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    pub archive_processed: bool,
+    pub archive_subfolder: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub document_count: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +115,54 @@ pub struct DocumentContent {
     pub indexed_at: DateTime<Utc>,
 }
 
+// Filtros da busca e do relatório de valores monetários (datas em YYYY-MM-DD)
+#[derive(Debug, Clone, Default)]
+pub struct AmountFilter {
+    pub min_cents: Option<i64>,
+    pub max_cents: Option<i64>,
+    pub role: Option<String>,
+    pub currency: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+// Intervalo de datas aplicado à busca por texto (datas em YYYY-MM-DD). Sem `kind`, vale a
+// data principal do documento (document_date); com ele, uma das datas com papel
+#[derive(Debug, Clone)]
+pub struct DateRangeFilter {
+    pub start_date: String,
+    pub end_date: String,
+    pub kind: Option<DateKind>,
+}
+
+// Tags exigidas na busca: qualquer uma, ou todas com match_all
+#[derive(Debug, Clone)]
+pub struct TagFilter {
+    pub tags: Vec<String>,
+    pub match_all: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmountMatch {
+    pub document_id: String,
+    pub document_name: String,
+    pub document_date: Option<String>,
+    pub role: String,
+    pub currency: String,
+    pub amount_cents: i64,
+    pub amount: String, // "1234.56"
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmountTotal {
+    pub group: String,
+    pub currency: String,
+    pub count: i64,
+    pub total_cents: i64,
+    pub total: String, // "1234.56"
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub document_id: String,
@@ -87,6 +195,70 @@ pub struct AuditLog {
     pub is_success: bool,              // Se a ação foi bem-sucedida
 }
 
+// Colunas da tabela documents na ordem esperada por document_from_row
+const DOCUMENT_COLUMNS: &str = "id, user_id, name, file_path, file_type, file_size, created_at, updated_at, tags, document_date, folder_slug, original_path, file_hash, deleted_at, parent_document_id";
+
+// Colunas da tabela jobs na ordem esperada por job_from_row
+const JOB_COLUMNS: &str =
+    "id, user_id, kind, payload, status, progress, stage, result, error, attempts, cancel_requested, created_at, updated_at";
+
+// Colunas da tabela watch_folders na ordem esperada por watch_folder_from_row
+const WATCH_FOLDER_COLUMNS: &str =
+    "id, user_id, path, include_patterns, exclude_patterns, archive_processed, archive_subfolder, enabled, created_at";
+
+fn document_from_row(row: &rusqlite::Row) -> SqliteResult<Document> {
+    let created_at_str: String = row.get(6)?;
+    let updated_at_str: String = row.get(7)?;
+    let tags_json: String = row.get(8)?;
+    let deleted_at_str: Option<String> = row.get(13)?;
+    
+    let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+    
+    Ok(Document {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        name: row.get(2)?,
+        file_path: row.get(3)?,
+        file_type: row.get(4)?,
+        file_size: row.get(5)?,
+        created_at: DateTime::parse_from_rfc3339(&created_at_str)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(6, "created_at".to_string(), rusqlite::types::Type::Text))?
+            .with_timezone(&Utc),
+        updated_at: DateTime::parse_from_rfc3339(&updated_at_str)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(7, "updated_at".to_string(), rusqlite::types::Type::Text))?
+            .with_timezone(&Utc),
+        tags,
+        document_date: row.get(9)?,
+        folder_slug: row.get(10)?,
+        original_path: row.get(11)?,
+        file_hash: row.get(12)?,
+        deleted_at: deleted_at_str
+            .map(|s| DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()
+            .map_err(|_| rusqlite::Error::InvalidColumnType(13, "deleted_at".to_string(), rusqlite::types::Type::Text))?,
+        parent_document_id: row.get(14)?,
+    })
+}
+
+// Termo comparável com o tokenizer do FTS5 (unicode61 remove_diacritics):
+// minúsculas, sem acentos e só letras e dígitos
+fn fold_search_term(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ã' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            other => other,
+        })
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
     db_path: PathBuf,
@@ -269,10 +441,159 @@ impl Database {
             log::debug!("⚠️ Coluna folder_slug já existe, pulando migration");
         }
         
+        // Migration 3: Armazenamento gerenciado - caminho original (proveniência) e hash do conteúdo
+        if !column_exists("documents", "original_path") {
+            conn.execute("ALTER TABLE documents ADD COLUMN original_path TEXT", [])?;
+            log::info!("✅ Migration: coluna original_path adicionada");
+        } else {
+            log::debug!("⚠️ Coluna original_path já existe, pulando migration");
+        }
+        
+        if !column_exists("documents", "file_hash") {
+            conn.execute("ALTER TABLE documents ADD COLUMN file_hash TEXT", [])?;
+            log::info!("✅ Migration: coluna file_hash adicionada");
+        } else {
+            log::debug!("⚠️ Coluna file_hash já existe, pulando migration");
+        }
+        
+        // Migration 4: Lixeira - soft delete com data de exclusão
+        if !column_exists("documents", "deleted_at") {
+            conn.execute("ALTER TABLE documents ADD COLUMN deleted_at TEXT", [])?;
+            log::info!("✅ Migration: coluna deleted_at adicionada");
+        } else {
+            log::debug!("⚠️ Coluna deleted_at já existe, pulando migration");
+        }
+        
+        // Migration 5: Documentos filhos (anexos extraídos de e-mails)
+        if !column_exists("documents", "parent_document_id") {
+            conn.execute("ALTER TABLE documents ADD COLUMN parent_document_id TEXT", [])?;
+            log::info!("✅ Migration: coluna parent_document_id adicionada");
+        } else {
+            log::debug!("⚠️ Coluna parent_document_id já existe, pulando migration");
+        }
+        
         // ÍNDICES PARA BUSCA POR DATA E PASTA
         conn.execute("CREATE INDEX IF NOT EXISTS idx_documents_document_date ON documents(document_date)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_documents_folder_slug ON documents(folder_slug)", [])?;
         conn.execute("CREATE INDEX IF NOT EXISTS idx_documents_folder_user ON documents(user_id, folder_slug)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_documents_file_hash ON documents(user_id, file_hash)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_documents_deleted_at ON documents(user_id, deleted_at)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_documents_parent ON documents(parent_document_id)", [])?;
+        
+        // SISTEMA DE TAGS NORMALIZADO
+        // documents.tags (JSON) é mantido apenas como cache de leitura, atualizado a cada mudança
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS tags (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL COLLATE NOCASE,
+                created_at TEXT NOT NULL,
+                UNIQUE (user_id, name),
+                FOREIGN KEY (user_id) REFERENCES users (id)
+            )
+        "#, [])?;
+        
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS document_tags (
+                document_id TEXT NOT NULL,
+                tag_id TEXT NOT NULL,
+                PRIMARY KEY (document_id, tag_id),
+                FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE,
+                FOREIGN KEY (tag_id) REFERENCES tags (id) ON DELETE CASCADE
+            )
+        "#, [])?;
+        
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_tags_user_id ON tags(user_id)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_document_tags_tag_id ON document_tags(tag_id)", [])?;
+        
+        // Migration 6: Copiar tags legadas (JSON em documents.tags) para as tabelas normalizadas
+        let legacy_tags: Vec<(String, String, String)> = {
+            let mut stmt = conn.prepare(
+                "SELECT d.id, d.user_id, je.value FROM documents d, json_each(d.tags) je 
+                 WHERE json_valid(d.tags) AND d.tags != '[]' 
+                 AND NOT EXISTS (SELECT 1 FROM document_tags dt WHERE dt.document_id = d.id)"
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<SqliteResult<Vec<_>>>()?
+        };
+        
+        if !legacy_tags.is_empty() {
+            for (document_id, user_id, tag_name) in &legacy_tags {
+                let tag_id = Self::get_or_create_tag(&conn, user_id, tag_name)?;
+                conn.execute(
+                    "INSERT OR IGNORE INTO document_tags (document_id, tag_id) VALUES (?1, ?2)",
+                    params![document_id, tag_id]
+                )?;
+            }
+            log::info!("✅ Migration: {} tags legadas migradas para tabela normalizada", legacy_tags.len());
+        }
+        
+        // FILA DE PROCESSAMENTO EM SEGUNDO PLANO (OCR, extração, indexação)
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS jobs (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                payload TEXT NOT NULL DEFAULT '{}',
+                status TEXT NOT NULL DEFAULT 'queued',
+                progress REAL NOT NULL DEFAULT 0,
+                stage TEXT,
+                result TEXT,
+                error TEXT,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                cancel_requested INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (user_id) REFERENCES users (id)
+            )
+        "#, [])?;
+        
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status, created_at)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_jobs_user_id ON jobs(user_id, created_at)", [])?;
+        
+        // PASTAS MONITORADAS - importação automática de arquivos novos
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS watch_folders (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                include_patterns TEXT NOT NULL DEFAULT '[]',
+                exclude_patterns TEXT NOT NULL DEFAULT '[]',
+                archive_processed INTEGER NOT NULL DEFAULT 0,
+                archive_subfolder TEXT NOT NULL DEFAULT 'Processados',
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                UNIQUE (user_id, path),
+                FOREIGN KEY (user_id) REFERENCES users (id)
+            )
+        "#, [])?;
+        
+        // Arquivos já vistos em cada pasta monitorada (evita reprocessar)
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS watch_folder_files (
+                watch_folder_id TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                modified_at TEXT NOT NULL,
+                status TEXT NOT NULL,
+                document_id TEXT,
+                error_message TEXT,
+                processed_at TEXT NOT NULL,
+                PRIMARY KEY (watch_folder_id, file_path),
+                FOREIGN KEY (watch_folder_id) REFERENCES watch_folders (id) ON DELETE CASCADE
+            )
+        "#, [])?;
+        
+        // Preferências por usuário (chave/valor) - ex: retenção da lixeira
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS user_settings (
+                user_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (user_id, key),
+                FOREIGN KEY (user_id) REFERENCES users (id)
+            )
+        "#, [])?;
         
         log::info!("📅 Sistema de organização por data e pastas configurado");
         
@@ -292,6 +613,149 @@ impl Database {
             )
         "#, [])?;
         
+        // Migration 7: Tipo corrigido pelo usuário (exemplo de treino do classificador)
+        if !column_exists("document_content", "type_corrected") {
+            conn.execute("ALTER TABLE document_content ADD COLUMN type_corrected INTEGER NOT NULL DEFAULT 0", [])?;
+            log::info!("✅ Migration: coluna type_corrected adicionada");
+        } else {
+            log::debug!("⚠️ Coluna type_corrected já existe, pulando migration");
+        }
+        
+        // Palavras reconhecidas pelo OCR com o retângulo na página, para destacar os
+        // termos buscados sobre a imagem. `normalized`: minúsculas, sem acentos nem pontuação
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS document_words (
+                document_id TEXT NOT NULL,
+                page_number INTEGER NOT NULL,
+                word_index INTEGER NOT NULL,
+                text TEXT NOT NULL,
+                normalized TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                box_left INTEGER NOT NULL,
+                box_top INTEGER NOT NULL,
+                box_width INTEGER NOT NULL,
+                box_height INTEGER NOT NULL,
+                page_width INTEGER NOT NULL,
+                page_height INTEGER NOT NULL,
+                PRIMARY KEY (document_id, page_number, word_index),
+                FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE
+            )
+        "#, [])?;
+        
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_document_words_normalized ON document_words(document_id, normalized)", [])?;
+        
+        // Valores monetários do texto, em centavos, com moeda e papel (total, desconto, imposto...)
+        let amounts_table_exists: bool = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'document_amounts'",
+            [],
+            |row| Ok(row.get::<_, i64>(0)? > 0)
+        )?;
+        
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS document_amounts (
+                document_id TEXT NOT NULL,
+                amount_index INTEGER NOT NULL,
+                role TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                amount_cents INTEGER NOT NULL,
+                raw TEXT NOT NULL,
+                PRIMARY KEY (document_id, amount_index),
+                FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE
+            )
+        "#, [])?;
+        
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_document_amounts_role ON document_amounts(role, amount_cents)", [])?;
+        
+        // Migration 8: Extrair os valores dos documentos já indexados
+        if !amounts_table_exists {
+            let contents: Vec<(String, String)> = {
+                let mut stmt = conn.prepare("SELECT document_id, extracted_text FROM document_content WHERE extracted_text != ''")?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                rows.collect::<SqliteResult<Vec<_>>>()?
+            };
+            
+            let mut backfilled = 0;
+            for (document_id, extracted_text) in &contents {
+                let amounts = money_parser::find_amounts(extracted_text);
+                Self::insert_document_amounts(&conn, document_id, &amounts)?;
+                backfilled += amounts.len();
+            }
+            log::info!("✅ Migration: {} valores monetários extraídos de {} documentos", backfilled, contents.len());
+        }
+        
+        // Datas com papel (emissão, vencimento, vigência, competência) encontradas no texto
+        let dates_table_exists: bool = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'document_dates'",
+            [],
+            |row| Ok(row.get::<_, i64>(0)? > 0)
+        )?;
+        
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS document_dates (
+                document_id TEXT NOT NULL,
+                date_index INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                date_value TEXT NOT NULL,
+                raw TEXT NOT NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (document_id, date_index),
+                FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE
+            )
+        "#, [])?;
+        
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_document_dates_kind ON document_dates(kind, date_value)", [])?;
+        
+        // Migration 9: Extrair as datas com papel dos documentos já indexados
+        if !dates_table_exists {
+            let contents: Vec<(String, String)> = {
+                let mut stmt = conn.prepare("SELECT document_id, extracted_text FROM document_content WHERE extracted_text != ''")?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                rows.collect::<SqliteResult<Vec<_>>>()?
+            };
+            
+            let mut backfilled = 0;
+            for (document_id, extracted_text) in &contents {
+                let dates = DateExtractor::for_text(extracted_text).extract_typed_dates(extracted_text);
+                Self::insert_document_dates(&conn, document_id, &dates)?;
+                backfilled += dates.len();
+            }
+            log::info!("✅ Migration: {} datas com papel extraídas de {} documentos", backfilled, contents.len());
+        }
+        
+        // Assinatura MinHash do texto, para detectar quase-duplicatas sem reler os textos
+        let signatures_table_exists: bool = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'document_signatures'",
+            [],
+            |row| Ok(row.get::<_, i64>(0)? > 0)
+        )?;
+        
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS document_signatures (
+                document_id TEXT PRIMARY KEY,
+                signature BLOB NOT NULL,
+                FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE
+            )
+        "#, [])?;
+        
+        // Migration 10: Assinaturas dos documentos já indexados
+        if !signatures_table_exists {
+            let contents: Vec<(String, String)> = {
+                let mut stmt = conn.prepare("SELECT document_id, extracted_text FROM document_content WHERE extracted_text != ''")?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                rows.collect::<SqliteResult<Vec<_>>>()?
+            };
+            
+            let mut backfilled = 0;
+            for (document_id, extracted_text) in &contents {
+                let signature = near_duplicate::signature(&near_duplicate::shingles(extracted_text));
+                if !signature.is_empty() {
+                    Self::insert_document_signature(&conn, document_id, &signature)?;
+                    backfilled += 1;
+                }
+            }
+            log::info!("✅ Migration: assinaturas de {} documentos calculadas", backfilled);
+        }
+        
         // TABELA VIRTUAL FTS5 - MOTOR DE BUSCA FULL-TEXT
         // Usando configuração otimizada para performance máxima
         conn.execute(r#"
@@ -346,6 +810,42 @@ impl Database {
         
         log::info!("📊 Schema FTS5 criado com sucesso - busca full-text ativada");
         
+        // HISTÓRICO DE VERSÕES - cada novo upload sobre um documento vira uma versão
+        // O registro em documents sempre aponta para a versão mais recente
+        conn.execute(r#"
+            CREATE TABLE IF NOT EXISTS document_versions (
+                id TEXT PRIMARY KEY,
+                document_id TEXT NOT NULL,
+                version_number INTEGER NOT NULL,
+                file_path TEXT NOT NULL,
+                file_hash TEXT,
+                file_size INTEGER NOT NULL,
+                file_type TEXT NOT NULL,
+                extracted_text TEXT NOT NULL DEFAULT '',
+                ocr_result TEXT,
+                created_at TEXT NOT NULL,
+                UNIQUE (document_id, version_number),
+                FOREIGN KEY (document_id) REFERENCES documents (id) ON DELETE CASCADE
+            )
+        "#, [])?;
+        
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_document_versions_document_id ON document_versions(document_id)", [])?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_document_versions_file_hash ON document_versions(file_hash)", [])?;
+        
+        // Migration 11: Criar a versão 1 dos documentos existentes
+        let backfilled = conn.execute(r#"
+            INSERT INTO document_versions 
+                (id, document_id, version_number, file_path, file_hash, file_size, file_type, extracted_text, ocr_result, created_at)
+            SELECT d.id || '-v1', d.id, 1, d.file_path, d.file_hash, d.file_size, d.file_type, 
+                   COALESCE(dc.extracted_text, ''), NULL, d.created_at
+            FROM documents d
+            LEFT JOIN document_content dc ON dc.document_id = d.id
+            WHERE NOT EXISTS (SELECT 1 FROM document_versions v WHERE v.document_id = d.id)
+        "#, [])?;
+        if backfilled > 0 {
+            log::info!("✅ Migration: versão inicial criada para {} documentos", backfilled);
+        }
+        
         // INICIALIZAR CONFIGURAÇÃO FTS5 (se necessário)
         // Rebuild do índice FTS5 caso exista conteúdo sem indexação
         let rebuild_result = conn.execute("INSERT INTO documents_fts(documents_fts) VALUES('rebuild')", []);
@@ -429,25 +929,7 @@ impl Database {
                 "SELECT id, username, email, password_hash, created_at, last_login FROM users WHERE username = ?1"
             )?;
             
-            let user_iter = stmt.query_map([username], |row| {
-                let created_at_str: String = row.get(4)?;
-                let last_login_str: Option<String> = row.get(5)?;
-                
-                Ok(User {
-                    id: row.get(0)?,
-                    username: row.get(1)?,
-                    email: row.get(2)?,
-                    password_hash: row.get(3)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_at_str)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(4, "created_at".to_string(), rusqlite::types::Type::Text))?
-                        .with_timezone(&Utc),
-                    last_login: last_login_str.map(|s| {
-                        DateTime::parse_from_rfc3339(&s)
-                            .map(|dt| dt.with_timezone(&Utc))
-                            .unwrap_or_else(|_| Utc::now())
-                    }),
-                })
-            })?;
+            let user_iter = stmt.query_map([username], Self::user_from_row)?;
             
             for user in user_iter {
                 return Ok(Some(user?));
@@ -457,13 +939,44 @@ impl Database {
         })
     }
     
+    pub fn get_user_by_id(&self, user_id: &str) -> SqliteResult<Option<User>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, username, email, password_hash, created_at, last_login FROM users WHERE id = ?1"
+            )?;
+            
+            let mut user_iter = stmt.query_map([user_id], Self::user_from_row)?;
+            user_iter.next().transpose()
+        })
+    }
+    
+    fn user_from_row(row: &rusqlite::Row) -> SqliteResult<User> {
+        let created_at_str: String = row.get(4)?;
+        let last_login_str: Option<String> = row.get(5)?;
+        
+        Ok(User {
+            id: row.get(0)?,
+            username: row.get(1)?,
+            email: row.get(2)?,
+            password_hash: row.get(3)?,
+            created_at: DateTime::parse_from_rfc3339(&created_at_str)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(4, "created_at".to_string(), rusqlite::types::Type::Text))?
+                .with_timezone(&Utc),
+            last_login: last_login_str.map(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now())
+            }),
+        })
+    }
+    
     pub fn create_document(&self, document: &Document) -> SqliteResult<()> {
         self.execute_with_retry(|conn| {
             let tags_json = serde_json::to_string(&document.tags)
                 .map_err(|_| rusqlite::Error::ToSqlConversionFailure(Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to serialize tags"))))?;
                 
             conn.execute(
-                "INSERT INTO documents (id, user_id, name, file_path, file_type, file_size, created_at, updated_at, tags, document_date, folder_slug) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                "INSERT INTO documents (id, user_id, name, file_path, file_type, file_size, created_at, updated_at, tags, document_date, folder_slug, original_path, file_hash, parent_document_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
                 params![
                     document.id,
                     document.user_id,
@@ -475,44 +988,29 @@ impl Database {
                     document.updated_at.to_rfc3339(),
                     tags_json,
                     document.document_date,
-                    document.folder_slug
+                    document.folder_slug,
+                    document.original_path,
+                    document.file_hash,
+                    document.parent_document_id
                 ]
             )?;
             Ok(())
-        })
+        })?;
+        
+        if !document.tags.is_empty() {
+            self.set_document_tags(&document.user_id, &document.id, &document.tags)?;
+        }
+        Ok(())
     }
     
     pub fn get_documents_by_user(&self, user_id: &str) -> SqliteResult<Vec<Document>> {
         self.execute_with_retry(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, user_id, name, file_path, file_type, file_size, created_at, updated_at, tags, document_date, folder_slug FROM documents WHERE user_id = ?1 ORDER BY created_at DESC"
-            )?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM documents WHERE user_id = ?1 AND deleted_at IS NULL ORDER BY created_at DESC",
+                DOCUMENT_COLUMNS
+            ))?;
             
-            let document_iter = stmt.query_map([user_id], |row| {
-                let created_at_str: String = row.get(6)?;
-                let updated_at_str: String = row.get(7)?;
-                let tags_json: String = row.get(8)?;
-                
-                let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-                
-                Ok(Document {
-                    id: row.get(0)?,
-                    user_id: row.get(1)?,
-                    name: row.get(2)?,
-                    file_path: row.get(3)?,
-                    file_type: row.get(4)?,
-                    file_size: row.get(5)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_at_str)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(6, "created_at".to_string(), rusqlite::types::Type::Text))?
-                        .with_timezone(&Utc),
-                    updated_at: DateTime::parse_from_rfc3339(&updated_at_str)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(7, "updated_at".to_string(), rusqlite::types::Type::Text))?
-                        .with_timezone(&Utc),
-                    tags,
-                    document_date: row.get(9)?,
-                    folder_slug: row.get(10)?,
-                })
-            })?;
+            let document_iter = stmt.query_map([user_id], document_from_row)?;
             
             let mut documents = Vec::new();
             for document in document_iter {
@@ -544,7 +1042,7 @@ impl Database {
     pub fn get_user_stats(&self, user_id: &str) -> SqliteResult<(i64, i64, i64)> {
         self.execute_with_retry(|conn| {
             // Total de documentos
-            let mut stmt = conn.prepare("SELECT COUNT(*) FROM documents WHERE user_id = ?1")?;
+            let mut stmt = conn.prepare("SELECT COUNT(*) FROM documents WHERE user_id = ?1 AND deleted_at IS NULL")?;
             let document_count: i64 = stmt.query_row([user_id], |row| row.get(0))?;
             
             // Total de atividades
@@ -552,7 +1050,7 @@ impl Database {
             let activity_count: i64 = stmt.query_row([user_id], |row| row.get(0))?;
             
             // Tamanho total dos arquivos
-            let mut stmt = conn.prepare("SELECT COALESCE(SUM(file_size), 0) FROM documents WHERE user_id = ?1")?;
+            let mut stmt = conn.prepare("SELECT COALESCE(SUM(file_size), 0) FROM documents WHERE user_id = ?1 AND deleted_at IS NULL")?;
             let total_size: i64 = stmt.query_row([user_id], |row| row.get(0))?;
             
             Ok((document_count, activity_count, total_size))
@@ -938,11 +1436,14 @@ impl Database {
             let fields_json = extracted_fields.to_string();
             let indexed_at = Utc::now().to_rfc3339();
             
-            // Inserir ou atualizar conteúdo
+            // Inserir ou atualizar conteúdo; um tipo corrigido pelo usuário é mantido
             conn.execute(
                 r#"INSERT OR REPLACE INTO document_content 
-                   (document_id, extracted_text, document_type, extracted_fields, indexed_at) 
-                   VALUES (?1, ?2, ?3, ?4, ?5)"#,
+                   (document_id, extracted_text, document_type, extracted_fields, indexed_at, type_corrected) 
+                   VALUES (?1, ?2,
+                           COALESCE((SELECT document_type FROM document_content WHERE document_id = ?1 AND type_corrected = 1), ?3),
+                           ?4, ?5,
+                           COALESCE((SELECT type_corrected FROM document_content WHERE document_id = ?1), 0))"#,
                 params![document_id, extracted_text, document_type, fields_json, indexed_at]
             )?;
             
@@ -952,51 +1453,335 @@ impl Database {
         })
     }
     
-    // Busca full-text nos documentos
-    pub fn search_documents(
-        &self,
-        user_id: &str,
-        query: &str,
-        limit: Option<usize>,
-    ) -> SqliteResult<Vec<SearchResult>> {
+    // Substituir as palavras com posição (OCR) de um documento
+    pub fn save_document_words(&self, document_id: &str, words: &[WordBox]) -> SqliteResult<()> {
         self.execute_with_retry(|conn| {
-            let limit_clause = limit.map(|l| format!(" LIMIT {}", l)).unwrap_or(" LIMIT 50".to_string());
-            
-            // Query FTS5 com ranking
-            let search_query = format!(
-                r#"SELECT 
-                    fts.document_id,
-                    d.name as document_name,
-                    fts.document_type,
-                    d.file_path,
-                    bm25(fts) as relevance_score,
-                    snippet(fts, 2, '<mark>', '</mark>', '...', 64) as matched_content,
-                    d.created_at
-                   FROM documents_fts fts
-                   JOIN documents d ON d.id = fts.document_id
-                   WHERE d.user_id = ?1 AND fts MATCH ?2
-                   ORDER BY relevance_score ASC{}"#,
-                limit_clause
-            );
-            
-            let mut stmt = conn.prepare(&search_query)?;
-            let search_iter = stmt.query_map([user_id, query], |row| {
-                let created_at_str: String = row.get(6)?;
-                Ok(SearchResult {
-                    document_id: row.get(0)?,
-                    document_name: row.get(1)?,
-                    document_type: row.get(2)?,
-                    file_path: row.get(3)?,
-                    relevance_score: row.get::<_, f64>(4)? as f32,
-                    matched_content: row.get(5)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_at_str)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(6, "created_at".to_string(), rusqlite::types::Type::Text))?
-                        .with_timezone(&Utc),
-                })
-            })?;
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM document_words WHERE document_id = ?1", [document_id])?;
+            
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT OR REPLACE INTO document_words 
+                     (document_id, page_number, word_index, text, normalized, confidence, 
+                      box_left, box_top, box_width, box_height, page_width, page_height) 
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"
+                )?;
+                for (index, word) in words.iter().enumerate() {
+                    stmt.execute(params![
+                        document_id,
+                        word.page_number,
+                        index as i64,
+                        word.text,
+                        fold_search_term(&word.text),
+                        word.confidence,
+                        word.left,
+                        word.top,
+                        word.width,
+                        word.height,
+                        word.page_width,
+                        word.page_height,
+                    ])?;
+                }
+            }
             
-            let mut results = Vec::new();
-            for result in search_iter {
+            tx.commit()?;
+            log::debug!("🔤 {} palavras com posição salvas para documento {}", words.len(), document_id);
+            Ok(())
+        })
+    }
+    
+    // Substituir os valores monetários de um documento
+    pub fn save_document_amounts(&self, document_id: &str, amounts: &[MonetaryAmount]) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM document_amounts WHERE document_id = ?1", [document_id])?;
+            Self::insert_document_amounts(&tx, document_id, amounts)?;
+            tx.commit()?;
+            log::debug!("💰 {} valores monetários salvos para documento {}", amounts.len(), document_id);
+            Ok(())
+        })
+    }
+    
+    fn insert_document_amounts(conn: &Connection, document_id: &str, amounts: &[MonetaryAmount]) -> SqliteResult<()> {
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO document_amounts 
+             (document_id, amount_index, role, currency, amount_cents, raw) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        )?;
+        for (index, amount) in amounts.iter().enumerate() {
+            stmt.execute(params![
+                document_id,
+                index as i64,
+                amount.role,
+                amount.currency,
+                amount.amount_cents,
+                amount.raw,
+            ])?;
+        }
+        Ok(())
+    }
+    
+    // Substituir as datas com papel de um documento
+    pub fn save_document_dates(&self, document_id: &str, dates: &[TypedDate]) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM document_dates WHERE document_id = ?1", [document_id])?;
+            Self::insert_document_dates(&tx, document_id, dates)?;
+            tx.commit()?;
+            log::debug!("📅 {} datas com papel salvas para documento {}", dates.len(), document_id);
+            Ok(())
+        })
+    }
+    
+    fn insert_document_dates(conn: &Connection, document_id: &str, dates: &[TypedDate]) -> SqliteResult<()> {
+        let mut stmt = conn.prepare(
+            "INSERT OR REPLACE INTO document_dates (document_id, date_index, kind, date_value, raw, position) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+        )?;
+        for (index, date) in dates.iter().enumerate() {
+            stmt.execute(params![
+                document_id,
+                index as i64,
+                date.kind.as_str(),
+                date.value.format("%Y-%m-%d").to_string(),
+                date.raw,
+                date.position as i64,
+            ])?;
+        }
+        Ok(())
+    }
+    
+    // Datas com papel de um documento do usuário, na ordem do texto
+    pub fn get_document_dates(&self, user_id: &str, document_id: &str) -> SqliteResult<Vec<TypedDate>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT dd.kind, dd.date_value, dd.raw, dd.position FROM document_dates dd 
+                 JOIN documents d ON d.id = dd.document_id 
+                 WHERE d.user_id = ?1 AND d.id = ?2 
+                 ORDER BY dd.date_index"
+            )?;
+            
+            let rows = stmt.query_map(params![user_id, document_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?))
+            })?;
+            
+            let mut dates = Vec::new();
+            for row in rows {
+                let (kind, value, raw, position) = row?;
+                // Linhas com papel ou data que não reconhecemos mais são ignoradas
+                if let (Some(kind), Ok(value)) = (DateKind::parse(&kind), chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d")) {
+                    dates.push(TypedDate { kind, value, raw, position: position as usize });
+                }
+            }
+            Ok(dates)
+        })
+    }
+    
+    // Cláusula WHERE (após o JOIN com documents `d` e document_amounts `a`) e parâmetros do filtro
+    fn amount_filter_clause(user_id: &str, filter: &AmountFilter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut clause = "d.user_id = ? AND d.deleted_at IS NULL".to_string();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(user_id.to_string())];
+        
+        if let Some(min) = filter.min_cents {
+            clause.push_str(" AND a.amount_cents >= ?");
+            params.push(Box::new(min));
+        }
+        if let Some(max) = filter.max_cents {
+            clause.push_str(" AND a.amount_cents <= ?");
+            params.push(Box::new(max));
+        }
+        if let Some(ref role) = filter.role {
+            clause.push_str(" AND a.role = ?");
+            params.push(Box::new(role.clone()));
+        }
+        if let Some(ref currency) = filter.currency {
+            clause.push_str(" AND a.currency = ?");
+            params.push(Box::new(currency.to_uppercase()));
+        }
+        if let Some(ref start) = filter.start_date {
+            clause.push_str(" AND COALESCE(d.document_date, substr(d.created_at, 1, 10)) >= ?");
+            params.push(Box::new(start.clone()));
+        }
+        if let Some(ref end) = filter.end_date {
+            clause.push_str(" AND COALESCE(d.document_date, substr(d.created_at, 1, 10)) <= ?");
+            params.push(Box::new(end.clone()));
+        }
+        (clause, params)
+    }
+    
+    // Valores monetários dentro da faixa/papel/moeda/período, do maior para o menor
+    pub fn find_documents_by_amount(&self, user_id: &str, filter: &AmountFilter) -> SqliteResult<Vec<AmountMatch>> {
+        self.execute_with_retry(|conn| {
+            let (clause, params) = Self::amount_filter_clause(user_id, filter);
+            let query = format!(
+                "SELECT d.id, d.name, d.document_date, a.role, a.currency, a.amount_cents, a.raw 
+                 FROM document_amounts a JOIN documents d ON d.id = a.document_id 
+                 WHERE {} 
+                 ORDER BY a.amount_cents DESC, d.created_at DESC",
+                clause
+            );
+            
+            let mut stmt = conn.prepare(&query)?;
+            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            let match_iter = stmt.query_map(&param_refs[..], |row| {
+                let amount_cents: i64 = row.get(5)?;
+                Ok(AmountMatch {
+                    document_id: row.get(0)?,
+                    document_name: row.get(1)?,
+                    document_date: row.get(2)?,
+                    role: row.get(3)?,
+                    currency: row.get(4)?,
+                    amount_cents,
+                    amount: money_parser::format_cents(amount_cents),
+                    raw: row.get(6)?,
+                })
+            })?;
+            
+            let matches: Vec<AmountMatch> = match_iter.collect::<SqliteResult<_>>()?;
+            log::debug!("💰 {} valores encontrados para usuário {}", matches.len(), user_id);
+            Ok(matches)
+        })
+    }
+    
+    // Soma dos valores agrupada por "month", "year", "role" ou "document_type", por moeda.
+    // Cada documento conta uma vez por papel e moeda (o maior valor), para não somar
+    // repetições do mesmo total no texto.
+    pub fn get_amount_totals(&self, user_id: &str, filter: &AmountFilter, group_by: &str) -> SqliteResult<Vec<AmountTotal>> {
+        let group_expr = match group_by {
+            "month" => "substr(COALESCE(d.document_date, d.created_at), 1, 7)",
+            "year" => "substr(COALESCE(d.document_date, d.created_at), 1, 4)",
+            "role" => "a.role",
+            "document_type" => "COALESCE(dc.document_type, 'generic')",
+            other => return Err(rusqlite::Error::InvalidParameterName(other.to_string())),
+        };
+        
+        self.execute_with_retry(|conn| {
+            let (clause, params) = Self::amount_filter_clause(user_id, filter);
+            let query = format!(
+                "SELECT grp, currency, COUNT(*), SUM(amount_cents) FROM (
+                     SELECT {} AS grp, a.currency AS currency, MAX(a.amount_cents) AS amount_cents 
+                     FROM document_amounts a JOIN documents d ON d.id = a.document_id 
+                     LEFT JOIN document_content dc ON dc.document_id = d.id 
+                     WHERE {} 
+                     GROUP BY d.id, a.role, a.currency
+                 ) 
+                 GROUP BY grp, currency 
+                 ORDER BY grp, currency",
+                group_expr, clause
+            );
+            
+            let mut stmt = conn.prepare(&query)?;
+            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            let total_iter = stmt.query_map(&param_refs[..], |row| {
+                let total_cents: i64 = row.get(3)?;
+                Ok(AmountTotal {
+                    group: row.get::<_, Option<String>>(0)?.unwrap_or_default(),
+                    currency: row.get(1)?,
+                    count: row.get(2)?,
+                    total_cents,
+                    total: money_parser::format_cents(total_cents),
+                })
+            })?;
+            total_iter.collect()
+        })
+    }
+    
+    // Palavras do documento que correspondem aos termos buscados (igual ou prefixo,
+    // como no FTS5), em ordem de página e leitura
+    pub fn find_word_boxes(&self, document_id: &str, terms: &[String]) -> SqliteResult<Vec<WordBox>> {
+        let terms: Vec<String> = terms.iter()
+            .map(|term| fold_search_term(term))
+            .filter(|term| !term.is_empty())
+            .collect();
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+        
+        self.execute_with_retry(|conn| {
+            let conditions = (0..terms.len())
+                .map(|i| format!("normalized LIKE ?{} || '%'", i + 2))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            let mut stmt = conn.prepare(&format!(
+                "SELECT page_number, text, confidence, box_left, box_top, box_width, box_height, page_width, page_height 
+                 FROM document_words WHERE document_id = ?1 AND ({}) 
+                 ORDER BY page_number, word_index LIMIT 200",
+                conditions
+            ))?;
+            
+            let mut values: Vec<&dyn rusqlite::ToSql> = vec![&document_id];
+            values.extend(terms.iter().map(|term| term as &dyn rusqlite::ToSql));
+            let word_iter = stmt.query_map(values.as_slice(), |row| {
+                Ok(WordBox {
+                    page_number: row.get(0)?,
+                    text: row.get(1)?,
+                    confidence: row.get(2)?,
+                    left: row.get(3)?,
+                    top: row.get(4)?,
+                    width: row.get(5)?,
+                    height: row.get(6)?,
+                    page_width: row.get(7)?,
+                    page_height: row.get(8)?,
+                })
+            })?;
+            word_iter.collect()
+        })
+    }
+    
+    // Busca full-text nos documentos
+    pub fn search_documents(
+        &self,
+        user_id: &str,
+        query: &str,
+        limit: Option<usize>,
+        date_range: Option<&DateRangeFilter>,
+        tag_filter: Option<&TagFilter>,
+    ) -> SqliteResult<Vec<SearchResult>> {
+        self.execute_with_retry(|conn| {
+            let limit_clause = limit.map(|l| format!(" LIMIT {}", l)).unwrap_or(" LIMIT 50".to_string());
+            let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(user_id.to_string()), Box::new(query.to_string())];
+            let mut date_clause = date_range.map(|range| Self::date_range_clause(range, &mut params)).unwrap_or_default();
+            if let Some(filter) = tag_filter {
+                date_clause.push_str(&Self::tag_filter_clause(user_id, filter, &mut params));
+            }
+            
+            // Query FTS5 com ranking
+            let search_query = format!(
+                r#"SELECT 
+                    fts.document_id,
+                    d.name as document_name,
+                    fts.document_type,
+                    d.file_path,
+                    bm25(fts) as relevance_score,
+                    snippet(fts, 2, '<mark>', '</mark>', '...', 64) as matched_content,
+                    d.created_at
+                   FROM documents_fts fts
+                   JOIN documents d ON d.id = fts.document_id
+                   WHERE d.user_id = ? AND d.deleted_at IS NULL AND fts MATCH ?{}
+                   ORDER BY relevance_score ASC{}"#,
+                date_clause,
+                limit_clause
+            );
+            
+            let mut stmt = conn.prepare(&search_query)?;
+            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            let search_iter = stmt.query_map(&param_refs[..], |row| {
+                let created_at_str: String = row.get(6)?;
+                Ok(SearchResult {
+                    document_id: row.get(0)?,
+                    document_name: row.get(1)?,
+                    document_type: row.get(2)?,
+                    file_path: row.get(3)?,
+                    relevance_score: row.get::<_, f64>(4)? as f32,
+                    matched_content: row.get(5)?,
+                    created_at: DateTime::parse_from_rfc3339(&created_at_str)
+                        .map_err(|_| rusqlite::Error::InvalidColumnType(6, "created_at".to_string(), rusqlite::types::Type::Text))?
+                        .with_timezone(&Utc),
+                })
+            })?;
+            
+            let mut results = Vec::new();
+            for result in search_iter {
                 results.push(result?);
             }
             
@@ -1012,9 +1797,17 @@ impl Database {
         user_id: &str,
         query: &str,
         limit: Option<usize>,
+        date_range: Option<&DateRangeFilter>,
+        tag_filter: Option<&TagFilter>,
     ) -> SqliteResult<Vec<SearchResult>> {
         self.execute_with_retry(|conn| {
             let limit_clause = limit.map(|l| format!(" LIMIT {}", l)).unwrap_or(" LIMIT 50".to_string());
+            let like_query = format!("%{}%", query);
+            let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(user_id.to_string()), Box::new(like_query)];
+            let mut date_clause = date_range.map(|range| Self::date_range_clause(range, &mut params)).unwrap_or_default();
+            if let Some(filter) = tag_filter {
+                date_clause.push_str(&Self::tag_filter_clause(user_id, filter, &mut params));
+            }
             
             // Query simples com LIKE
             let search_query = format!(
@@ -1028,15 +1821,16 @@ impl Database {
                     d.created_at
                    FROM documents d
                    LEFT JOIN document_content dc ON dc.document_id = d.id
-                   WHERE d.user_id = ?1 
-                   AND (d.name LIKE ?2 OR dc.extracted_text LIKE ?2 OR dc.extracted_fields LIKE ?2)
+                   WHERE d.user_id = ?1 AND d.deleted_at IS NULL
+                   AND (d.name LIKE ?2 OR dc.extracted_text LIKE ?2 OR dc.extracted_fields LIKE ?2){}
                    ORDER BY d.created_at DESC{}"#,
+                date_clause,
                 limit_clause
             );
             
-            let like_query = format!("%{}%", query);
             let mut stmt = conn.prepare(&search_query)?;
-            let search_iter = stmt.query_map([user_id, &like_query], |row| {
+            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            let search_iter = stmt.query_map(&param_refs[..], |row| {
                 let created_at_str: String = row.get(6)?;
                 Ok(SearchResult {
                     document_id: row.get(0)?,
@@ -1064,14 +1858,14 @@ impl Database {
     pub fn get_search_stats(&self, user_id: &str) -> SqliteResult<(i64, i64)> {
         self.execute_with_retry(|conn| {
             // Total de documentos do usuário
-            let mut stmt = conn.prepare("SELECT COUNT(*) FROM documents WHERE user_id = ?1")?;
+            let mut stmt = conn.prepare("SELECT COUNT(*) FROM documents WHERE user_id = ?1 AND deleted_at IS NULL")?;
             let total_docs: i64 = stmt.query_row([user_id], |row| row.get(0))?;
             
             // Documentos indexados
             let mut stmt = conn.prepare(
                 "SELECT COUNT(*) FROM document_content dc 
                  JOIN documents d ON d.id = dc.document_id 
-                 WHERE d.user_id = ?1"
+                 WHERE d.user_id = ?1 AND d.deleted_at IS NULL"
             )?;
             let indexed_docs: i64 = stmt.query_row([user_id], |row| row.get(0)).unwrap_or(0);
             
@@ -1094,38 +1888,15 @@ impl Database {
     
     pub fn get_documents_by_folder(&self, user_id: &str, folder_slug: &str) -> SqliteResult<Vec<Document>> {
         self.execute_with_retry(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, user_id, name, file_path, file_type, file_size, created_at, updated_at, tags, document_date, folder_slug 
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} 
                  FROM documents 
-                 WHERE user_id = ?1 AND folder_slug = ?2 
-                 ORDER BY document_date DESC, created_at DESC"
-            )?;
+                 WHERE user_id = ?1 AND folder_slug = ?2 AND deleted_at IS NULL 
+                 ORDER BY document_date DESC, created_at DESC",
+                DOCUMENT_COLUMNS
+            ))?;
             
-            let document_iter = stmt.query_map(params![user_id, folder_slug], |row| {
-                let created_at_str: String = row.get(6)?;
-                let updated_at_str: String = row.get(7)?;
-                let tags_json: String = row.get(8)?;
-                
-                let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-                
-                Ok(Document {
-                    id: row.get(0)?,
-                    user_id: row.get(1)?,
-                    name: row.get(2)?,
-                    file_path: row.get(3)?,
-                    file_type: row.get(4)?,
-                    file_size: row.get(5)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_at_str)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(6, "created_at".to_string(), rusqlite::types::Type::Text))?
-                        .with_timezone(&Utc),
-                    updated_at: DateTime::parse_from_rfc3339(&updated_at_str)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(7, "updated_at".to_string(), rusqlite::types::Type::Text))?
-                        .with_timezone(&Utc),
-                    tags,
-                    document_date: row.get(9)?,
-                    folder_slug: row.get(10)?,
-                })
-            })?;
+            let document_iter = stmt.query_map(params![user_id, folder_slug], document_from_row)?;
             
             let mut documents = Vec::new();
             for document in document_iter {
@@ -1142,7 +1913,7 @@ impl Database {
             let mut stmt = conn.prepare(
                 "SELECT folder_slug, COUNT(*) as doc_count 
                  FROM documents 
-                 WHERE user_id = ?1 AND folder_slug IS NOT NULL 
+                 WHERE user_id = ?1 AND folder_slug IS NOT NULL AND deleted_at IS NULL 
                  GROUP BY folder_slug 
                  ORDER BY folder_slug DESC"
             )?;
@@ -1164,45 +1935,75 @@ impl Database {
         })
     }
     
+    // Condição extra (documentos `d`) e parâmetros do intervalo de datas da busca por texto
+    fn date_range_clause(range: &DateRangeFilter, params: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+        let clause = match range.kind {
+            None => " AND d.document_date >= ? AND d.document_date <= ?".to_string(),
+            Some(kind) => {
+                params.push(Box::new(kind.as_str()));
+                " AND d.id IN (SELECT document_id FROM document_dates WHERE kind = ? AND date_value >= ? AND date_value <= ?)".to_string()
+            }
+        };
+        params.push(Box::new(range.start_date.clone()));
+        params.push(Box::new(range.end_date.clone()));
+        clause
+    }
+    
+    // Condição extra (documentos `d`) e parâmetros do filtro por tags, aplicada antes do LIMIT
+    fn tag_filter_clause(user_id: &str, filter: &TagFilter, params: &mut Vec<Box<dyn rusqlite::ToSql>>) -> String {
+        params.push(Box::new(user_id.to_string()));
+        for tag in &filter.tags {
+            params.push(Box::new(tag.clone()));
+        }
+        format!(
+            " AND d.id IN (SELECT dt.document_id FROM document_tags dt JOIN tags t ON t.id = dt.tag_id 
+              WHERE t.user_id = ? AND t.name IN ({}) 
+              GROUP BY dt.document_id HAVING COUNT(DISTINCT t.id) >= {})",
+            vec!["?"; filter.tags.len()].join(", "),
+            if filter.match_all { filter.tags.len() } else { 1 }
+        )
+    }
+    
+    // Sem `date_kind`, filtra pela data principal (document_date); com ele, por uma das
+    // datas com papel do documento (ex.: vencimento)
     pub fn get_documents_by_date_range(
         &self, 
         user_id: &str, 
         start_date: &str, 
-        end_date: &str
+        end_date: &str,
+        date_kind: Option<DateKind>,
+        tag_filter: Option<&TagFilter>,
     ) -> SqliteResult<Vec<Document>> {
         self.execute_with_retry(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, user_id, name, file_path, file_type, file_size, created_at, updated_at, tags, document_date, folder_slug 
-                 FROM documents 
-                 WHERE user_id = ?1 AND document_date >= ?2 AND document_date <= ?3 
-                 ORDER BY document_date DESC, created_at DESC"
-            )?;
+            let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![
+                Box::new(user_id.to_string()),
+                Box::new(start_date.to_string()),
+                Box::new(end_date.to_string()),
+            ];
+            let date_condition = match date_kind {
+                None => "document_date >= ?2 AND document_date <= ?3".to_string(),
+                Some(kind) => {
+                    params.push(Box::new(kind.as_str()));
+                    "id IN (
+                         SELECT document_id FROM document_dates 
+                         WHERE kind = ?4 AND date_value >= ?2 AND date_value <= ?3
+                     )".to_string()
+                }
+            };
+            let tag_clause = tag_filter.map(|filter| Self::tag_filter_clause(user_id, filter, &mut params)).unwrap_or_default();
+            let query = format!(
+                "SELECT {} 
+                 FROM documents d 
+                 WHERE user_id = ?1 AND deleted_at IS NULL AND {}{} 
+                 ORDER BY document_date DESC, created_at DESC",
+                DOCUMENT_COLUMNS,
+                date_condition,
+                tag_clause
+            );
+            let mut stmt = conn.prepare(&query)?;
             
-            let document_iter = stmt.query_map(params![user_id, start_date, end_date], |row| {
-                let created_at_str: String = row.get(6)?;
-                let updated_at_str: String = row.get(7)?;
-                let tags_json: String = row.get(8)?;
-                
-                let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
-                
-                Ok(Document {
-                    id: row.get(0)?,
-                    user_id: row.get(1)?,
-                    name: row.get(2)?,
-                    file_path: row.get(3)?,
-                    file_type: row.get(4)?,
-                    file_size: row.get(5)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_at_str)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(6, "created_at".to_string(), rusqlite::types::Type::Text))?
-                        .with_timezone(&Utc),
-                    updated_at: DateTime::parse_from_rfc3339(&updated_at_str)
-                        .map_err(|_| rusqlite::Error::InvalidColumnType(7, "updated_at".to_string(), rusqlite::types::Type::Text))?
-                        .with_timezone(&Utc),
-                    tags,
-                    document_date: row.get(9)?,
-                    folder_slug: row.get(10)?,
-                })
-            })?;
+            let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+            let document_iter = stmt.query_map(&param_refs[..], document_from_row)?;
             
             let mut documents = Vec::new();
             for document in document_iter {
@@ -1213,4 +2014,991 @@ impl Database {
             Ok(documents)
         })
     }
-}
+    
+    // Atualizar metadados editáveis do documento (nome, tags, data e pasta)
+    pub fn update_document(&self, document: &Document) -> SqliteResult<bool> {
+        let updated = self.execute_with_retry(|conn| {
+            let affected = conn.execute(
+                "UPDATE documents SET name = ?1, document_date = ?2, folder_slug = ?3, updated_at = ?4 
+                 WHERE user_id = ?5 AND id = ?6 AND deleted_at IS NULL",
+                params![
+                    document.name,
+                    document.document_date,
+                    document.folder_slug,
+                    document.updated_at.to_rfc3339(),
+                    document.user_id,
+                    document.id
+                ]
+            )?;
+            Ok(affected > 0)
+        })?;
+        
+        if updated {
+            self.set_document_tags(&document.user_id, &document.id, &document.tags)?;
+        }
+        Ok(updated)
+    }
+    
+    // ==================================================================================
+    // LIXEIRA - EXCLUSÃO REVERSÍVEL, RESTAURAÇÃO E EXPURGO
+    // ==================================================================================
+    
+    // Buscar documento por ID (inclui documentos na lixeira)
+    pub fn get_document_by_id(&self, user_id: &str, document_id: &str) -> SqliteResult<Option<Document>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM documents WHERE user_id = ?1 AND id = ?2",
+                DOCUMENT_COLUMNS
+            ))?;
+            
+            match stmt.query_row(params![user_id, document_id], document_from_row) {
+                Ok(document) => Ok(Some(document)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e),
+            }
+        })
+    }
+    
+    // Mover documento para a lixeira (retorna false se não encontrado ou já excluído)
+    pub fn soft_delete_document(&self, user_id: &str, document_id: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let affected = conn.execute(
+                "UPDATE documents SET deleted_at = ?1 WHERE user_id = ?2 AND id = ?3 AND deleted_at IS NULL",
+                params![Utc::now().to_rfc3339(), user_id, document_id]
+            )?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // Restaurar documento da lixeira
+    pub fn restore_document(&self, user_id: &str, document_id: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let affected = conn.execute(
+                "UPDATE documents SET deleted_at = NULL WHERE user_id = ?1 AND id = ?2 AND deleted_at IS NOT NULL",
+                params![user_id, document_id]
+            )?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // Listar documentos na lixeira (mais recentes primeiro)
+    pub fn list_trash(&self, user_id: &str) -> SqliteResult<Vec<Document>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM documents WHERE user_id = ?1 AND deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+                DOCUMENT_COLUMNS
+            ))?;
+            
+            let document_iter = stmt.query_map([user_id], document_from_row)?;
+            
+            let mut documents = Vec::new();
+            for document in document_iter {
+                documents.push(document?);
+            }
+            
+            Ok(documents)
+        })
+    }
+    
+    // Documentos na lixeira excluídos antes do limite informado
+    pub fn get_expired_trash(&self, user_id: &str, deleted_before: DateTime<Utc>) -> SqliteResult<Vec<Document>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM documents WHERE user_id = ?1 AND deleted_at IS NOT NULL AND deleted_at < ?2",
+                DOCUMENT_COLUMNS
+            ))?;
+            
+            let document_iter = stmt.query_map(params![user_id, deleted_before.to_rfc3339()], document_from_row)?;
+            
+            let mut documents = Vec::new();
+            for document in document_iter {
+                documents.push(document?);
+            }
+            
+            Ok(documents)
+        })
+    }
+    
+    // Expurgar definitivamente um documento da lixeira, incluindo conteúdo indexado.
+    // Só remove documentos que já estão na lixeira.
+    pub fn purge_document(&self, user_id: &str, document_id: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            
+            let in_trash: bool = tx.query_row(
+                "SELECT COUNT(*) FROM documents WHERE user_id = ?1 AND id = ?2 AND deleted_at IS NOT NULL",
+                params![user_id, document_id],
+                |row| Ok(row.get::<_, i64>(0)? > 0)
+            )?;
+            
+            if !in_trash {
+                return Ok(false);
+            }
+            
+            // Remover conteúdo extraído (trigger documents_fts_delete limpa o FTS5)
+            tx.execute("DELETE FROM document_content WHERE document_id = ?1", [document_id])?;
+            tx.execute("DELETE FROM document_words WHERE document_id = ?1", [document_id])?;
+            tx.execute("DELETE FROM document_amounts WHERE document_id = ?1", [document_id])?;
+            tx.execute("DELETE FROM document_dates WHERE document_id = ?1", [document_id])?;
+            tx.execute("DELETE FROM document_signatures WHERE document_id = ?1", [document_id])?;
+            tx.execute("DELETE FROM document_tags WHERE document_id = ?1", [document_id])?;
+            tx.execute("DELETE FROM document_versions WHERE document_id = ?1", [document_id])?;
+            // Anexos continuam existindo, sem o vínculo com o documento expurgado
+            tx.execute("UPDATE documents SET parent_document_id = NULL WHERE parent_document_id = ?1", [document_id])?;
+            // Garantir que não sobrou entrada órfã no índice FTS5
+            tx.execute("DELETE FROM documents_fts WHERE document_id = ?1", [document_id])?;
+            tx.execute("DELETE FROM documents WHERE user_id = ?1 AND id = ?2", params![user_id, document_id])?;
+            
+            tx.commit()?;
+            
+            log::info!("🗑️ Documento {} expurgado definitivamente", document_id);
+            Ok(true)
+        })
+    }
+    
+    // Quantos documentos (incluindo lixeira) ou versões ainda usam o arquivo com este hash
+    pub fn count_documents_with_hash(&self, user_id: &str, file_hash: &str) -> SqliteResult<i64> {
+        self.execute_with_retry(|conn| {
+            conn.query_row(
+                "SELECT 
+                    (SELECT COUNT(*) FROM documents WHERE user_id = ?1 AND file_hash = ?2) + 
+                    (SELECT COUNT(*) FROM document_versions v JOIN documents d ON d.id = v.document_id 
+                     WHERE d.user_id = ?1 AND v.file_hash = ?2)",
+                params![user_id, file_hash],
+                |row| row.get(0)
+            )
+        })
+    }
+    
+    // Documento ativo (fora da lixeira) com o mesmo conteúdo, se houver
+    pub fn find_active_document_by_hash(&self, user_id: &str, file_hash: &str) -> SqliteResult<Option<Document>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM documents WHERE user_id = ?1 AND file_hash = ?2 AND deleted_at IS NULL 
+                 ORDER BY created_at ASC LIMIT 1",
+                DOCUMENT_COLUMNS
+            ))?;
+            
+            let mut document_iter = stmt.query_map([user_id, file_hash], document_from_row)?;
+            document_iter.next().transpose()
+        })
+    }
+    
+    // Registrar a origem de um documento extraído de outro arquivo (anexo de e-mail, mensagem de mbox)
+    pub fn set_document_origin(
+        &self,
+        user_id: &str,
+        document_id: &str,
+        parent_document_id: Option<&str>,
+        original_path: &str,
+    ) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let affected = conn.execute(
+                "UPDATE documents SET parent_document_id = ?1, original_path = ?2 WHERE user_id = ?3 AND id = ?4",
+                params![parent_document_id, original_path, user_id, document_id]
+            )?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // Documentos ativos extraídos de um documento (ex.: anexos de um e-mail)
+    pub fn list_child_documents(&self, user_id: &str, parent_document_id: &str) -> SqliteResult<Vec<Document>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM documents WHERE user_id = ?1 AND parent_document_id = ?2 AND deleted_at IS NULL 
+                 ORDER BY created_at ASC",
+                DOCUMENT_COLUMNS
+            ))?;
+            
+            let document_iter = stmt.query_map([user_id, parent_document_id], document_from_row)?;
+            document_iter.collect()
+        })
+    }
+    
+    // Conteúdo indexado de um documento do usuário
+    pub fn get_document_content(&self, user_id: &str, document_id: &str) -> SqliteResult<Option<DocumentContent>> {
+        self.execute_with_retry(|conn| {
+            let result = conn.query_row(
+                "SELECT dc.document_id, dc.extracted_text, dc.document_type, dc.extracted_fields, dc.indexed_at
+                 FROM document_content dc JOIN documents d ON d.id = dc.document_id
+                 WHERE d.user_id = ?1 AND d.id = ?2",
+                params![user_id, document_id],
+                |row| {
+                    let indexed_at: String = row.get(4)?;
+                    Ok(DocumentContent {
+                        document_id: row.get(0)?,
+                        extracted_text: row.get(1)?,
+                        document_type: row.get(2)?,
+                        extracted_fields: row.get(3)?,
+                        indexed_at: DateTime::parse_from_rfc3339(&indexed_at)
+                            .map(|date| date.with_timezone(&Utc))
+                            .unwrap_or_else(|_| Utc::now()),
+                    })
+                },
+            );
+            
+            match result {
+                Ok(content) => Ok(Some(content)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e),
+            }
+        })
+    }
+    
+    // Corrigir o tipo de um documento; passa a ser exemplo de treino do classificador
+    pub fn set_document_type(&self, user_id: &str, document_id: &str, document_type: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let updated = conn.execute(
+                "UPDATE document_content SET document_type = ?3, type_corrected = 1
+                 WHERE document_id = ?2 AND document_id IN (SELECT id FROM documents WHERE user_id = ?1)",
+                params![user_id, document_id, document_type],
+            )?;
+            Ok(updated > 0)
+        })
+    }
+    
+    // Documentos ativos com tipo corrigido pelo usuário (tipo, texto)
+    pub fn get_corrected_documents(&self, user_id: &str) -> SqliteResult<Vec<(String, String)>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT dc.document_type, dc.extracted_text FROM documents d 
+                 JOIN document_content dc ON dc.document_id = d.id 
+                 WHERE d.user_id = ?1 AND d.deleted_at IS NULL AND dc.type_corrected = 1 AND dc.extracted_text != ''"
+            )?;
+            
+            let rows = stmt.query_map([user_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })
+    }
+    
+    // Substituir a assinatura MinHash do documento (vazia remove: texto curto demais para comparar)
+    pub fn save_document_signature(&self, document_id: &str, signature: &[u64]) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            if signature.is_empty() {
+                conn.execute("DELETE FROM document_signatures WHERE document_id = ?1", [document_id])?;
+                return Ok(());
+            }
+            Self::insert_document_signature(conn, document_id, signature)
+        })
+    }
+    
+    fn insert_document_signature(conn: &Connection, document_id: &str, signature: &[u64]) -> SqliteResult<()> {
+        let bytes: Vec<u8> = signature.iter().flat_map(|value| value.to_le_bytes()).collect();
+        conn.execute(
+            "INSERT OR REPLACE INTO document_signatures (document_id, signature) VALUES (?1, ?2)",
+            params![document_id, bytes]
+        )?;
+        Ok(())
+    }
+    
+    // Assinaturas dos documentos ativos do usuário (id, assinatura)
+    pub fn get_document_signatures(&self, user_id: &str) -> SqliteResult<Vec<(String, Vec<u64>)>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT d.id, ds.signature FROM documents d 
+                 JOIN document_signatures ds ON ds.document_id = d.id 
+                 WHERE d.user_id = ?1 AND d.deleted_at IS NULL"
+            )?;
+            
+            let rows = stmt.query_map([user_id], |row| {
+                let bytes: Vec<u8> = row.get(1)?;
+                let signature = bytes.chunks_exact(8)
+                    .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap_or_default()))
+                    .collect();
+                Ok((row.get(0)?, signature))
+            })?;
+            rows.collect()
+        })
+    }
+    
+    // ==================================================================================
+    // HISTÓRICO DE VERSÕES
+    // ==================================================================================
+    
+    fn version_from_row(row: &rusqlite::Row) -> SqliteResult<DocumentVersion> {
+        let ocr_result: Option<String> = row.get(8)?;
+        let created_at_str: String = row.get(9)?;
+        
+        Ok(DocumentVersion {
+            id: row.get(0)?,
+            document_id: row.get(1)?,
+            version_number: row.get(2)?,
+            file_path: row.get(3)?,
+            file_hash: row.get(4)?,
+            file_size: row.get(5)?,
+            file_type: row.get(6)?,
+            extracted_text: row.get(7)?,
+            ocr_result: ocr_result.and_then(|json| serde_json::from_str(&json).ok()),
+            created_at: DateTime::parse_from_rfc3339(&created_at_str)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(9, "created_at".to_string(), rusqlite::types::Type::Text))?
+                .with_timezone(&Utc),
+        })
+    }
+    
+    // Próximo número de versão de um documento
+    pub fn next_version_number(&self, document_id: &str) -> SqliteResult<i64> {
+        self.execute_with_retry(|conn| {
+            conn.query_row(
+                "SELECT COALESCE(MAX(version_number), 0) + 1 FROM document_versions WHERE document_id = ?1",
+                [document_id],
+                |row| row.get(0)
+            )
+        })
+    }
+    
+    pub fn create_document_version(&self, version: &DocumentVersion) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            conn.execute(
+                "INSERT INTO document_versions 
+                    (id, document_id, version_number, file_path, file_hash, file_size, file_type, extracted_text, ocr_result, created_at) 
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    version.id,
+                    version.document_id,
+                    version.version_number,
+                    version.file_path,
+                    version.file_hash,
+                    version.file_size,
+                    version.file_type,
+                    version.extracted_text,
+                    version.ocr_result.as_ref().map(|v| v.to_string()),
+                    version.created_at.to_rfc3339()
+                ]
+            )?;
+            Ok(())
+        })
+    }
+    
+    // Registrar tipo/campos do OCR na versão mais recente que ainda não os tem (a versão 1
+    // é criada antes de o documento ser indexado)
+    pub fn fill_latest_version_ocr_result(&self, document_id: &str, ocr_result: &serde_json::Value) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let affected = conn.execute(
+                "UPDATE document_versions SET ocr_result = ?2 
+                 WHERE document_id = ?1 AND ocr_result IS NULL AND version_number = (
+                     SELECT MAX(version_number) FROM document_versions WHERE document_id = ?1
+                 )",
+                params![document_id, ocr_result.to_string()]
+            )?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // Versões de um documento do usuário, da mais recente para a mais antiga
+    pub fn list_document_versions(&self, user_id: &str, document_id: &str) -> SqliteResult<Vec<DocumentVersion>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT v.id, v.document_id, v.version_number, v.file_path, v.file_hash, v.file_size, 
+                        v.file_type, v.extracted_text, v.ocr_result, v.created_at 
+                 FROM document_versions v 
+                 JOIN documents d ON d.id = v.document_id 
+                 WHERE d.user_id = ?1 AND v.document_id = ?2 
+                 ORDER BY v.version_number DESC"
+            )?;
+            
+            let version_iter = stmt.query_map([user_id, document_id], Self::version_from_row)?;
+            
+            let mut versions = Vec::new();
+            for version in version_iter {
+                versions.push(version?);
+            }
+            Ok(versions)
+        })
+    }
+    
+    pub fn get_document_version(&self, user_id: &str, document_id: &str, version_id: &str) -> SqliteResult<Option<DocumentVersion>> {
+        Ok(self.list_document_versions(user_id, document_id)?
+            .into_iter()
+            .find(|version| version.id == version_id))
+    }
+    
+    // Apontar o documento para o arquivo de uma versão (a mais recente)
+    pub fn set_document_current_version(&self, user_id: &str, version: &DocumentVersion) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let affected = conn.execute(
+                "UPDATE documents SET file_path = ?1, file_hash = ?2, file_size = ?3, file_type = ?4, updated_at = ?5 
+                 WHERE user_id = ?6 AND id = ?7 AND deleted_at IS NULL",
+                params![
+                    version.file_path,
+                    version.file_hash,
+                    version.file_size,
+                    version.file_type,
+                    version.created_at.to_rfc3339(),
+                    user_id,
+                    version.document_id
+                ]
+            )?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // ==================================================================================
+    // FILA DE JOBS
+    // ==================================================================================
+    
+    fn job_from_row(row: &rusqlite::Row) -> SqliteResult<Job> {
+        let payload: String = row.get(3)?;
+        let result: Option<String> = row.get(7)?;
+        let created_at_str: String = row.get(11)?;
+        let updated_at_str: String = row.get(12)?;
+        
+        Ok(Job {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            kind: row.get(2)?,
+            payload: serde_json::from_str(&payload).unwrap_or_default(),
+            status: row.get(4)?,
+            progress: row.get(5)?,
+            stage: row.get(6)?,
+            result: result.and_then(|json| serde_json::from_str(&json).ok()),
+            error: row.get(8)?,
+            attempts: row.get(9)?,
+            cancel_requested: row.get::<_, i64>(10)? != 0,
+            created_at: DateTime::parse_from_rfc3339(&created_at_str)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(11, "created_at".to_string(), rusqlite::types::Type::Text))?
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&updated_at_str)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(12, "updated_at".to_string(), rusqlite::types::Type::Text))?
+                .with_timezone(&Utc),
+        })
+    }
+    
+    pub fn create_job(&self, job: &Job) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            conn.execute(
+                &format!("INSERT INTO jobs ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)", JOB_COLUMNS),
+                params![
+                    job.id,
+                    job.user_id,
+                    job.kind,
+                    job.payload.to_string(),
+                    job.status,
+                    job.progress,
+                    job.stage,
+                    job.result.as_ref().map(|r| r.to_string()),
+                    job.error,
+                    job.attempts,
+                    job.cancel_requested as i64,
+                    job.created_at.to_rfc3339(),
+                    job.updated_at.to_rfc3339()
+                ]
+            )?;
+            Ok(())
+        })
+    }
+    
+    pub fn get_job(&self, job_id: &str) -> SqliteResult<Option<Job>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS))?;
+            let mut job_iter = stmt.query_map([job_id], Self::job_from_row)?;
+            job_iter.next().transpose()
+        })
+    }
+    
+    pub fn list_jobs(&self, user_id: &str, status: Option<&str>, limit: usize) -> SqliteResult<Vec<Job>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM jobs WHERE user_id = ?1 AND (?2 IS NULL OR status = ?2) 
+                 ORDER BY created_at DESC LIMIT ?3",
+                JOB_COLUMNS
+            ))?;
+            let jobs = stmt.query_map(params![user_id, status, limit as i64], Self::job_from_row)?
+                .collect::<SqliteResult<Vec<_>>>()?;
+            Ok(jobs)
+        })
+    }
+    
+    // Pegar o próximo job da fila e marcá-lo como em execução (atômico sob o lock da conexão)
+    pub fn claim_next_job(&self) -> SqliteResult<Option<Job>> {
+        self.execute_with_retry(|conn| {
+            let job_id: String = match conn.query_row(
+                "SELECT id FROM jobs WHERE status = 'queued' ORDER BY created_at LIMIT 1",
+                [],
+                |row| row.get(0)
+            ) {
+                Ok(id) => id,
+                Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+            
+            conn.execute(
+                "UPDATE jobs SET status = 'running', attempts = attempts + 1, progress = 0, 
+                 error = NULL, updated_at = ?1 WHERE id = ?2",
+                params![Utc::now().to_rfc3339(), job_id]
+            )?;
+            
+            let mut stmt = conn.prepare(&format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS))?;
+            let mut job_iter = stmt.query_map([&job_id], Self::job_from_row)?;
+            job_iter.next().transpose()
+        })
+    }
+    
+    pub fn update_job_progress(&self, job_id: &str, progress: f64, stage: &str) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            conn.execute(
+                "UPDATE jobs SET progress = ?1, stage = ?2, updated_at = ?3 WHERE id = ?4",
+                params![progress, stage, Utc::now().to_rfc3339(), job_id]
+            )?;
+            Ok(())
+        })
+    }
+    
+    // Finalizar job com status completed, failed ou cancelled
+    pub fn finish_job(
+        &self,
+        job_id: &str,
+        status: &str,
+        result: Option<&serde_json::Value>,
+        error: Option<&str>,
+    ) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            conn.execute(
+                "UPDATE jobs SET status = ?1, result = ?2, error = ?3, 
+                 progress = CASE WHEN ?1 = 'completed' THEN 1.0 ELSE progress END, updated_at = ?4 
+                 WHERE id = ?5",
+                params![status, result.map(|r| r.to_string()), error, Utc::now().to_rfc3339(), job_id]
+            )?;
+            Ok(())
+        })
+    }
+    
+    // Cancelar: jobs na fila são cancelados na hora; em execução recebem um pedido de cancelamento
+    pub fn cancel_job(&self, user_id: &str, job_id: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let now = Utc::now().to_rfc3339();
+            let cancelled = conn.execute(
+                "UPDATE jobs SET status = 'cancelled', updated_at = ?1 WHERE user_id = ?2 AND id = ?3 AND status = 'queued'",
+                params![now, user_id, job_id]
+            )?;
+            let requested = conn.execute(
+                "UPDATE jobs SET cancel_requested = 1, updated_at = ?1 WHERE user_id = ?2 AND id = ?3 AND status = 'running'",
+                params![now, user_id, job_id]
+            )?;
+            Ok(cancelled + requested > 0)
+        })
+    }
+    
+    pub fn is_job_cancel_requested(&self, job_id: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            conn.query_row(
+                "SELECT cancel_requested FROM jobs WHERE id = ?1",
+                [job_id],
+                |row| Ok(row.get::<_, i64>(0)? != 0)
+            )
+        })
+    }
+    
+    // Recolocar na fila um job que falhou ou foi cancelado
+    pub fn retry_job(&self, user_id: &str, job_id: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let affected = conn.execute(
+                "UPDATE jobs SET status = 'queued', progress = 0, stage = NULL, result = NULL, error = NULL, 
+                 cancel_requested = 0, updated_at = ?1 
+                 WHERE user_id = ?2 AND id = ?3 AND status IN ('failed', 'cancelled')",
+                params![Utc::now().to_rfc3339(), user_id, job_id]
+            )?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // Jobs interrompidos pelo fechamento do app voltam para a fila
+    pub fn requeue_interrupted_jobs(&self) -> SqliteResult<usize> {
+        self.execute_with_retry(|conn| {
+            conn.execute(
+                "UPDATE jobs SET status = CASE WHEN cancel_requested = 1 THEN 'cancelled' ELSE 'queued' END, 
+                 updated_at = ?1 WHERE status = 'running'",
+                [Utc::now().to_rfc3339()]
+            )
+        })
+    }
+    
+    // ==================================================================================
+    // PASTAS MONITORADAS
+    // ==================================================================================
+    
+    fn watch_folder_from_row(row: &rusqlite::Row) -> SqliteResult<WatchFolder> {
+        let include_json: String = row.get(3)?;
+        let exclude_json: String = row.get(4)?;
+        let created_at_str: String = row.get(8)?;
+        
+        Ok(WatchFolder {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            path: row.get(2)?,
+            include_patterns: serde_json::from_str(&include_json).unwrap_or_default(),
+            exclude_patterns: serde_json::from_str(&exclude_json).unwrap_or_default(),
+            archive_processed: row.get::<_, i64>(5)? != 0,
+            archive_subfolder: row.get(6)?,
+            enabled: row.get::<_, i64>(7)? != 0,
+            created_at: DateTime::parse_from_rfc3339(&created_at_str)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(8, "created_at".to_string(), rusqlite::types::Type::Text))?
+                .with_timezone(&Utc),
+        })
+    }
+    
+    // Criar ou atualizar configuração de pasta monitorada
+    pub fn save_watch_folder(&self, folder: &WatchFolder) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            conn.execute(
+                &format!("INSERT OR REPLACE INTO watch_folders ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)", WATCH_FOLDER_COLUMNS),
+                params![
+                    folder.id,
+                    folder.user_id,
+                    folder.path,
+                    serde_json::to_string(&folder.include_patterns).unwrap_or_else(|_| "[]".to_string()),
+                    serde_json::to_string(&folder.exclude_patterns).unwrap_or_else(|_| "[]".to_string()),
+                    folder.archive_processed as i64,
+                    folder.archive_subfolder,
+                    folder.enabled as i64,
+                    folder.created_at.to_rfc3339()
+                ]
+            )?;
+            Ok(())
+        })
+    }
+    
+    pub fn list_watch_folders(&self, user_id: &str) -> SqliteResult<Vec<WatchFolder>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM watch_folders WHERE user_id = ?1 ORDER BY created_at", WATCH_FOLDER_COLUMNS
+            ))?;
+            let folders = stmt.query_map([user_id], Self::watch_folder_from_row)?
+                .collect::<SqliteResult<Vec<_>>>()?;
+            Ok(folders)
+        })
+    }
+    
+    // Pastas ativas de todos os usuários (usado pelo serviço em segundo plano)
+    pub fn list_enabled_watch_folders(&self) -> SqliteResult<Vec<WatchFolder>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM watch_folders WHERE enabled = 1", WATCH_FOLDER_COLUMNS
+            ))?;
+            let folders = stmt.query_map([], Self::watch_folder_from_row)?
+                .collect::<SqliteResult<Vec<_>>>()?;
+            Ok(folders)
+        })
+    }
+    
+    pub fn delete_watch_folder(&self, user_id: &str, folder_id: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            conn.execute("DELETE FROM watch_folder_files WHERE watch_folder_id = ?1 AND watch_folder_id IN (SELECT id FROM watch_folders WHERE user_id = ?2)", params![folder_id, user_id])?;
+            let affected = conn.execute("DELETE FROM watch_folders WHERE user_id = ?1 AND id = ?2", params![user_id, folder_id])?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // Arquivo já foi processado com esta data de modificação?
+    pub fn is_watch_file_processed(&self, folder_id: &str, file_path: &str, modified_at: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM watch_folder_files WHERE watch_folder_id = ?1 AND file_path = ?2 AND modified_at = ?3",
+                params![folder_id, file_path, modified_at],
+                |row| row.get(0)
+            )?;
+            Ok(count > 0)
+        })
+    }
+    
+    pub fn record_watch_file(
+        &self,
+        folder_id: &str,
+        file_path: &str,
+        modified_at: &str,
+        status: &str,
+        document_id: Option<&str>,
+        error_message: Option<&str>,
+    ) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO watch_folder_files 
+                    (watch_folder_id, file_path, modified_at, status, document_id, error_message, processed_at) 
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![folder_id, file_path, modified_at, status, document_id, error_message, Utc::now().to_rfc3339()]
+            )?;
+            Ok(())
+        })
+    }
+    
+    // ==================================================================================
+    // SISTEMA DE TAGS
+    // ==================================================================================
+    
+    // Buscar tag pelo nome (sem diferenciar maiúsculas) ou criá-la
+    fn get_or_create_tag(conn: &Connection, user_id: &str, name: &str) -> SqliteResult<String> {
+        let name = name.trim();
+        match conn.query_row(
+            "SELECT id FROM tags WHERE user_id = ?1 AND name = ?2",
+            params![user_id, name],
+            |row| row.get::<_, String>(0)
+        ) {
+            Ok(id) => Ok(id),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                let id = Uuid::new_v4().to_string();
+                conn.execute(
+                    "INSERT INTO tags (id, user_id, name, created_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![id, user_id, name, Utc::now().to_rfc3339()]
+                )?;
+                Ok(id)
+            }
+            Err(e) => Err(e),
+        }
+    }
+    
+    // Atualizar o cache JSON documents.tags a partir das tabelas normalizadas, só nos
+    // documentos afetados pela operação
+    fn refresh_tags_cache(conn: &Connection, user_id: &str, document_ids: &[String]) -> SqliteResult<()> {
+        let mut stmt = conn.prepare(
+            "UPDATE documents SET tags = (
+                SELECT json_group_array(name) FROM (
+                    SELECT t.name FROM document_tags dt JOIN tags t ON t.id = dt.tag_id
+                    WHERE dt.document_id = documents.id ORDER BY t.name
+                )
+             ) WHERE user_id = ?1 AND id = ?2"
+        )?;
+        for document_id in document_ids {
+            stmt.execute(params![user_id, document_id])?;
+        }
+        Ok(())
+    }
+    
+    // Documentos vinculados a uma tag
+    fn tagged_document_ids(conn: &Connection, tag_id: &str) -> SqliteResult<Vec<String>> {
+        let mut stmt = conn.prepare("SELECT document_id FROM document_tags WHERE tag_id = ?1")?;
+        let ids = stmt.query_map([tag_id], |row| row.get::<_, String>(0))?;
+        ids.collect()
+    }
+    
+    // Substituir as tags de um documento (cria tags inexistentes)
+    pub fn set_document_tags(&self, user_id: &str, document_id: &str, tags: &[String]) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            
+            tx.execute("DELETE FROM document_tags WHERE document_id = ?1", [document_id])?;
+            for tag in tags {
+                let tag_id = Self::get_or_create_tag(&tx, user_id, tag)?;
+                tx.execute(
+                    "INSERT OR IGNORE INTO document_tags (document_id, tag_id) VALUES (?1, ?2)",
+                    params![document_id, tag_id]
+                )?;
+            }
+            Self::refresh_tags_cache(&tx, user_id, &[document_id.to_string()])?;
+            
+            tx.commit()
+        })
+    }
+    
+    // Listar tags com contagem de documentos ativos (similar a get_available_folders)
+    pub fn list_tags(&self, user_id: &str) -> SqliteResult<Vec<Tag>> {
+        self.execute_with_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT t.id, t.name, COUNT(d.id) as doc_count 
+                 FROM tags t 
+                 LEFT JOIN document_tags dt ON dt.tag_id = t.id 
+                 LEFT JOIN documents d ON d.id = dt.document_id AND d.deleted_at IS NULL 
+                 WHERE t.user_id = ?1 
+                 GROUP BY t.id, t.name 
+                 ORDER BY t.name"
+            )?;
+            
+            let tag_iter = stmt.query_map([user_id], |row| {
+                Ok(Tag {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    document_count: row.get(2)?,
+                })
+            })?;
+            
+            let mut tags = Vec::new();
+            for tag in tag_iter {
+                tags.push(tag?);
+            }
+            
+            log::debug!("🏷️ Encontradas {} tags para usuário {}", tags.len(), user_id);
+            Ok(tags)
+        })
+    }
+    
+    pub fn get_tag(&self, user_id: &str, tag_id: &str) -> SqliteResult<Option<Tag>> {
+        Ok(self.list_tags(user_id)?.into_iter().find(|tag| tag.id == tag_id))
+    }
+    
+    pub fn create_tag(&self, user_id: &str, name: &str) -> SqliteResult<String> {
+        self.execute_with_retry(|conn| Self::get_or_create_tag(conn, user_id, name))
+    }
+    
+    pub fn rename_tag(&self, user_id: &str, tag_id: &str, new_name: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let affected = tx.execute(
+                "UPDATE tags SET name = ?1 WHERE user_id = ?2 AND id = ?3",
+                params![new_name.trim(), user_id, tag_id]
+            )?;
+            Self::refresh_tags_cache(&tx, user_id, &Self::tagged_document_ids(&tx, tag_id)?)?;
+            tx.commit()?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // Mesclar tags de origem na tag de destino; retorna quantos vínculos foram movidos
+    pub fn merge_tags(&self, user_id: &str, source_tag_ids: &[String], target_tag_id: &str) -> SqliteResult<usize> {
+        self.execute_with_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut moved = 0;
+            
+            for source_id in source_tag_ids.iter().filter(|id| id.as_str() != target_tag_id) {
+                let owned: i64 = tx.query_row(
+                    "SELECT COUNT(*) FROM tags WHERE user_id = ?1 AND id = ?2",
+                    params![user_id, source_id],
+                    |row| row.get(0)
+                )?;
+                if owned == 0 {
+                    continue;
+                }
+                
+                moved += tx.execute(
+                    "INSERT OR IGNORE INTO document_tags (document_id, tag_id) 
+                     SELECT document_id, ?1 FROM document_tags WHERE tag_id = ?2",
+                    params![target_tag_id, source_id]
+                )?;
+                tx.execute("DELETE FROM document_tags WHERE tag_id = ?1", [source_id])?;
+                tx.execute("DELETE FROM tags WHERE user_id = ?1 AND id = ?2", params![user_id, source_id])?;
+            }
+            
+            // Os documentos das tags de origem agora estão todos na de destino
+            Self::refresh_tags_cache(&tx, user_id, &Self::tagged_document_ids(&tx, target_tag_id)?)?;
+            tx.commit()?;
+            Ok(moved)
+        })
+    }
+    
+    pub fn delete_tag(&self, user_id: &str, tag_id: &str) -> SqliteResult<bool> {
+        self.execute_with_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let tagged = Self::tagged_document_ids(&tx, tag_id)?;
+            tx.execute(
+                "DELETE FROM document_tags WHERE tag_id IN (SELECT id FROM tags WHERE user_id = ?1 AND id = ?2)",
+                params![user_id, tag_id]
+            )?;
+            let affected = tx.execute("DELETE FROM tags WHERE user_id = ?1 AND id = ?2", params![user_id, tag_id])?;
+            Self::refresh_tags_cache(&tx, user_id, &tagged)?;
+            tx.commit()?;
+            Ok(affected > 0)
+        })
+    }
+    
+    // Adicionar/remover tags em vários documentos de uma vez; retorna documentos afetados
+    pub fn bulk_tag_documents(
+        &self,
+        user_id: &str,
+        document_ids: &[String],
+        add_tags: &[String],
+        remove_tags: &[String],
+    ) -> SqliteResult<usize> {
+        self.execute_with_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            
+            let add_ids = add_tags.iter()
+                .map(|name| Self::get_or_create_tag(&tx, user_id, name))
+                .collect::<SqliteResult<Vec<String>>>()?;
+            
+            let mut changed_docs = Vec::new();
+            for document_id in document_ids {
+                let owned: i64 = tx.query_row(
+                    "SELECT COUNT(*) FROM documents WHERE user_id = ?1 AND id = ?2",
+                    params![user_id, document_id],
+                    |row| row.get(0)
+                )?;
+                if owned == 0 {
+                    continue;
+                }
+                
+                let mut changed = 0;
+                for tag_id in &add_ids {
+                    changed += tx.execute(
+                        "INSERT OR IGNORE INTO document_tags (document_id, tag_id) VALUES (?1, ?2)",
+                        params![document_id, tag_id]
+                    )?;
+                }
+                for tag_name in remove_tags {
+                    changed += tx.execute(
+                        "DELETE FROM document_tags WHERE document_id = ?1 
+                         AND tag_id IN (SELECT id FROM tags WHERE user_id = ?2 AND name = ?3)",
+                        params![document_id, user_id, tag_name.trim()]
+                    )?;
+                }
+                if changed > 0 {
+                    changed_docs.push(document_id.clone());
+                }
+            }
+            
+            Self::refresh_tags_cache(&tx, user_id, &changed_docs)?;
+            tx.commit()?;
+            Ok(changed_docs.len())
+        })
+    }
+    
+    // IDs de documentos que possuem alguma (ou todas, se match_all) das tags informadas
+    pub fn get_document_ids_with_tags(
+        &self,
+        user_id: &str,
+        tags: &[String],
+        match_all: bool,
+    ) -> SqliteResult<std::collections::HashSet<String>> {
+        self.execute_with_retry(|conn| {
+            let placeholders: Vec<String> = (0..tags.len()).map(|i| format!("?{}", i + 2)).collect();
+            let query = format!(
+                "SELECT dt.document_id FROM document_tags dt 
+                 JOIN tags t ON t.id = dt.tag_id 
+                 WHERE t.user_id = ?1 AND t.name IN ({}) 
+                 GROUP BY dt.document_id 
+                 HAVING COUNT(DISTINCT t.id) >= {}",
+                placeholders.join(", "),
+                if match_all { tags.len() } else { 1 }
+            );
+            
+            let mut params: Vec<&dyn rusqlite::ToSql> = vec![&user_id];
+            for tag in tags {
+                params.push(tag);
+            }
+            
+            let mut stmt = conn.prepare(&query)?;
+            let id_iter = stmt.query_map(&params[..], |row| row.get::<_, String>(0))?;
+            
+            let mut ids = std::collections::HashSet::new();
+            for id in id_iter {
+                ids.insert(id?);
+            }
+            Ok(ids)
+        })
+    }
+    
+    // ================================
+    // PREFERÊNCIAS DO USUÁRIO
+    // ================================
+    
+    pub fn get_user_setting(&self, user_id: &str, key: &str) -> SqliteResult<Option<String>> {
+        self.execute_with_retry(|conn| {
+            match conn.query_row(
+                "SELECT value FROM user_settings WHERE user_id = ?1 AND key = ?2",
+                params![user_id, key],
+                |row| row.get::<_, String>(0)
+            ) {
+                Ok(value) => Ok(Some(value)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(e),
+            }
+        })
+    }
+    
+    pub fn set_user_setting(&self, user_id: &str, key: &str, value: &str) -> SqliteResult<()> {
+        self.execute_with_retry(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO user_settings (user_id, key, value, updated_at) VALUES (?1, ?2, ?3, ?4)",
+                params![user_id, key, value, Utc::now().to_rfc3339()]
+            )?;
+            Ok(())
+        })
+    }
+}
+
@@ -1,12 +1,15 @@
 use chrono::{NaiveDate, Datelike};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use crate::br_validators;
+use crate::date_locale::{self, DateLocale};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DateSource {
     Filename,
     Content,
+    // Data estruturada do próprio arquivo (ex.: dhEmi da NF-e)
+    Metadata,
     Fallback,
 }
 
@@ -17,228 +20,557 @@ pub struct DateDetectionResult {
     pub confidence: f32,
 }
 
+// Papel de uma data no documento, pelo rótulo que aparece antes dela
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DateKind {
+    Emissao,
+    Vencimento,
+    VigenciaInicio,
+    VigenciaFim,
+    Competencia,
+}
+
+impl DateKind {
+    pub const ALL: [DateKind; 5] = [
+        DateKind::Emissao,
+        DateKind::Vencimento,
+        DateKind::VigenciaInicio,
+        DateKind::VigenciaFim,
+        DateKind::Competencia,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateKind::Emissao => "emissao",
+            DateKind::Vencimento => "vencimento",
+            DateKind::VigenciaInicio => "vigencia_inicio",
+            DateKind::VigenciaFim => "vigencia_fim",
+            DateKind::Competencia => "competencia",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TypedDate {
+    pub kind: DateKind,
+    pub value: NaiveDate,
+    // Trecho original ("10/10/2025", "outubro/2025")
+    pub raw: String,
+    // Posição (em bytes) no texto
+    pub position: usize,
+}
+
+// Quanto texto antes da data (na mesma linha) é considerado ao procurar o rótulo
+const DATE_LABEL_WINDOW_CHARS: usize = 60;
+
+// Padrões de data no nome do arquivo: (regex, posição de ano/mês/dia nos grupos, confiança base)
+const FILENAME_PATTERNS: &[(&str, [usize; 3], f32)] = &[
+    // YYYY-MM-DD (ISO 8601) - mais confiável
+    (r"(\d{4})-(\d{2})-(\d{2})", [0, 1, 2], 0.95),
+    // DD-MM-YYYY
+    (r"(\d{2})-(\d{2})-(\d{4})", [2, 1, 0], 0.95),
+    // DD/MM/YYYY
+    (r"(\d{2})/(\d{2})/(\d{4})", [2, 1, 0], 0.95),
+    // DD_MM_YYYY
+    (r"(\d{2})_(\d{2})_(\d{4})", [2, 1, 0], 0.95),
+    // YYYYMMDD (compacto)
+    (r"(\d{4})(\d{2})(\d{2})", [0, 1, 2], 0.90),
+    // DDMMYYYY (compacto)
+    (r"(\d{2})(\d{2})(\d{4})", [2, 1, 0], 0.85),
+    // DD-MM-YY (ano com 2 dígitos)
+    (r"(\d{2})-(\d{2})-(\d{2})", [2, 1, 0], 0.80),
+    // DD/MM/YY
+    (r"(\d{2})/(\d{2})/(\d{2})", [2, 1, 0], 0.80),
+    // DD_MM_YY
+    (r"(\d{2})_(\d{2})_(\d{2})", [2, 1, 0], 0.80),
+    // DDMMYY (compacto)
+    (r"(\d{2})(\d{2})(\d{2})", [2, 1, 0], 0.75),
+];
+
+// Padrões de data no conteúdo: (regex, grupos de ano/mês/dia - dia 0 = só mês/ano, confiança base).
+// As datas numéricas DD/MM/YYYY viram MM/DD/YYYY nos idiomas com mês primeiro.
+const CONTENT_PATTERNS: &[(&str, [usize; 3], f32)] = &[
+    // 4 de outubro de 2025
+    (r"(?i)\b(\d{1,2})\s+de\s+(\p{L}+)\s+de\s+(\d{4})\b", [3, 2, 1], 0.85),
+    // 4 outubro 2025
+    (r"(?i)\b(\d{1,2})\s+(\p{L}+)\s+(\d{4})\b", [3, 2, 1], 0.80),
+    // DD/MM/YYYY, DD-MM-YYYY, DD.MM.YYYY
+    (r"\b(\d{1,2})[/.-](\d{1,2})[/.-](\d{4})\b", [3, 2, 1], 0.75),
+    // YYYY-MM-DD
+    (r"\b(\d{4})-(\d{2})-(\d{2})\b", [1, 2, 3], 0.75),
+    // outubro/2025, outubro de 2025
+    (r"(?i)\b(\p{L}+)\s*(?:/|\s+de\s+)\s*(\d{4})\b", [2, 1, 0], 0.70),
+    // MM/YYYY
+    (r"\b(\d{1,2})/(\d{4})\b", [2, 1, 0], 0.60),
+];
+
+// Formas do inglês: "October 4, 2025", "Oct 4th 2025" e "Oct 2025"
+const MONTH_FIRST_PATTERNS: &[(&str, [usize; 3], f32)] = &[
+    (r"(?i)\b(\p{L}+)\.?\s+(\d{1,2})(?:st|nd|rd|th)?,?\s+(\d{4})\b", [3, 1, 2], 0.85),
+    (r"(?i)\b(\p{L}+)\.?,?\s+(\d{4})\b", [2, 1, 0], 0.70),
+];
+
+// Posição dos grupos de DD/MM/YYYY quando o mês vem primeiro (MM/DD/YYYY)
+const NUMERIC_MONTH_FIRST: [usize; 3] = [3, 1, 2];
+
+// Caracteres de contexto mostrados de cada lado da data
+const CONTEXT_CHARS: usize = 30;
+// Bônus por repetição da mesma data (por ocorrência extra) e o máximo somado
+const REPEAT_BONUS: f32 = 0.05;
+const MAX_REPEAT_BONUS: f32 = 0.15;
+// Penalidade para datas fora do período plausível (10 anos atrás até 1 ano à frente)
+const IMPLAUSIBLE_PENALTY: f32 = 0.3;
+
+struct DateCandidate {
+    start: usize,
+    end: usize,
+    value: NaiveDate,
+    // Só mês/ano (vale apenas como competência)
+    month_only: bool,
+    base_confidence: f32,
+}
+
+/// Data candidata com a pontuação final e o porquê dela, para a interface oferecer
+/// alternativas quando a confiança da melhor é baixa
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankedDate {
+    pub value: NaiveDate,
+    pub source: DateSource,
+    pub confidence: f32,
+    // Papel pelo rótulo mais próximo (só no conteúdo)
+    pub kind: Option<DateKind>,
+    // Trecho original da ocorrência mais bem pontuada
+    pub raw: String,
+    // Posição (em bytes) da ocorrência no nome do arquivo ou no texto
+    pub position: usize,
+    // Texto ao redor da ocorrência
+    pub context: String,
+    // Quantas vezes a data aparece (nome do arquivo + conteúdo)
+    pub occurrences: usize,
+    pub reasons: Vec<String>,
+}
+
+impl RankedDate {
+    pub fn detection(&self) -> DateDetectionResult {
+        DateDetectionResult {
+            value: self.value,
+            source: self.source.clone(),
+            confidence: self.confidence,
+        }
+    }
+}
+
+// Tipo de data que costuma datar cada tipo de documento
+fn preferred_kind(document_type: &str) -> Option<DateKind> {
+    match document_type {
+        "nota_fiscal" | "recibo" | "boleto" | "email" => Some(DateKind::Emissao),
+        "contrato" => Some(DateKind::VigenciaInicio),
+        "documento_rh" => Some(DateKind::Competencia),
+        _ => None,
+    }
+}
+
+// Bônus pelo rótulo: a data de emissão é a que melhor representa o documento
+fn kind_bonus(kind: DateKind) -> f32 {
+    match kind {
+        DateKind::Emissao => 0.08,
+        DateKind::Competencia | DateKind::VigenciaInicio => 0.04,
+        DateKind::Vencimento => 0.02,
+        DateKind::VigenciaFim => 0.0,
+    }
+}
+
+// Texto ao redor de [start, end), numa linha só
+fn context_around(text: &str, start: usize, end: usize) -> String {
+    let mut before: Vec<char> = text[..start].chars().rev().take(CONTEXT_CHARS).collect();
+    before.reverse();
+    let after: String = text[end..].chars().take(CONTEXT_CHARS).collect();
+    let context = format!("{}{}{}", before.into_iter().collect::<String>(), &text[start..end], after);
+    context.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 pub struct DateExtractor {
-    month_map_ptbr: HashMap<String, u32>,
+    locale: DateLocale,
 }
 
 impl DateExtractor {
+    /// Extrator para documentos em português (padrão)
     pub fn new() -> Self {
-        let mut month_map_ptbr = HashMap::new();
-        month_map_ptbr.insert("janeiro".to_string(), 1);
-        month_map_ptbr.insert("fevereiro".to_string(), 2);
-        month_map_ptbr.insert("março".to_string(), 3);
-        month_map_ptbr.insert("marco".to_string(), 3);
-        month_map_ptbr.insert("abril".to_string(), 4);
-        month_map_ptbr.insert("maio".to_string(), 5);
-        month_map_ptbr.insert("junho".to_string(), 6);
-        month_map_ptbr.insert("julho".to_string(), 7);
-        month_map_ptbr.insert("agosto".to_string(), 8);
-        month_map_ptbr.insert("setembro".to_string(), 9);
-        month_map_ptbr.insert("outubro".to_string(), 10);
-        month_map_ptbr.insert("novembro".to_string(), 11);
-        month_map_ptbr.insert("dezembro".to_string(), 12);
-        
-        month_map_ptbr.insert("jan".to_string(), 1);
-        month_map_ptbr.insert("fev".to_string(), 2);
-        month_map_ptbr.insert("mar".to_string(), 3);
-        month_map_ptbr.insert("abr".to_string(), 4);
-        month_map_ptbr.insert("mai".to_string(), 5);
-        month_map_ptbr.insert("jun".to_string(), 6);
-        month_map_ptbr.insert("jul".to_string(), 7);
-        month_map_ptbr.insert("ago".to_string(), 8);
-        month_map_ptbr.insert("set".to_string(), 9);
-        month_map_ptbr.insert("out".to_string(), 10);
-        month_map_ptbr.insert("nov".to_string(), 11);
-        month_map_ptbr.insert("dez".to_string(), 12);
-
-        DateExtractor { month_map_ptbr }
+        Self::for_locale(DateLocale::PtBr)
     }
 
-    pub fn extract_date_from_filename(&self, filename: &str) -> Option<DateDetectionResult> {
-        log::debug!("🔍 Extraindo data do filename: {}", filename);
+    pub fn for_locale(locale: DateLocale) -> Self {
+        DateExtractor { locale }
+    }
+
+    /// Extrator no idioma detectado no texto do documento
+    pub fn for_text(text: &str) -> Self {
+        Self::for_locale(date_locale::detect_locale(text))
+    }
+
+    pub fn locale(&self) -> DateLocale {
+        self.locale
+    }
+
+    // Todas as datas válidas do nome do arquivo. Números colados em outros dígitos
+    // (ex.: parte do número de uma nota) são ignorados.
+    fn filename_candidates(&self, filename: &str) -> Vec<DateCandidate> {
+        let mut candidates: Vec<DateCandidate> = Vec::new();
+
+        for (pattern_str, order, base_confidence) in FILENAME_PATTERNS {
+            let Ok(regex) = Regex::new(pattern_str) else { continue };
+            for captures in regex.captures_iter(filename) {
+                let Some(whole) = captures.get(0) else { continue };
+                let glued = filename[..whole.start()].chars().next_back().is_some_and(|c| c.is_ascii_digit())
+                    || filename[whole.end()..].chars().next().is_some_and(|c| c.is_ascii_digit());
+                if glued || candidates.iter().any(|c| whole.start() < c.end && c.start < whole.end()) {
+                    continue;
+                }
+
+                let parts: Vec<&str> = (1..=3)
+                    .map(|i| captures.get(i).map(|m| m.as_str()).unwrap_or(""))
+                    .collect();
+
+                if let (Ok(mut year), Ok(month), Ok(day)) = (
+                    parts[order[0]].parse::<i32>(),
+                    parts[order[1]].parse::<u32>(),
+                    parts[order[2]].parse::<u32>(),
+                ) {
+                    if year < 100 {
+                        year += if year > 50 { 1900 } else { 2000 };
+                    }
 
-        let patterns = vec![
-            // YYYY-MM-DD (ISO 8601) - mais confiável
-            (r"(\d{4})-(\d{2})-(\d{2})", vec![0, 1, 2], 0.95),
-            // DD-MM-YYYY
-            (r"(\d{2})-(\d{2})-(\d{4})", vec![2, 1, 0], 0.95),
-            // DD/MM/YYYY
-            (r"(\d{2})/(\d{2})/(\d{4})", vec![2, 1, 0], 0.95),
-            // DD_MM_YYYY
-            (r"(\d{2})_(\d{2})_(\d{4})", vec![2, 1, 0], 0.95),
-            // YYYYMMDD (compacto)
-            (r"(\d{4})(\d{2})(\d{2})", vec![0, 1, 2], 0.90),
-            // DDMMYYYY (compacto)
-            (r"(\d{2})(\d{2})(\d{4})", vec![2, 1, 0], 0.85),
-            // DD-MM-YY (ano com 2 dígitos)
-            (r"(\d{2})-(\d{2})-(\d{2})", vec![2, 1, 0], 0.80),
-            // DD/MM/YY
-            (r"(\d{2})/(\d{2})/(\d{2})", vec![2, 1, 0], 0.80),
-            // DD_MM_YY
-            (r"(\d{2})_(\d{2})_(\d{2})", vec![2, 1, 0], 0.80),
-            // DDMMYY (compacto)
-            (r"(\d{2})(\d{2})(\d{2})", vec![2, 1, 0], 0.75),
-        ];
-
-        for (pattern_str, order, base_confidence) in patterns {
-            if let Ok(regex) = Regex::new(pattern_str) {
-                if let Some(captures) = regex.captures(filename) {
-                    let parts: Vec<&str> = (1..=3)
-                        .map(|i| captures.get(i).map(|m| m.as_str()).unwrap_or(""))
-                        .collect();
-
-                    if parts.len() == 3 {
-                        let year_str = parts[order[0]];
-                        let month_str = parts[order[1]];
-                        let day_str = parts[order[2]];
-
-                        if let (Ok(mut year), Ok(month), Ok(day)) = (
-                            year_str.parse::<i32>(),
-                            month_str.parse::<u32>(),
-                            day_str.parse::<u32>(),
-                        ) {
-                            if year < 100 {
-                                year += if year > 50 { 1900 } else { 2000 };
-                            }
-
-                            if month >= 1 && month <= 12 && day >= 1 && day <= 31 {
-                                if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
-                                    log::info!(
-                                        "✅ Data extraída do filename: {} (confidence: {:.2})",
-                                        date.format("%Y-%m-%d"),
-                                        base_confidence
-                                    );
-                                    return Some(DateDetectionResult {
-                                        value: date,
-                                        source: DateSource::Filename,
-                                        confidence: base_confidence,
-                                    });
-                                }
-                            }
-                        }
+                    if let Some(value) = NaiveDate::from_ymd_opt(year, month, day) {
+                        candidates.push(DateCandidate {
+                            start: whole.start(),
+                            end: whole.end(),
+                            value,
+                            month_only: false,
+                            base_confidence: *base_confidence,
+                        });
                     }
                 }
             }
         }
 
-        log::debug!("⚠️ Nenhuma data encontrada no filename");
-        None
+        candidates.sort_by_key(|candidate| candidate.start);
+        candidates
+    }
+
+    pub fn extract_date_from_filename(&self, filename: &str) -> Option<DateDetectionResult> {
+        log::debug!("🔍 Extraindo data do filename: {}", filename);
+
+        // O padrão mais confiável vence; no empate, o que aparece primeiro
+        let best = self
+            .filename_candidates(filename)
+            .into_iter()
+            .reduce(|best, candidate| if candidate.base_confidence > best.base_confidence { candidate } else { best });
+
+        match best {
+            Some(candidate) => {
+                log::info!(
+                    "✅ Data extraída do filename: {} (confidence: {:.2})",
+                    candidate.value.format("%Y-%m-%d"),
+                    candidate.base_confidence
+                );
+                Some(DateDetectionResult {
+                    value: candidate.value,
+                    source: DateSource::Filename,
+                    confidence: candidate.base_confidence,
+                })
+            }
+            None => {
+                log::debug!("⚠️ Nenhuma data encontrada no filename");
+                None
+            }
+        }
     }
 
+    /// Melhor data do conteúdo entre todas as candidatas (ver `rank_dates`)
     pub fn extract_date_from_content_ptbr(&self, text: &str) -> Option<DateDetectionResult> {
         log::debug!("🔍 Extraindo data do conteúdo (PT-BR)");
 
-        let text_lower = text.to_lowercase();
-
-        let current_year = chrono::Utc::now().year();
-        let year_range = (current_year - 10)..=(current_year + 1);
-
-        let patterns = vec![
-            (
-                r"(\d{1,2})\s+de\s+([a-záàâãéèêíïóôõöúçñ]+)\s+de\s+(\d{4})",
-                vec![2, 1, 0],
-                0.85,
-            ),
-            (
-                r"(\d{1,2})\s+([a-záàâãéèêíïóôõöúçñ]+)\s+(\d{4})",
-                vec![2, 1, 0],
-                0.80,
-            ),
-            (
-                r"([a-záàâãéèêíïóôõöúçñ]+)\s+de\s+(\d{4})",
-                vec![1, 0, 999],
-                0.70,
-            ),
-            (r"(\d{2})/(\d{2})/(\d{4})", vec![2, 1, 0], 0.75),
-            (r"(\d{2})-(\d{2})-(\d{4})", vec![2, 1, 0], 0.75),
-        ];
-
-        for (pattern_str, order, base_confidence) in patterns {
-            if let Ok(regex) = Regex::new(pattern_str) {
-                if let Some(captures) = regex.captures(&text_lower) {
-                    let year_idx = order[0];
-                    let month_idx = order[1];
-                    let day_idx = order[2];
-
-                    let year = if year_idx < 999 {
-                        captures.get(year_idx + 1).and_then(|m| m.as_str().parse::<i32>().ok())
-                    } else {
-                        None
-                    };
-
-                    let month_str = if month_idx < 999 {
-                        captures.get(month_idx + 1).map(|m| m.as_str())
-                    } else {
-                        None
-                    };
-
-                    let day = if day_idx < 999 {
-                        captures.get(day_idx + 1).and_then(|m| m.as_str().parse::<u32>().ok())
-                    } else {
-                        None
-                    };
-
-                    if let Some(year_val) = year {
-                        if year_range.contains(&year_val) {
-                            let month_val = month_str.and_then(|m_str| {
-                                m_str.parse::<u32>().ok().or_else(|| {
-                                    self.month_map_ptbr
-                                        .get(m_str.trim())
-                                        .copied()
-                                })
-                            });
-
-                            if let Some(month) = month_val {
-                                if month >= 1 && month <= 12 {
-                                    let day_val = day.unwrap_or(1);
-                                    
-                                    if day_val >= 1 && day_val <= 31 {
-                                        if let Some(date) = NaiveDate::from_ymd_opt(year_val, month, day_val) {
-                                            log::info!(
-                                                "✅ Data extraída do conteúdo: {} (confidence: {:.2})",
-                                                date.format("%Y-%m-%d"),
-                                                base_confidence
-                                            );
-                                            return Some(DateDetectionResult {
-                                                value: date,
-                                                source: DateSource::Content,
-                                                confidence: base_confidence,
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                        }
+        let today = chrono::Utc::now().date_naive();
+        let best = self.rank_dates_at("", text, None, today).into_iter().next();
+        match best {
+            Some(best) => {
+                log::info!(
+                    "✅ Data extraída do conteúdo: {} (confidence: {:.2})",
+                    best.value.format("%Y-%m-%d"),
+                    best.confidence
+                );
+                Some(best.detection())
+            }
+            None => {
+                log::debug!("⚠️ Nenhuma data PT-BR encontrada no conteúdo");
+                None
+            }
+        }
+    }
+
+    fn month_number(&self, value: &str) -> Option<u32> {
+        value.parse::<u32>().ok().or_else(|| self.locale.month_number(value))
+    }
+
+    // Datas completas e mês/ano do texto, na ordem em que aparecem, sem sobreposição
+    fn date_candidates(&self, text: &str) -> Vec<DateCandidate> {
+        let table = self.locale.table();
+        let patterns = CONTENT_PATTERNS.iter().map(|&(pattern, groups, base_confidence)| {
+            // Só o padrão numérico DD/MM/YYYY muda de ordem
+            let numeric = pattern.starts_with(r"\b(\d{1,2})[/.-]");
+            (pattern, if numeric && !table.day_first { NUMERIC_MONTH_FIRST } else { groups }, base_confidence)
+        });
+        let month_first = if table.day_first { &[][..] } else { MONTH_FIRST_PATTERNS };
+
+        let mut candidates: Vec<DateCandidate> = Vec::new();
+        for (pattern, [year_group, month_group, day_group], base_confidence) in month_first.iter().copied().chain(patterns) {
+            let Ok(regex) = Regex::new(pattern) else { continue };
+            for captures in regex.captures_iter(text) {
+                let Some(whole) = captures.get(0) else { continue };
+                if candidates.iter().any(|c| whole.start() < c.end && c.start < whole.end()) {
+                    continue;
+                }
+
+                let year = captures.get(year_group).and_then(|m| m.as_str().parse::<i32>().ok());
+                let month = captures.get(month_group).and_then(|m| self.month_number(m.as_str()));
+                let day = if day_group == 0 {
+                    Some(1)
+                } else {
+                    captures.get(day_group).and_then(|m| m.as_str().parse::<u32>().ok())
+                };
+
+                let value = match (year, month, day) {
+                    (Some(year), Some(month), Some(day)) if (1900..=2100).contains(&year) => {
+                        NaiveDate::from_ymd_opt(year, month, day)
                     }
+                    _ => None,
+                };
+                if let Some(value) = value {
+                    candidates.push(DateCandidate {
+                        start: whole.start(),
+                        end: whole.end(),
+                        value,
+                        month_only: day_group == 0,
+                        base_confidence,
+                    });
                 }
             }
         }
+        candidates.sort_by_key(|candidate| candidate.start);
+        candidates
+    }
 
-        log::debug!("⚠️ Nenhuma data PT-BR encontrada no conteúdo");
-        None
+    // Papel de cada candidata (alinhado com `candidates`) pelo rótulo mais próximo antes
+    // dela na mesma linha; "Vigência: X a Y" vira início e fim
+    fn candidate_kinds(&self, text: &str, candidates: &[DateCandidate]) -> Vec<Option<DateKind>> {
+        let table = self.locale.table();
+        let mut kinds: Vec<Option<DateKind>> = vec![None; candidates.len()];
+
+        let mut index = 0;
+        while index < candidates.len() {
+            let candidate = &candidates[index];
+            let floor = if index > 0 { candidates[index - 1].end } else { 0 };
+            let line_start = text[..candidate.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            let mut window: Vec<char> = text[line_start.max(floor)..candidate.start]
+                .chars()
+                .rev()
+                .take(DATE_LABEL_WINDOW_CHARS)
+                .collect();
+            window.reverse();
+            let window: String = window.into_iter().collect();
+
+            kinds[index] = match br_validators::closest_label(&window, table.date_labels) {
+                Some("vigencia") => {
+                    // Intervalo "X a Y" / "X até Y": início e fim de uma vez
+                    let next = candidates.get(index + 1).filter(|next| {
+                        let gap = br_validators::fold(text[candidate.end..next.start].trim());
+                        table.range_connectors.contains(&gap.as_str()) && !candidate.month_only && !next.month_only
+                    });
+                    if next.is_some() {
+                        kinds[index] = Some(DateKind::VigenciaInicio);
+                        kinds[index + 1] = Some(DateKind::VigenciaFim);
+                        index += 2;
+                        continue;
+                    }
+                    let words = br_validators::fold(&window);
+                    let is_end = words.split(|c: char| !c.is_alphanumeric()).any(|word| table.end_words.contains(&word));
+                    Some(if is_end { DateKind::VigenciaFim } else { DateKind::VigenciaInicio })
+                }
+                Some(label) => DateKind::parse(label),
+                None => None,
+            };
+            index += 1;
+        }
+        kinds
     }
 
-    pub fn extract_date_auto(&self, filename: &str, content: &str) -> DateDetectionResult {
-        log::info!("🔍 Iniciando extração automática de data");
+    /// Datas com papel (emissão, vencimento, início/fim de vigência, competência) pelo
+    /// rótulo mais próximo antes de cada uma na mesma linha. Datas sem rótulo ficam de
+    /// fora; "Vigência: 01/01/2025 a 31/12/2025" vira início e fim.
+    pub fn extract_typed_dates(&self, text: &str) -> Vec<TypedDate> {
+        let candidates = self.date_candidates(text);
+        let kinds = self.candidate_kinds(text, &candidates);
+
+        let mut dates: Vec<TypedDate> = Vec::new();
+        for (candidate, kind) in candidates.iter().zip(kinds) {
+            let Some(kind) = kind else { continue };
+            // Mês/ano sozinho só faz sentido como competência
+            if candidate.month_only && kind != DateKind::Competencia {
+                continue;
+            }
+            if !dates.iter().any(|date| date.kind == kind && date.value == candidate.value) {
+                dates.push(TypedDate {
+                    kind,
+                    value: candidate.value,
+                    raw: text[candidate.start..candidate.end].to_string(),
+                    position: candidate.start,
+                });
+            }
+        }
+
+        log::debug!("📅 {} datas com papel encontradas no conteúdo", dates.len());
+        dates
+    }
+
+    /// Todas as datas do nome do arquivo e do conteúdo, pontuadas e da mais para a menos
+    /// provável. A pontuação parte da confiança do padrão e considera o rótulo próximo,
+    /// o tipo de documento, a repetição da data e se ela é plausível.
+    pub fn rank_dates(&self, filename: &str, content: &str, document_type: Option<&str>) -> Vec<RankedDate> {
+        self.rank_dates_at(filename, content, document_type, chrono::Utc::now().date_naive())
+    }
+
+    fn rank_dates_at(&self, filename: &str, content: &str, document_type: Option<&str>, today: NaiveDate) -> Vec<RankedDate> {
+        let plausible = (today.year() - 10)..=(today.year() + 1);
+        let preferred = document_type.and_then(preferred_kind);
+
+        let mut found: Vec<RankedDate> = Vec::new();
+        for candidate in self.filename_candidates(filename) {
+            let raw = filename[candidate.start..candidate.end].to_string();
+            found.push(RankedDate {
+                value: candidate.value,
+                source: DateSource::Filename,
+                confidence: candidate.base_confidence,
+                kind: None,
+                reasons: vec![format!("'{}' no nome do arquivo ({:.2})", raw, candidate.base_confidence)],
+                raw,
+                position: candidate.start,
+                context: filename.to_string(),
+                occurrences: 1,
+            });
+        }
+
+        let candidates = self.date_candidates(content);
+        let kinds = self.candidate_kinds(content, &candidates);
+        for (candidate, kind) in candidates.iter().zip(kinds) {
+            let raw = content[candidate.start..candidate.end].to_string();
+            let mut confidence = candidate.base_confidence;
+            let mut reasons = vec![format!("'{}' no conteúdo ({:.2})", raw, candidate.base_confidence)];
+
+            if let Some(kind) = kind {
+                confidence += kind_bonus(kind);
+                reasons.push(format!("rótulo de {} antes da data", kind.as_str()));
+                if preferred == Some(kind) {
+                    confidence += 0.05;
+                    reasons.push(format!("{} costuma ser datado pela {}", document_type.unwrap_or_default(), kind.as_str()));
+                }
+            }
+            if candidate.month_only {
+                reasons.push("só mês/ano (dia 1 assumido)".to_string());
+            }
+
+            found.push(RankedDate {
+                value: candidate.value,
+                source: DateSource::Content,
+                confidence,
+                kind,
+                raw,
+                position: candidate.start,
+                context: context_around(content, candidate.start, candidate.end),
+                occurrences: 1,
+                reasons,
+            });
+        }
+
+        for date in &mut found {
+            if !plausible.contains(&date.value.year()) {
+                date.confidence -= IMPLAUSIBLE_PENALTY;
+                date.reasons.push(format!("fora do período plausível ({}–{})", plausible.start(), plausible.end()));
+            }
+        }
+
+        // Uma entrada por data: a ocorrência mais bem pontuada, somando as repetições
+        found.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        let mut ranked: Vec<RankedDate> = Vec::new();
+        let mut sources: Vec<Vec<DateSource>> = Vec::new();
+        for date in found {
+            match ranked.iter().position(|r| r.value == date.value) {
+                Some(index) => {
+                    ranked[index].occurrences += 1;
+                    sources[index].push(date.source);
+                }
+                None => {
+                    sources.push(vec![date.source.clone()]);
+                    ranked.push(date);
+                }
+            }
+        }
 
-        if let Some(result) = self.extract_date_from_filename(filename) {
-            log::info!("✅ Data extraída do filename com confidence {:.2}", result.confidence);
-            return result;
+        for (date, sources) in ranked.iter_mut().zip(&sources) {
+            if date.occurrences > 1 {
+                date.confidence += (REPEAT_BONUS * (date.occurrences - 1) as f32).min(MAX_REPEAT_BONUS);
+                date.reasons.push(format!("aparece {} vezes", date.occurrences));
+            }
+            if sources.contains(&DateSource::Filename) && sources.contains(&DateSource::Content) {
+                date.reasons.push("nome do arquivo e conteúdo concordam".to_string());
+            }
+            date.confidence = date.confidence.clamp(0.0, 1.0);
         }
 
-        if let Some(result) = self.extract_date_from_content_ptbr(content) {
-            log::info!("✅ Data extraída do conteúdo com confidence {:.2}", result.confidence);
-            return result;
+        ranked.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        log::debug!("📅 {} datas candidatas ranqueadas", ranked.len());
+        ranked
+    }
+
+    pub fn extract_date_auto(&self, filename: &str, content: &str) -> DateDetectionResult {
+        self.extract_date_ranked(filename, content, None).0
+    }
+
+    /// Melhor data (ou hoje, com confiança baixa, se não houver nenhuma) e a lista
+    /// completa de candidatas para oferecer alternativas
+    pub fn extract_date_ranked(
+        &self,
+        filename: &str,
+        content: &str,
+        document_type: Option<&str>,
+    ) -> (DateDetectionResult, Vec<RankedDate>) {
+        log::info!("🔍 Iniciando extração automática de data");
+
+        let ranked = self.rank_dates(filename, content, document_type);
+        if let Some(best) = ranked.first() {
+            log::info!(
+                "✅ Data {} escolhida entre {} candidatas ({:?}, confidence {:.2})",
+                best.value.format("%Y-%m-%d"),
+                ranked.len(),
+                best.source,
+                best.confidence
+            );
+            return (best.detection(), ranked);
         }
 
         let today = chrono::Utc::now().date_naive();
         log::warn!("⚠️ Usando data atual como fallback: {}", today.format("%Y-%m-%d"));
-        
-        DateDetectionResult {
+
+        let fallback = DateDetectionResult {
             value: today,
             source: DateSource::Fallback,
             confidence: 0.1,
-        }
+        };
+        (fallback, ranked)
     }
 }
 
@@ -369,6 +701,102 @@ mod tests {
         assert_eq!(result.source, DateSource::Content);
     }
 
+    #[test]
+    fn test_extract_typed_dates_from_boleto() {
+        let extractor = DateExtractor::new();
+        let content = "Data do Documento: 01/10/2025  Vencimento: 10/10/2025\nValor: R$ 150,00\nAutenticado em 02/10/2025";
+        let dates = extractor.extract_typed_dates(content);
+        let summary: Vec<(DateKind, String)> =
+            dates.iter().map(|d| (d.kind, d.value.format("%Y-%m-%d").to_string())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DateKind::Emissao, "2025-10-01".to_string()),
+                (DateKind::Vencimento, "2025-10-10".to_string()),
+            ]
+        );
+        assert_eq!(dates[1].raw, "10/10/2025");
+    }
+
+    #[test]
+    fn test_extract_typed_dates_validity_and_competence() {
+        let extractor = DateExtractor::new();
+        let content = "Contrato emitido em 4 de outubro de 2025.\nVigência: 01/11/2025 a 31/10/2026\n\
+                       Competência: 09/2025\nFim da vigência original: 2025-12-31\nPublicado em outubro de 2025";
+        let dates = extractor.extract_typed_dates(content);
+        let summary: Vec<(&str, String)> =
+            dates.iter().map(|d| (d.kind.as_str(), d.value.format("%Y-%m-%d").to_string())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("emissao", "2025-10-04".to_string()),
+                ("vigencia_inicio", "2025-11-01".to_string()),
+                ("vigencia_fim", "2026-10-31".to_string()),
+                ("competencia", "2025-09-01".to_string()),
+                ("vigencia_fim", "2025-12-31".to_string()),
+            ]
+        );
+        assert_eq!(DateKind::parse("vigencia_fim"), Some(DateKind::VigenciaFim));
+    }
+
+    #[test]
+    fn test_filename_ignores_digits_of_longer_numbers() {
+        let extractor = DateExtractor::new();
+        assert!(extractor.extract_date_from_filename("NF_123456789.pdf").is_none());
+
+        // O número da nota não vira data, mas a data ISO ao lado sim
+        let result = extractor.extract_date_from_filename("NF_1204251_2025-10-04.pdf").unwrap();
+        assert_eq!(result.value, NaiveDate::from_ymd_opt(2025, 10, 4).unwrap());
+    }
+
+    #[test]
+    fn test_rank_dates_prefers_labelled_and_repeated_dates() {
+        let extractor = DateExtractor::new();
+        let today = NaiveDate::from_ymd_opt(2025, 11, 1).unwrap();
+        let content = "Impresso em 20/10/2025 às 10:00\nNota Fiscal\nData de emissão: 15/10/2025\n\
+                       Vencimento: 30/10/2025\nReferente ao pedido de 15/10/2025\nCliente desde 01/01/1990";
+        let ranked = extractor.rank_dates_at("nota.pdf", content, Some("nota_fiscal"), today);
+
+        let order: Vec<String> = ranked.iter().map(|d| d.value.format("%Y-%m-%d").to_string()).collect();
+        assert_eq!(order, vec!["2025-10-15", "2025-10-30", "2025-10-20", "1990-01-01"]);
+
+        let best = &ranked[0];
+        assert_eq!(best.kind, Some(DateKind::Emissao));
+        assert_eq!(best.occurrences, 2);
+        assert_eq!(best.raw, "15/10/2025");
+        assert!(best.context.contains("Data de emissão"));
+        assert!(best.reasons.iter().any(|reason| reason.contains("2 vezes")));
+        assert!(ranked[3].reasons.iter().any(|reason| reason.contains("plausível")));
+        assert!(ranked.windows(2).all(|pair| pair[0].confidence >= pair[1].confidence));
+    }
+
+    #[test]
+    fn test_english_and_spanish_content() {
+        let today = NaiveDate::from_ymd_opt(2025, 11, 1).unwrap();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let english = "Invoice date: October 4, 2025\nDue date: 11/03/2025\nBilling period: Oct 2025";
+        let extractor = DateExtractor::for_text(english);
+        assert_eq!(extractor.locale(), DateLocale::En);
+        let typed: Vec<(DateKind, NaiveDate)> =
+            extractor.extract_typed_dates(english).iter().map(|d| (d.kind, d.value)).collect();
+        assert_eq!(
+            typed,
+            vec![
+                (DateKind::Emissao, date(2025, 10, 4)),
+                (DateKind::Vencimento, date(2025, 11, 3)),
+                (DateKind::Competencia, date(2025, 10, 1)),
+            ]
+        );
+
+        let spanish = "Factura emitida el 4 de octubre de 2025. Fecha de vencimiento: 03/11/2025";
+        let extractor = DateExtractor::for_locale(DateLocale::Es);
+        let ranked = extractor.rank_dates_at("factura.pdf", spanish, None, today);
+        assert_eq!(ranked[0].value, date(2025, 10, 4));
+        assert_eq!(ranked[1].value, date(2025, 11, 3));
+        assert_eq!(ranked[1].kind, Some(DateKind::Vencimento));
+    }
+
     #[test]
     fn test_extract_auto_fallback_to_today() {
         let extractor = DateExtractor::new();
@@ -0,0 +1,318 @@
+// TABELA DE IDIOMAS PARA DATAS (PT-BR, INGLÊS E ESPANHOL)
+// Meses, conectores e rótulos usados tanto pela extração de datas dos documentos
+// (DateExtractor) quanto pela busca por data (DateSearchParser). O idioma de cada
+// documento é detectado pelas palavras mais frequentes do texto.
+
+use serde::{Deserialize, Serialize};
+
+use crate::br_validators;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum DateLocale {
+    #[serde(rename = "pt-BR")]
+    PtBr,
+    #[serde(rename = "en")]
+    En,
+    #[serde(rename = "es")]
+    Es,
+}
+
+/// Períodos relativos ao dia de hoje ("ontem", "semana passada", "este mês")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelativePeriod {
+    Today,
+    Yesterday,
+    ThisWeek,
+    LastWeek,
+    ThisMonth,
+    LastMonth,
+    ThisYear,
+    LastYear,
+}
+
+/// Unidade de "últimos N dias/semanas/meses/anos"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeriodUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+pub struct LocaleTable {
+    // Nomes completos dos meses, sem acentos e em minúsculas
+    pub months: &'static [(&'static str, u32)],
+    // Abreviações (só valem junto de dia ou ano: "4 de out", "Oct 2025")
+    pub abbreviations: &'static [(&'static str, u32)],
+    // Dia antes do mês nas datas numéricas (04/10/2025); false = MM/DD/YYYY
+    pub day_first: bool,
+    // Palavras que podem acompanhar uma data numa busca ("dia 4 de outubro", "on October 4")
+    pub search_connectors: &'static [&'static str],
+    // Antes de um mês sozinho numa busca com texto ("notas de outubro"); sem elas o nome
+    // do mês fica no texto ("contrato Marco Aurélio")
+    pub month_prepositions: &'static [&'static str],
+    // Entre as duas datas de um intervalo ("01/01/2025 a 31/12/2025")
+    pub range_connectors: &'static [&'static str],
+    // Palavras que indicam fim de vigência junto do rótulo genérico ("fim da vigência")
+    pub end_words: &'static [&'static str],
+    // Rótulo -> papel da data (ver date_extractor::DateKind; "vigencia" é refinado depois)
+    pub date_labels: &'static [(&'static str, &'static str)],
+    // Palavras frequentes usadas para detectar o idioma do texto
+    pub stopwords: &'static [&'static str],
+    // Expressões relativas de busca ("semana passada", "last month")
+    pub relative_periods: &'static [(&'static str, RelativePeriod)],
+    // "últimos" em "últimos 30 dias"
+    pub last_words: &'static [&'static str],
+    pub period_units: &'static [(&'static str, PeriodUnit)],
+    pub quarter_words: &'static [&'static str],
+    // Abrem um intervalo na busca ("entre 01/02 e 15/02", "de março a maio")
+    pub range_openers: &'static [&'static str],
+    // Separam as duas pontas de um intervalo aberto por "entre"
+    pub and_words: &'static [&'static str],
+}
+
+const PT_BR: LocaleTable = LocaleTable {
+    months: &[
+        ("janeiro", 1), ("fevereiro", 2), ("marco", 3), ("abril", 4), ("maio", 5), ("junho", 6),
+        ("julho", 7), ("agosto", 8), ("setembro", 9), ("outubro", 10), ("novembro", 11), ("dezembro", 12),
+    ],
+    abbreviations: &[
+        ("jan", 1), ("fev", 2), ("mar", 3), ("abr", 4), ("mai", 5), ("jun", 6),
+        ("jul", 7), ("ago", 8), ("set", 9), ("out", 10), ("nov", 11), ("dez", 12),
+    ],
+    day_first: true,
+    search_connectors: &["dia", "de", "em", "do", "da", "nos", "nas"],
+    month_prepositions: &["de", "em", "do", "desde"],
+    range_connectors: &["a", "ate", "-", "–"],
+    end_words: &["fim", "final", "ate"],
+    date_labels: &[
+        ("emissao", "emissao"),
+        ("emitido", "emissao"),
+        ("emitida", "emissao"),
+        ("data do documento", "emissao"),
+        ("vencimento", "vencimento"),
+        ("venc", "vencimento"),
+        ("vence", "vencimento"),
+        ("pagavel ate", "vencimento"),
+        ("pagar ate", "vencimento"),
+        ("data limite", "vencimento"),
+        ("vigencia", "vigencia"),
+        ("vigente", "vigencia"),
+        ("periodo", "vigencia"),
+        ("a partir de", "vigencia_inicio"),
+        ("validade", "vigencia_fim"),
+        ("valido ate", "vigencia_fim"),
+        ("termino", "vigencia_fim"),
+        ("encerramento", "vigencia_fim"),
+        ("competencia", "competencia"),
+        ("referencia", "competencia"),
+        ("mes de referencia", "competencia"),
+        ("periodo de apuracao", "competencia"),
+    ],
+    stopwords: &["do", "da", "dos", "das", "nao", "com", "uma", "pelo", "pela", "seu", "sua", "voce", "sao", "nota", "valor"],
+    relative_periods: &[
+        ("hoje", RelativePeriod::Today),
+        ("ontem", RelativePeriod::Yesterday),
+        ("esta semana", RelativePeriod::ThisWeek),
+        ("nesta semana", RelativePeriod::ThisWeek),
+        ("semana passada", RelativePeriod::LastWeek),
+        ("este mes", RelativePeriod::ThisMonth),
+        ("neste mes", RelativePeriod::ThisMonth),
+        ("mes passado", RelativePeriod::LastMonth),
+        ("este ano", RelativePeriod::ThisYear),
+        ("neste ano", RelativePeriod::ThisYear),
+        ("ano passado", RelativePeriod::LastYear),
+    ],
+    last_words: &["ultimos", "ultimas"],
+    period_units: &[
+        ("dias", PeriodUnit::Day), ("semanas", PeriodUnit::Week), ("meses", PeriodUnit::Month), ("anos", PeriodUnit::Year),
+    ],
+    quarter_words: &["trimestre"],
+    range_openers: &["entre", "de", "desde"],
+    and_words: &["e"],
+};
+
+const EN: LocaleTable = LocaleTable {
+    months: &[
+        ("january", 1), ("february", 2), ("march", 3), ("april", 4), ("may", 5), ("june", 6),
+        ("july", 7), ("august", 8), ("september", 9), ("october", 10), ("november", 11), ("december", 12),
+    ],
+    abbreviations: &[
+        ("jan", 1), ("feb", 2), ("mar", 3), ("apr", 4), ("jun", 6), ("jul", 7),
+        ("aug", 8), ("sep", 9), ("sept", 9), ("oct", 10), ("nov", 11), ("dec", 12),
+    ],
+    day_first: false,
+    search_connectors: &["on", "of", "in", "the", "st", "nd", "rd", "th"],
+    month_prepositions: &["in", "of", "since"],
+    range_connectors: &["to", "until", "through", "-", "–"],
+    end_words: &["end", "until", "expiry", "expiration"],
+    date_labels: &[
+        ("issue date", "emissao"),
+        ("date of issue", "emissao"),
+        ("issued", "emissao"),
+        ("invoice date", "emissao"),
+        ("due date", "vencimento"),
+        ("due", "vencimento"),
+        ("payment due", "vencimento"),
+        ("pay by", "vencimento"),
+        ("period", "vigencia"),
+        ("term", "vigencia"),
+        ("valid from", "vigencia_inicio"),
+        ("effective date", "vigencia_inicio"),
+        ("start date", "vigencia_inicio"),
+        ("valid until", "vigencia_fim"),
+        ("valid through", "vigencia_fim"),
+        ("expiry date", "vigencia_fim"),
+        ("expiration date", "vigencia_fim"),
+        ("end date", "vigencia_fim"),
+        ("billing period", "competencia"),
+        ("statement period", "competencia"),
+    ],
+    stopwords: &["the", "and", "of", "to", "for", "with", "is", "are", "this", "that", "your", "invoice", "date", "total"],
+    relative_periods: &[
+        ("today", RelativePeriod::Today),
+        ("yesterday", RelativePeriod::Yesterday),
+        ("this week", RelativePeriod::ThisWeek),
+        ("last week", RelativePeriod::LastWeek),
+        ("this month", RelativePeriod::ThisMonth),
+        ("last month", RelativePeriod::LastMonth),
+        ("this year", RelativePeriod::ThisYear),
+        ("last year", RelativePeriod::LastYear),
+    ],
+    last_words: &["last", "past"],
+    period_units: &[
+        ("days", PeriodUnit::Day), ("weeks", PeriodUnit::Week), ("months", PeriodUnit::Month), ("years", PeriodUnit::Year),
+    ],
+    quarter_words: &["quarter"],
+    range_openers: &["between", "from"],
+    and_words: &["and"],
+};
+
+const ES: LocaleTable = LocaleTable {
+    months: &[
+        ("enero", 1), ("febrero", 2), ("marzo", 3), ("abril", 4), ("mayo", 5), ("junio", 6),
+        ("julio", 7), ("agosto", 8), ("septiembre", 9), ("setiembre", 9), ("octubre", 10), ("noviembre", 11),
+        ("diciembre", 12),
+    ],
+    abbreviations: &[
+        ("ene", 1), ("feb", 2), ("mar", 3), ("abr", 4), ("may", 5), ("jun", 6),
+        ("jul", 7), ("ago", 8), ("sep", 9), ("sept", 9), ("oct", 10), ("nov", 11), ("dic", 12),
+    ],
+    day_first: true,
+    search_connectors: &["dia", "de", "del", "en", "el", "la", "los", "las"],
+    month_prepositions: &["de", "en", "del", "desde"],
+    range_connectors: &["a", "al", "hasta", "-", "–"],
+    end_words: &["fin", "final", "hasta"],
+    date_labels: &[
+        ("fecha de emision", "emissao"),
+        ("emision", "emissao"),
+        ("emitido", "emissao"),
+        ("emitida", "emissao"),
+        ("vencimiento", "vencimento"),
+        ("vence", "vencimento"),
+        ("fecha limite", "vencimento"),
+        ("vigencia", "vigencia"),
+        ("periodo", "vigencia"),
+        ("a partir de", "vigencia_inicio"),
+        ("fecha de inicio", "vigencia_inicio"),
+        ("valido hasta", "vigencia_fim"),
+        ("fecha de fin", "vigencia_fim"),
+        ("caducidad", "vigencia_fim"),
+        ("periodo de facturacion", "competencia"),
+        ("mes de referencia", "competencia"),
+    ],
+    stopwords: &["el", "los", "las", "del", "y", "con", "una", "su", "usted", "fecha", "factura", "hasta", "por"],
+    relative_periods: &[
+        ("hoy", RelativePeriod::Today),
+        ("ayer", RelativePeriod::Yesterday),
+        ("esta semana", RelativePeriod::ThisWeek),
+        ("semana pasada", RelativePeriod::LastWeek),
+        ("este mes", RelativePeriod::ThisMonth),
+        ("mes pasado", RelativePeriod::LastMonth),
+        ("este año", RelativePeriod::ThisYear),
+        ("año pasado", RelativePeriod::LastYear),
+    ],
+    last_words: &["ultimos", "ultimas"],
+    period_units: &[
+        ("dias", PeriodUnit::Day), ("semanas", PeriodUnit::Week), ("meses", PeriodUnit::Month), ("años", PeriodUnit::Year),
+    ],
+    quarter_words: &["trimestre"],
+    range_openers: &["entre", "de", "desde"],
+    and_words: &["y"],
+};
+
+// Mínimo de palavras frequentes para trocar o idioma padrão (PT-BR) por outro
+const MIN_LANGUAGE_HITS: usize = 3;
+
+impl DateLocale {
+    pub const ALL: [DateLocale; 3] = [DateLocale::PtBr, DateLocale::En, DateLocale::Es];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateLocale::PtBr => "pt-BR",
+            DateLocale::En => "en",
+            DateLocale::Es => "es",
+        }
+    }
+
+    pub fn table(&self) -> &'static LocaleTable {
+        match self {
+            DateLocale::PtBr => &PT_BR,
+            DateLocale::En => &EN,
+            DateLocale::Es => &ES,
+        }
+    }
+
+    /// Número do mês pelo nome completo ou abreviado ("Outubro", "octubre", "Oct.")
+    pub fn month_number(&self, word: &str) -> Option<u32> {
+        let word = br_validators::fold(word.trim().trim_end_matches('.'));
+        let table = self.table();
+        table
+            .months
+            .iter()
+            .chain(table.abbreviations)
+            .find(|(name, _)| *name == word)
+            .map(|(_, month)| *month)
+    }
+}
+
+/// Idioma mais provável do texto pelas palavras frequentes de cada um; PT-BR quando
+/// nenhum outro se destaca
+pub fn detect_locale(text: &str) -> DateLocale {
+    let folded = br_validators::fold(text);
+    let words: Vec<&str> = folded.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+
+    let hits = |locale: DateLocale| words.iter().filter(|word| locale.table().stopwords.contains(word)).count();
+    let portuguese = hits(DateLocale::PtBr);
+
+    let best = [DateLocale::En, DateLocale::Es]
+        .into_iter()
+        .map(|locale| (locale, hits(locale)))
+        .max_by_key(|(_, count)| *count);
+
+    match best {
+        Some((locale, count)) if count >= MIN_LANGUAGE_HITS && count > portuguese => locale,
+        _ => DateLocale::PtBr,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_lookup_per_locale() {
+        assert_eq!(DateLocale::PtBr.month_number("Março"), Some(3));
+        assert_eq!(DateLocale::En.month_number("Oct."), Some(10));
+        assert_eq!(DateLocale::Es.month_number("octubre"), Some(10));
+        assert_eq!(DateLocale::En.month_number("outubro"), None);
+    }
+
+    #[test]
+    fn test_detect_locale() {
+        assert_eq!(detect_locale("Invoice date: October 4, 2025. Payment is due within 30 days of the invoice date."), DateLocale::En);
+        assert_eq!(detect_locale("Factura del mes. La fecha de vencimiento es el 4 de octubre de 2025 y el total con IVA."), DateLocale::Es);
+        assert_eq!(detect_locale("Nota fiscal emitida em 4 de outubro de 2025 com valor da compra"), DateLocale::PtBr);
+        assert_eq!(detect_locale("2025"), DateLocale::PtBr);
+    }
+}
@@ -1,6 +1,9 @@
-use chrono::{NaiveDate, Datelike, Duration};
+use chrono::{NaiveDate, Datelike, Duration, Months};
 use regex::Regex;
-use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::br_validators;
+use crate::date_locale::{DateLocale, PeriodUnit, RelativePeriod};
 
 #[derive(Debug, Clone)]
 pub struct DateSearchQuery {
@@ -15,152 +18,466 @@ pub enum DateQueryType {
     Month,           // "outubro 2025" ou "outubro"
     DayAndMonth,     // "4 de outubro"
     TextualDate,     // "dia 4 de outubro de 2025"
+    Relative,        // "ontem", "semana passada", "este mês"
+    LastPeriod,      // "últimos 30 dias"
+    Quarter,         // "1º trimestre 2024"
+    MonthRange,      // "de março a maio de 2025"
+    DateRange,       // "entre 01/02 e 15/02"
+}
+
+/// Query separada em data e texto: "nota fiscal outubro 2025" -> "nota fiscal" + outubro/2025
+#[derive(Debug, Clone)]
+pub struct DateSplit {
+    pub date: DateSearchQuery,
+    // Restante da query, sem a data e os conectores junto dela (vazio se a query é só data)
+    pub text: String,
+}
+
+// Nomes para exibir o período entendido ("outubro/2025")
+const MONTH_NAMES: [&str; 12] = [
+    "janeiro", "fevereiro", "março", "abril", "maio", "junho",
+    "julho", "agosto", "setembro", "outubro", "novembro", "dezembro",
+];
+
+impl DateSearchQuery {
+    /// Período em português para mostrar ao usuário: "04/10/2025", "outubro/2025",
+    /// "março/2025 a maio/2025" ou "01/02/2025 a 15/02/2025"
+    pub fn describe(&self) -> String {
+        let whole_month = |start: NaiveDate, end: NaiveDate| {
+            start.day() == 1 && (end + Duration::days(1)).day() == 1
+        };
+        let month_name = |date: NaiveDate| format!("{}/{}", MONTH_NAMES[date.month0() as usize], date.year());
+
+        if self.start_date == self.end_date {
+            self.start_date.format("%d/%m/%Y").to_string()
+        } else if whole_month(self.start_date, self.end_date) {
+            if (self.start_date.year(), self.start_date.month()) == (self.end_date.year(), self.end_date.month()) {
+                month_name(self.start_date)
+            } else {
+                format!("{} a {}", month_name(self.start_date), month_name(self.end_date))
+            }
+        } else {
+            format!("{} a {}", self.start_date.format("%d/%m/%Y"), self.end_date.format("%d/%m/%Y"))
+        }
+    }
 }
 
+// Intervalo encontrado na query e o trecho (em bytes) que ele ocupa
+type RangeMatch = (DateSearchQuery, Range<usize>);
+type RangeParser = fn(&DateSearchParser, &str, DateLocale, NaiveDate) -> Option<RangeMatch>;
+
 pub struct DateSearchParser {
-    month_map_ptbr: HashMap<String, u32>,
+    // Idiomas tentados em ordem; o primeiro que reconhecer a query vence
+    locales: Vec<DateLocale>,
 }
 
 impl DateSearchParser {
+    /// Português primeiro (04/10/2025 = 4 de outubro), depois inglês e espanhol
     pub fn new() -> Self {
-        let mut month_map_ptbr = HashMap::new();
-        
-        // Meses completos
-        month_map_ptbr.insert("janeiro".to_string(), 1);
-        month_map_ptbr.insert("fevereiro".to_string(), 2);
-        month_map_ptbr.insert("março".to_string(), 3);
-        month_map_ptbr.insert("marco".to_string(), 3);
-        month_map_ptbr.insert("abril".to_string(), 4);
-        month_map_ptbr.insert("maio".to_string(), 5);
-        month_map_ptbr.insert("junho".to_string(), 6);
-        month_map_ptbr.insert("julho".to_string(), 7);
-        month_map_ptbr.insert("agosto".to_string(), 8);
-        month_map_ptbr.insert("setembro".to_string(), 9);
-        month_map_ptbr.insert("outubro".to_string(), 10);
-        month_map_ptbr.insert("novembro".to_string(), 11);
-        month_map_ptbr.insert("dezembro".to_string(), 12);
-        
-        // Abreviações
-        month_map_ptbr.insert("jan".to_string(), 1);
-        month_map_ptbr.insert("fev".to_string(), 2);
-        month_map_ptbr.insert("mar".to_string(), 3);
-        month_map_ptbr.insert("abr".to_string(), 4);
-        month_map_ptbr.insert("mai".to_string(), 5);
-        month_map_ptbr.insert("jun".to_string(), 6);
-        month_map_ptbr.insert("jul".to_string(), 7);
-        month_map_ptbr.insert("ago".to_string(), 8);
-        month_map_ptbr.insert("set".to_string(), 9);
-        month_map_ptbr.insert("out".to_string(), 10);
-        month_map_ptbr.insert("nov".to_string(), 11);
-        month_map_ptbr.insert("dez".to_string(), 12);
-
-        DateSearchParser { month_map_ptbr }
+        Self::with_locales(&DateLocale::ALL)
+    }
+
+    pub fn with_locales(locales: &[DateLocale]) -> Self {
+        DateSearchParser { locales: locales.to_vec() }
     }
 
     /// Detecta se a query é uma busca PURAMENTE por data (sem texto adicional)
     /// Retorna None se a query contém palavras além de componentes de data
     pub fn parse(&self, query: &str) -> Option<DateSearchQuery> {
-        let query_lower = query.to_lowercase().trim().to_string();
-        
+        self.parse_at(query, chrono::Local::now().date_naive())
+    }
+
+    /// Igual a `parse`, com o dia de "hoje" informado: base das expressões relativas
+    /// ("ontem", "últimos 30 dias") e do ano quando a query não traz ano
+    pub fn parse_at(&self, query: &str, today: NaiveDate) -> Option<DateSearchQuery> {
+        // Minúsculas e sem acentos: "Março" e "marco" são o mesmo mês
+        let query_lower = br_validators::fold(query).trim().to_string();
+
         log::debug!("🔍 Analisando query de data: '{}'", query_lower);
 
+        for &locale in &self.locales {
+            if let Some(result) = self.parse_in_locale(&query_lower, locale, today) {
+                return Some(result);
+            }
+        }
+
+        log::debug!("⚠️ Query não é busca PURA por data (pode ter texto adicional)");
+        None
+    }
+
+    /// Separa a data do texto numa query mista ("nota fiscal outubro 2025"). Query só de
+    /// data volta com texto vazio; sem data reconhecida, None
+    pub fn split(&self, query: &str) -> Option<DateSplit> {
+        self.split_at(query, chrono::Local::now().date_naive())
+    }
+
+    pub fn split_at(&self, query: &str, today: NaiveDate) -> Option<DateSplit> {
+        // Query pura segue exatamente as regras de `parse`
+        if let Some(date) = self.parse_at(query, today) {
+            return Some(DateSplit { date, text: String::new() });
+        }
+
+        // Palavras originais (com acentos, para a busca por texto) e sem acentos (para a data)
+        let words: Vec<&str> = query.split_whitespace().collect();
+        let folded_words: Vec<String> = words.iter().map(|word| br_validators::fold(word)).collect();
+        let folded = folded_words.join(" ");
+        let mut word_starts = Vec::with_capacity(words.len());
+        let mut offset = 0;
+        for word in &folded_words {
+            word_starts.push(offset);
+            offset += word.len() + 1;
+        }
+
+        for &locale in &self.locales {
+            let Some((date, span)) = self.find_in_locale(&folded, locale, today) else { continue };
+
+            // Palavras tocadas pela data saem inteiras, junto dos conectores vizinhos
+            // ("notas de outubro 2025" -> "notas")
+            let covered: Vec<usize> = (0..words.len())
+                .filter(|&i| word_starts[i] < span.end && span.start < word_starts[i] + folded_words[i].len())
+                .collect();
+            let (Some(&first), Some(&last)) = (covered.first(), covered.last()) else { continue };
+            let connectors = locale.table().search_connectors;
+            let mut start = first;
+            while start > 0 && connectors.contains(&folded_words[start - 1].as_str()) {
+                start -= 1;
+            }
+            let mut end = last + 1;
+            while end < words.len() && connectors.contains(&folded_words[end].as_str()) {
+                end += 1;
+            }
+
+            let text = words[..start].iter().chain(&words[end..]).copied().collect::<Vec<_>>().join(" ");
+            log::info!("✅ Query mista ({}): texto '{}' + {:?}", locale.as_str(), text, date);
+            return Some(DateSplit { date, text });
+        }
+
+        None
+    }
+
+    /// Primeira data da query em qualquer posição, na mesma ordem de `parse_in_locale`;
+    /// só vale se não estiver colada em outra palavra ou número ("setor", "120042025")
+    fn find_in_locale(&self, query: &str, locale: DateLocale, today: NaiveDate) -> Option<RangeMatch> {
+        let isolated = |span: &Range<usize>| {
+            let before = query[..span.start].chars().next_back();
+            let after = query[span.end..].chars().next();
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+        };
+
+        // Mês sozinho no meio de texto pode ser nome ("Marco Aurélio", "may contract"):
+        // só vale logo depois de uma preposição ("notas de outubro")
+        let month_only = self.parse_month_only(query, locale, today).filter(|(_, span)| {
+            query[..span.start]
+                .split_whitespace()
+                .next_back()
+                .is_some_and(|word| locale.table().month_prepositions.contains(&word))
+        });
+
+        let candidates = [
+            self.parse_date_range(query, locale, today),
+            self.parse_month_range(query, locale, today),
+            self.parse_quarter(query, locale, today),
+            self.parse_last_period(query, locale, today),
+            self.parse_relative(query, locale, today),
+            self.parse_numeric_date(query, locale),
+            self.parse_textual_date(query, locale, today),
+            self.parse_month_year(query, locale),
+            month_only,
+        ];
+        candidates.into_iter().flatten().find(|(_, span)| isolated(span))
+    }
+
+    fn parse_in_locale(&self, query_lower: &str, locale: DateLocale, today: NaiveDate) -> Option<DateSearchQuery> {
         // IMPORTANTE: Só tratar como date-only se a query for PURAMENTE data
         // Queries mistas como "rastreabilidade outubro" devem ir para FTS5
-        
+
+        // 0. Intervalos e expressões relativas: "entre 01/02 e 15/02", "de março a maio de 2025",
+        //    "1º trimestre 2024", "últimos 30 dias", "semana passada"
+        // (antes das datas simples, que reconheceriam só uma das pontas do intervalo)
+        let range_parsers: [RangeParser; 5] = [
+            Self::parse_date_range,
+            Self::parse_month_range,
+            Self::parse_quarter,
+            Self::parse_last_period,
+            Self::parse_relative,
+        ];
+        for range_parser in range_parsers {
+            if let Some((result, span)) = range_parser(self, query_lower, locale, today) {
+                // Fora do intervalo só podem sobrar conectores ("nos últimos 30 dias")
+                let rest = format!("{} {}", &query_lower[..span.start], &query_lower[span.end..]);
+                if self.is_only_connectors(&rest, locale) {
+                    log::info!("✅ Detectado intervalo puro ({}): {:?}", locale.as_str(), result);
+                    return Some(result);
+                }
+            }
+        }
+
         // 1. Data completa numérica: "04/10/2025", "04-10-2025", "2025-10-04"
-        if let Some(result) = self.parse_numeric_date(&query_lower) {
+        if let Some((result, _)) = self.parse_numeric_date(query_lower, locale) {
             // Verificar se a query tem APENAS a data (sem palavras extras)
-            if self.is_pure_date_query(&query_lower, &["dia", "de", "em", "do", "da"]) {
-                log::info!("✅ Detectada data numérica pura: {:?}", result);
+            if self.is_pure_date_query(query_lower, locale) {
+                log::info!("✅ Detectada data numérica pura ({}): {:?}", locale.as_str(), result);
                 return Some(result);
             }
         }
 
-        // 2. Texto natural: "dia 4 de outubro de 2025", "4 de outubro"
-        if let Some(result) = self.parse_textual_date(&query_lower) {
+        // 2. Texto natural: "dia 4 de outubro de 2025", "4 de outubro", "October 4, 2025"
+        if let Some((result, _)) = self.parse_textual_date(query_lower, locale, today) {
             // Verificar se não tem palavras além de "dia", "de", números e mês
-            if self.is_pure_date_query(&query_lower, &["dia", "de", "em", "do", "da"]) {
-                log::info!("✅ Detectada data textual pura: {:?}", result);
+            if self.is_pure_date_query(query_lower, locale) {
+                log::info!("✅ Detectada data textual pura ({}): {:?}", locale.as_str(), result);
                 return Some(result);
             }
         }
 
-        // 3. Mês e ano: "outubro 2025", "outubro de 2025"
-        if let Some(result) = self.parse_month_year(&query_lower) {
+        // 3. Mês e ano: "outubro 2025", "outubro de 2025", "Oct 2025"
+        if let Some((result, _)) = self.parse_month_year(query_lower, locale) {
             // Verificar se tem APENAS mês e ano
-            if self.is_pure_date_query(&query_lower, &["de", "em", "do", "da"]) {
-                log::info!("✅ Detectado mês/ano puro: {:?}", result);
+            if self.is_pure_date_query(query_lower, locale) {
+                log::info!("✅ Detectado mês/ano puro ({}): {:?}", locale.as_str(), result);
                 return Some(result);
             }
         }
 
         // 4. Apenas mês: "outubro", "maio"
         // APENAS se a query for SOMENTE o nome do mês (sem outras palavras)
-        if let Some(result) = self.parse_month_only(&query_lower) {
+        if let Some((result, _)) = self.parse_month_only(query_lower, locale, today) {
             // Verificar se a query tem APENAS o nome do mês
             let tokens: Vec<&str> = query_lower.split_whitespace().collect();
             if tokens.len() == 1 {
-                log::info!("✅ Detectado mês puro: {:?}", result);
+                log::info!("✅ Detectado mês puro ({}): {:?}", locale.as_str(), result);
                 return Some(result);
             }
         }
 
-        log::debug!("⚠️ Query não é busca PURA por data (pode ter texto adicional)");
         None
     }
 
     /// Verifica se a query é composta APENAS por componentes de data
-    /// (números, meses, conectores como "de", "dia", etc)
-    fn is_pure_date_query(&self, query: &str, allowed_connectors: &[&str]) -> bool {
+    /// (números, meses, conectores do idioma como "de", "dia", "of", etc)
+    fn is_pure_date_query(&self, query: &str, locale: DateLocale) -> bool {
+        let table = locale.table();
+
         // Remover números e meses conhecidos
         let mut clean_query = query.to_string();
-        
+
         // Remover datas numéricas
         clean_query = Regex::new(r"\d{1,4}").unwrap().replace_all(&clean_query, "").to_string();
-        
-        // Remover meses
-        for month_name in self.month_map_ptbr.keys() {
-            let pattern = format!(r"\b{}\b", regex::escape(month_name));
+
+        // Remover meses (nomes completos e abreviações, com ponto opcional)
+        for (month_name, _) in table.months.iter().chain(table.abbreviations) {
+            let pattern = format!(r"\b{}\b\.?", regex::escape(month_name));
             if let Ok(regex) = Regex::new(&pattern) {
                 clean_query = regex.replace_all(&clean_query, "").to_string();
             }
         }
-        
+
         // Remover conectores permitidos
-        for connector in allowed_connectors {
+        for connector in table.search_connectors {
             let pattern = format!(r"\b{}\b", regex::escape(connector));
             if let Ok(regex) = Regex::new(&pattern) {
                 clean_query = regex.replace_all(&clean_query, "").to_string();
             }
         }
-        
-        // Remover separadores (/, -, _)
-        clean_query = Regex::new(r"[/\-_\s]+").unwrap().replace_all(&clean_query, " ").to_string();
-        
+
+        // Remover separadores (/, -, _, vírgula)
+        clean_query = Regex::new(r"[/\-_,\s]+").unwrap().replace_all(&clean_query, " ").to_string();
+
         // Se sobrou alguma palavra, não é pure date query
         let remaining = clean_query.trim();
         let is_pure = remaining.is_empty();
-        
+
         if !is_pure {
             log::debug!("⚠️ Query tem palavras além de data: '{}'", remaining);
         }
-        
+
         is_pure
     }
 
+    /// Verifica se o texto tem apenas conectores do idioma ("dia", "de", "nos", "in the")
+    fn is_only_connectors(&self, text: &str, locale: DateLocale) -> bool {
+        text.split(|c: char| c.is_whitespace() || matches!(c, ',' | '/' | '-' | '_'))
+            .filter(|word| !word.is_empty())
+            .all(|word| locale.table().search_connectors.contains(&word))
+    }
+
+    /// Parse: "entre 01/02 e 15/02", "de 01/02/2025 a 15/02/2025", "between 02/01 and 02/15"
+    /// (sem ano, vale o ano das outras pontas ou o atual; "15/12 a 15/01" atravessa o ano)
+    fn parse_date_range(&self, query: &str, locale: DateLocale, today: NaiveDate) -> Option<RangeMatch> {
+        let table = locale.table();
+        let endpoint = r"(\d{1,2})[/.-](\d{1,2})(?:[/.-](\d{4}|\d{2}))?";
+        let pattern = format!(
+            r"(?:\b(?:{})\s+)?\b{}(?:\s+(?:{})\s+|\s*[-–]\s*){}\b",
+            alternatives(table.range_openers),
+            endpoint,
+            alternatives(&separator_words(locale)),
+            endpoint
+        );
+        let regex = Regex::new(&pattern).ok()?;
+
+        for captures in regex.captures_iter(query) {
+            let Some(whole) = captures.get(0) else { continue };
+            let number = |group: usize| captures.get(group).and_then(|m| m.as_str().parse::<u32>().ok());
+            let year = |group: usize| {
+                captures.get(group).and_then(|m| {
+                    let value = m.as_str().parse::<i32>().ok()?;
+                    Some(if m.as_str().len() == 2 { 2000 + value } else { value })
+                })
+            };
+            let (first, second) = if table.day_first { (2, 1) } else { (1, 2) };
+            let (Some(start_month), Some(start_day), Some(end_month), Some(end_day)) =
+                (number(first), number(second), number(first + 3), number(second + 3)) else { continue };
+
+            let (start_year, end_year) = (year(3), year(6));
+            let end_year_value = end_year.or(start_year).unwrap_or(today.year());
+            let Some(end_date) = NaiveDate::from_ymd_opt(end_year_value, end_month, end_day) else { continue };
+            let Some(mut start_date) = NaiveDate::from_ymd_opt(start_year.unwrap_or(end_year_value), start_month, start_day) else { continue };
+            if start_date > end_date && start_year.is_none() {
+                let Some(previous) = NaiveDate::from_ymd_opt(end_year_value - 1, start_month, start_day) else { continue };
+                start_date = previous;
+            }
+            if start_date > end_date {
+                continue;
+            }
+
+            return Some((
+                DateSearchQuery { start_date, end_date, query_type: DateQueryType::DateRange },
+                whole.range(),
+            ));
+        }
+
+        None
+    }
+
+    /// Parse: "de março a maio de 2025", "março a maio", "from March to May 2025"
+    /// ("de novembro a fevereiro de 2025" começa em novembro de 2024)
+    fn parse_month_range(&self, query: &str, locale: DateLocale, today: NaiveDate) -> Option<RangeMatch> {
+        let table = locale.table();
+        let pattern = format!(
+            r"(?:\b(?:{})\s+)?\b([a-z]+)\.?(?:\s+(?:{})\s+|\s*[-–]\s*)([a-z]+)\b\.?(?:(?:\s+(?:de|del|of))?,?\s+(\d{{4}})\b)?",
+            alternatives(table.range_openers),
+            alternatives(&separator_words(locale))
+        );
+        let regex = Regex::new(&pattern).ok()?;
+
+        for captures in regex.captures_iter(query) {
+            let Some(whole) = captures.get(0) else { continue };
+            let month = |group: usize| captures.get(group).and_then(|m| locale.month_number(m.as_str()));
+            let (Some(start_month), Some(end_month)) = (month(1), month(2)) else { continue };
+
+            let end_year = captures.get(3).and_then(|m| m.as_str().parse::<i32>().ok()).unwrap_or(today.year());
+            let start_year = if start_month > end_month { end_year - 1 } else { end_year };
+            let (Some((start_date, _)), Some((_, end_date))) =
+                (month_bounds(start_year, start_month), month_bounds(end_year, end_month)) else { continue };
+
+            return Some((
+                DateSearchQuery { start_date, end_date, query_type: DateQueryType::MonthRange },
+                whole.range(),
+            ));
+        }
+
+        None
+    }
+
+    /// Parse: "1º trimestre 2024", "3o trimestre de 2025", "2nd quarter 2024", "4 trimestre"
+    fn parse_quarter(&self, query: &str, locale: DateLocale, today: NaiveDate) -> Option<RangeMatch> {
+        let pattern = format!(
+            r"\b([1-4])\s*(?:º|°|o|st|nd|rd|th)?\s*(?:{})\b(?:(?:\s+(?:de|del|of))?\s+(\d{{4}})\b)?",
+            alternatives(locale.table().quarter_words)
+        );
+        let captures = Regex::new(&pattern).ok()?.captures(query)?;
+
+        let quarter: u32 = captures.get(1)?.as_str().parse().ok()?;
+        let year = captures.get(2).and_then(|m| m.as_str().parse::<i32>().ok()).unwrap_or(today.year());
+        let (start_date, _) = month_bounds(year, quarter * 3 - 2)?;
+        let (_, end_date) = month_bounds(year, quarter * 3)?;
+
+        Some((
+            DateSearchQuery { start_date, end_date, query_type: DateQueryType::Quarter },
+            captures.get(0)?.range(),
+        ))
+    }
+
+    /// Parse: "últimos 30 dias", "últimas 2 semanas", "last 6 months" (terminando hoje, inclusive)
+    fn parse_last_period(&self, query: &str, locale: DateLocale, today: NaiveDate) -> Option<RangeMatch> {
+        let table = locale.table();
+        let units: Vec<&str> = table.period_units.iter().map(|(word, _)| *word).collect();
+        let pattern = format!(
+            r"\b(?:{})\s+(\d{{1,4}})\s+({})\b",
+            alternatives(table.last_words),
+            alternatives(&units)
+        );
+        let captures = Regex::new(&pattern).ok()?.captures(query)?;
+
+        let count: u32 = captures.get(1)?.as_str().parse().ok()?;
+        if count == 0 {
+            return None;
+        }
+        let unit_word = captures.get(2)?.as_str();
+        let unit = table.period_units.iter().find(|(word, _)| *word == unit_word)?.1;
+
+        let start_date = match unit {
+            PeriodUnit::Day => today - Duration::days(count as i64 - 1),
+            PeriodUnit::Week => today - Duration::days(count as i64 * 7 - 1),
+            PeriodUnit::Month => today.checked_sub_months(Months::new(count))? + Duration::days(1),
+            PeriodUnit::Year => today.checked_sub_months(Months::new(count * 12))? + Duration::days(1),
+        };
+
+        Some((
+            DateSearchQuery { start_date, end_date: today, query_type: DateQueryType::LastPeriod },
+            captures.get(0)?.range(),
+        ))
+    }
+
+    /// Parse: "hoje", "ontem", "semana passada", "este mês", "ano passado", "last week"
+    /// (semanas de segunda a domingo)
+    fn parse_relative(&self, query: &str, locale: DateLocale, today: NaiveDate) -> Option<RangeMatch> {
+        // A expressão mais longa vence ("mes passado" e não só "mes")
+        let (span, period) = locale
+            .table()
+            .relative_periods
+            .iter()
+            .filter_map(|(phrase, period)| {
+                let regex = Regex::new(&format!(r"\b{}\b", regex::escape(phrase))).ok()?;
+                regex.find(query).map(|m| (m.range(), *period))
+            })
+            .max_by_key(|(span, _)| span.len())?;
+
+        let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        let (start_date, end_date) = match period {
+            RelativePeriod::Today => (today, today),
+            RelativePeriod::Yesterday => (today - Duration::days(1), today - Duration::days(1)),
+            RelativePeriod::ThisWeek => (week_start, week_start + Duration::days(6)),
+            RelativePeriod::LastWeek => (week_start - Duration::days(7), week_start - Duration::days(1)),
+            RelativePeriod::ThisMonth => month_bounds(today.year(), today.month())?,
+            RelativePeriod::LastMonth => {
+                let previous = today.checked_sub_months(Months::new(1))?;
+                month_bounds(previous.year(), previous.month())?
+            }
+            RelativePeriod::ThisYear => (NaiveDate::from_ymd_opt(today.year(), 1, 1)?, NaiveDate::from_ymd_opt(today.year(), 12, 31)?),
+            RelativePeriod::LastYear => (NaiveDate::from_ymd_opt(today.year() - 1, 1, 1)?, NaiveDate::from_ymd_opt(today.year() - 1, 12, 31)?),
+        };
+
+        Some((DateSearchQuery { start_date, end_date, query_type: DateQueryType::Relative }, span))
+    }
+
     /// Parse: "04/10/2025", "04-10-2025", "2025-10-04", etc
-    fn parse_numeric_date(&self, query: &str) -> Option<DateSearchQuery> {
+    /// (em inglês "10/04/2025" é 4 de outubro: mês antes do dia)
+    fn parse_numeric_date(&self, query: &str, locale: DateLocale) -> Option<RangeMatch> {
+        // Ordem de dia e mês nos formatos com ano no fim
+        let day_month = if locale.table().day_first { vec![2, 1, 0] } else { vec![2, 0, 1] };
+
         let patterns = vec![
             // ISO 8601: YYYY-MM-DD
             (r"(\d{4})-(\d{2})-(\d{2})", vec![0, 1, 2]),
-            // BR: DD/MM/YYYY
-            (r"(\d{2})/(\d{2})/(\d{4})", vec![2, 1, 0]),
-            // BR: DD-MM-YYYY
-            (r"(\d{2})-(\d{2})-(\d{4})", vec![2, 1, 0]),
+            // BR: DD/MM/YYYY (EN: MM/DD/YYYY)
+            (r"(\d{2})/(\d{2})/(\d{4})", day_month.clone()),
+            // BR: DD-MM-YYYY (EN: MM-DD-YYYY)
+            (r"(\d{2})-(\d{2})-(\d{4})", day_month.clone()),
             // Compacto: YYYYMMDD
             (r"(\d{4})(\d{2})(\d{2})", vec![0, 1, 2]),
-            // Compacto BR: DDMMYYYY
-            (r"(\d{2})(\d{2})(\d{4})", vec![2, 1, 0]),
+            // Compacto BR: DDMMYYYY (EN: MMDDYYYY)
+            (r"(\d{2})(\d{2})(\d{4})", day_month),
         ];
 
         for (pattern_str, order) in patterns {
@@ -182,11 +499,14 @@ impl DateSearchParser {
                         ) {
                             if month >= 1 && month <= 12 && day >= 1 && day <= 31 {
                                 if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
-                                    return Some(DateSearchQuery {
-                                        start_date: date,
-                                        end_date: date,
-                                        query_type: DateQueryType::ExactDate,
-                                    });
+                                    return Some((
+                                        DateSearchQuery {
+                                            start_date: date,
+                                            end_date: date,
+                                            query_type: DateQueryType::ExactDate,
+                                        },
+                                        captures.get(0)?.range(),
+                                    ));
                                 }
                             }
                         }
@@ -198,35 +518,48 @@ impl DateSearchParser {
         None
     }
 
-    /// Parse: "dia 4 de outubro de 2025", "4 de outubro", "carga dia 4 de outubro"
-    fn parse_textual_date(&self, query: &str) -> Option<DateSearchQuery> {
-        // Regex para: "dia? <numero> de <mes> de? <ano>?"
-        // Exemplos: "dia 4 de outubro de 2025", "4 de outubro", "4 de out"
-        let pattern = r"(?:dia\s+)?(\d{1,2})\s+de\s+([a-zç]+)(?:\s+de\s+)?(\d{4})?";
-        
-        if let Ok(regex) = Regex::new(pattern) {
-            if let Some(captures) = regex.captures(query) {
-                let day_str = captures.get(1).map(|m| m.as_str()).unwrap_or("");
-                let month_str = captures.get(2).map(|m| m.as_str()).unwrap_or("");
-                let year_str = captures.get(3).map(|m| m.as_str());
-
-                if let Ok(day) = day_str.parse::<u32>() {
-                    if let Some(&month) = self.month_map_ptbr.get(month_str) {
-                        let current_year = chrono::Local::now().year();
-                        let year = if let Some(y_str) = year_str {
-                            y_str.parse::<i32>().unwrap_or(current_year)
-                        } else {
-                            current_year
-                        };
-
-                        if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
-                            return Some(DateSearchQuery {
-                                start_date: date,
-                                end_date: date,
-                                query_type: DateQueryType::TextualDate,
-                            });
-                        }
-                    }
+    /// Parse: "dia 4 de outubro de 2025", "4 de outubro", "carga dia 4 de outubro",
+    /// "4 de octubre de 2025", "October 4, 2025", "4th of October"
+    fn parse_textual_date(&self, query: &str, locale: DateLocale, today: NaiveDate) -> Option<RangeMatch> {
+        // Padrões: (regex, grupo do dia, grupo do mês, grupo do ano opcional)
+        let patterns: Vec<(&str, usize, usize, usize)> = match locale {
+            // "dia? <numero> de <mes> de? <ano>?"
+            // Exemplos: "dia 4 de outubro de 2025", "4 de outubro", "4 de out"
+            DateLocale::PtBr | DateLocale::Es => {
+                vec![(r"(?:dia\s+)?(\d{1,2})\s+de\s+([a-z]+)(?:\s+de(?:l)?\s+)?(\d{4})?", 1, 2, 3)]
+            }
+            // "October 4, 2025", "Oct 4th", "4th of October 2025"
+            DateLocale::En => vec![
+                (r"([a-z]+)\.?\s+(\d{1,2})(?:st|nd|rd|th)?\b(?:,?\s+(\d{4}))?", 2, 1, 3),
+                (r"(\d{1,2})(?:st|nd|rd|th)?\s+(?:of\s+)?([a-z]+)\.?(?:,?\s+(\d{4}))?", 1, 2, 3),
+            ],
+        };
+
+        for (pattern, day_group, month_group, year_group) in patterns {
+            let Ok(regex) = Regex::new(pattern) else { continue };
+            let Some(captures) = regex.captures(query) else { continue };
+
+            let day_str = captures.get(day_group).map(|m| m.as_str()).unwrap_or("");
+            let month_str = captures.get(month_group).map(|m| m.as_str()).unwrap_or("");
+            let year_str = captures.get(year_group).map(|m| m.as_str());
+
+            if let (Ok(day), Some(month)) = (day_str.parse::<u32>(), locale.month_number(month_str)) {
+                let current_year = today.year();
+                let year = if let Some(y_str) = year_str {
+                    y_str.parse::<i32>().unwrap_or(current_year)
+                } else {
+                    current_year
+                };
+
+                if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+                    return Some((
+                        DateSearchQuery {
+                            start_date: date,
+                            end_date: date,
+                            query_type: DateQueryType::TextualDate,
+                        },
+                        captures.get(0)?.range(),
+                    ));
                 }
             }
         }
@@ -234,18 +567,19 @@ impl DateSearchParser {
         None
     }
 
-    /// Parse: "outubro 2025", "outubro de 2025"
-    fn parse_month_year(&self, query: &str) -> Option<DateSearchQuery> {
+    /// Parse: "outubro 2025", "outubro de 2025", "Oct 2025", "octubre de 2025"
+    fn parse_month_year(&self, query: &str, locale: DateLocale) -> Option<RangeMatch> {
         // Regex para: "<mes> de? <ano>"
-        // Exemplos: "outubro 2025", "out de 2025"
-        let pattern = r"([a-zç]+)(?:\s+de\s+|\s+)(\d{4})";
-        
+        // Exemplos: "outubro 2025", "out de 2025", "oct. 2025", "out/2025"
+        let pattern = r"([a-z]+)\.?(?:\s+de\s+|\s+|/)(\d{4})";
+
         if let Ok(regex) = Regex::new(pattern) {
-            if let Some(captures) = regex.captures(query) {
+            // A primeira palavra antes de um ano pode não ser mês ("pedido 2025 outubro 2025")
+            for captures in regex.captures_iter(query) {
                 let month_str = captures.get(1).map(|m| m.as_str()).unwrap_or("");
                 let year_str = captures.get(2).map(|m| m.as_str()).unwrap_or("");
 
-                if let Some(&month) = self.month_map_ptbr.get(month_str) {
+                if let Some(month) = locale.month_number(month_str) {
                     if let Ok(year) = year_str.parse::<i32>() {
                         // Retornar o mês inteiro (primeiro ao último dia)
                         if let Some(start_date) = NaiveDate::from_ymd_opt(year, month, 1) {
@@ -258,12 +592,15 @@ impl DateSearchParser {
 
                             if let Some(next_month_date) = next_month {
                                 let end_date = next_month_date - Duration::days(1);
-                                
-                                return Some(DateSearchQuery {
-                                    start_date,
-                                    end_date,
-                                    query_type: DateQueryType::Month,
-                                });
+
+                                return Some((
+                                    DateSearchQuery {
+                                        start_date,
+                                        end_date,
+                                        query_type: DateQueryType::Month,
+                                    },
+                                    captures.get(0)?.range(),
+                                ));
                             }
                         }
                     }
@@ -277,37 +614,34 @@ impl DateSearchParser {
     /// Parse: "outubro", "documentos de outubro"
     /// IMPORTANTE: Usa word boundaries para evitar falsos positivos
     /// (ex: "setor" não deve detectar "set")
-    fn parse_month_only(&self, query: &str) -> Option<DateSearchQuery> {
-        // Tentar meses COMPLETOS primeiro (mais específicos)
-        let full_month_names = vec![
-            "janeiro", "fevereiro", "março", "marco", "abril", "maio", "junho",
-            "julho", "agosto", "setembro", "outubro", "novembro", "dezembro"
-        ];
-
-        for month_name in full_month_names {
+    fn parse_month_only(&self, query: &str, locale: DateLocale, today: NaiveDate) -> Option<RangeMatch> {
+        // Só meses COMPLETOS (mais específicos)
+        for (month_name, month_num) in locale.table().months {
             // Usar word boundary regex para match exato
             let pattern = format!(r"\b{}\b", regex::escape(month_name));
             if let Ok(regex) = Regex::new(&pattern) {
-                if regex.is_match(query) {
-                    if let Some(&month_num) = self.month_map_ptbr.get(month_name) {
-                        let current_year = chrono::Local::now().year();
-                        
-                        if let Some(start_date) = NaiveDate::from_ymd_opt(current_year, month_num, 1) {
-                            let next_month = if month_num == 12 {
-                                NaiveDate::from_ymd_opt(current_year + 1, 1, 1)
-                            } else {
-                                NaiveDate::from_ymd_opt(current_year, month_num + 1, 1)
-                            };
+                if let Some(found) = regex.find(query) {
+                    let month_num = *month_num;
+                    let current_year = today.year();
 
-                            if let Some(next_month_date) = next_month {
-                                let end_date = next_month_date - Duration::days(1);
-                                
-                                return Some(DateSearchQuery {
+                    if let Some(start_date) = NaiveDate::from_ymd_opt(current_year, month_num, 1) {
+                        let next_month = if month_num == 12 {
+                            NaiveDate::from_ymd_opt(current_year + 1, 1, 1)
+                        } else {
+                            NaiveDate::from_ymd_opt(current_year, month_num + 1, 1)
+                        };
+
+                        if let Some(next_month_date) = next_month {
+                            let end_date = next_month_date - Duration::days(1);
+
+                            return Some((
+                                DateSearchQuery {
                                     start_date,
                                     end_date,
                                     query_type: DateQueryType::Month,
-                                });
-                            }
+                                },
+                                found.range(),
+                            ));
                         }
                     }
                 }
@@ -320,6 +654,30 @@ impl DateSearchParser {
     }
 }
 
+/// Primeiro e último dia do mês
+fn month_bounds(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let end = start.checked_add_months(Months::new(1))? - Duration::days(1);
+    Some((start, end))
+}
+
+/// Palavras como alternativas de regex ("a|ate|e")
+fn alternatives(words: &[&str]) -> String {
+    words.iter().map(|word| regex::escape(word)).collect::<Vec<_>>().join("|")
+}
+
+/// Palavras entre as pontas de um intervalo ("a", "até", "e"; traços são tratados à parte)
+fn separator_words(locale: DateLocale) -> Vec<&'static str> {
+    let table = locale.table();
+    table
+        .range_connectors
+        .iter()
+        .chain(table.and_words)
+        .copied()
+        .filter(|word| word.chars().all(char::is_alphabetic))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -386,6 +744,28 @@ mod tests {
         assert!(parser.parse("agenda de trabalho").is_none(), "agenda não deve detectar 'ago'");
     }
 
+    #[test]
+    fn test_english_and_spanish_queries() {
+        let parser = DateSearchParser::new();
+        let date = |query: &str| parser.parse(query).map(|result| result.start_date.format("%Y-%m-%d").to_string());
+
+        assert_eq!(date("October 4, 2025").as_deref(), Some("2025-10-04"));
+        assert_eq!(date("4th of October 2025").as_deref(), Some("2025-10-04"));
+        assert_eq!(date("4 de octubre de 2025").as_deref(), Some("2025-10-04"));
+        assert_eq!(date("Março 2025").as_deref(), Some("2025-03-01"));
+
+        let result = parser.parse("Oct 2025").unwrap();
+        assert_eq!(result.end_date.format("%Y-%m-%d").to_string(), "2025-10-31");
+
+        // Numérica ambígua: português primeiro; só em inglês vira mês/dia
+        assert_eq!(date("04/10/2025").as_deref(), Some("2025-10-04"));
+        let english = DateSearchParser::with_locales(&[DateLocale::En]);
+        assert_eq!(english.parse("10/04/2025").unwrap().start_date.format("%Y-%m-%d").to_string(), "2025-10-04");
+
+        assert!(parser.parse("invoice october").is_none());
+        assert!(parser.parse("mayoría de casos").is_none());
+    }
+
     #[test]
     fn test_mixed_text_and_date_queries() {
         let parser = DateSearchParser::new();
@@ -403,4 +783,122 @@ mod tests {
         assert!(parser.parse("4 de outubro de 2025").is_some(), "data textual pura deve ser detectada");
         assert!(parser.parse("outubro 2025").is_some(), "mês/ano deve ser detectado");
     }
+
+    fn range_at(parser: &DateSearchParser, query: &str) -> Option<(String, String)> {
+        let today = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap(); // quarta-feira
+        parser.parse_at(query, today).map(|result| {
+            (result.start_date.format("%Y-%m-%d").to_string(), result.end_date.format("%Y-%m-%d").to_string())
+        })
+    }
+
+    #[test]
+    fn test_relative_expressions() {
+        let parser = DateSearchParser::new();
+        let range = |query: &str| range_at(&parser, query);
+        let expected = |start: &str, end: &str| Some((start.to_string(), end.to_string()));
+
+        assert_eq!(range("hoje"), expected("2025-10-15", "2025-10-15"));
+        assert_eq!(range("ontem"), expected("2025-10-14", "2025-10-14"));
+        assert_eq!(range("esta semana"), expected("2025-10-13", "2025-10-19"));
+        assert_eq!(range("semana passada"), expected("2025-10-06", "2025-10-12"));
+        assert_eq!(range("este mês"), expected("2025-10-01", "2025-10-31"));
+        assert_eq!(range("mês passado"), expected("2025-09-01", "2025-09-30"));
+        assert_eq!(range("ano passado"), expected("2024-01-01", "2024-12-31"));
+        assert_eq!(range("últimos 30 dias"), expected("2025-09-16", "2025-10-15"));
+        assert_eq!(range("nos últimos 30 dias"), expected("2025-09-16", "2025-10-15"));
+        assert_eq!(range("últimas 2 semanas"), expected("2025-10-02", "2025-10-15"));
+        assert_eq!(range("últimos 3 meses"), expected("2025-07-16", "2025-10-15"));
+        assert_eq!(range("last week"), expected("2025-10-06", "2025-10-12"));
+        assert_eq!(range("el mes pasado"), expected("2025-09-01", "2025-09-30"));
+
+        // Mês sem ano usa o ano de "hoje"
+        assert_eq!(range("outubro"), expected("2025-10-01", "2025-10-31"));
+
+        let result = parser.parse_at("ontem", NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()).unwrap();
+        assert_eq!(result.start_date, NaiveDate::from_ymd_opt(2024, 12, 31).unwrap());
+        assert!(matches!(result.query_type, DateQueryType::Relative));
+    }
+
+    #[test]
+    fn test_range_expressions() {
+        let parser = DateSearchParser::new();
+        let range = |query: &str| range_at(&parser, query);
+        let expected = |start: &str, end: &str| Some((start.to_string(), end.to_string()));
+
+        assert_eq!(range("de março a maio de 2025"), expected("2025-03-01", "2025-05-31"));
+        assert_eq!(range("março a maio"), expected("2025-03-01", "2025-05-31"));
+        assert_eq!(range("de novembro a fevereiro de 2025"), expected("2024-11-01", "2025-02-28"));
+        assert_eq!(range("from March to May 2025"), expected("2025-03-01", "2025-05-31"));
+        assert_eq!(range("1º trimestre 2024"), expected("2024-01-01", "2024-03-31"));
+        assert_eq!(range("4o trimestre de 2024"), expected("2024-10-01", "2024-12-31"));
+        assert_eq!(range("2nd quarter 2024"), expected("2024-04-01", "2024-06-30"));
+        assert_eq!(range("3º trimestre"), expected("2025-07-01", "2025-09-30"));
+        assert_eq!(range("entre 01/02 e 15/02"), expected("2025-02-01", "2025-02-15"));
+        assert_eq!(range("de 01/02/2024 a 15/03/2024"), expected("2024-02-01", "2024-03-15"));
+        assert_eq!(range("15/12 a 15/01/2025"), expected("2024-12-15", "2025-01-15"));
+        assert_eq!(range("between 02/01 and 02/15"), expected("2025-02-01", "2025-02-15"));
+
+        let result = parser.parse("entre 01/02 e 15/02/2025").unwrap();
+        assert!(matches!(result.query_type, DateQueryType::DateRange));
+    }
+
+    #[test]
+    fn test_range_false_positives_regression() {
+        let parser = DateSearchParser::new();
+
+        // Texto junto de expressões relativas ou intervalos continua indo para FTS5
+        assert!(parser.parse("relatório de ontem").is_none(), "texto + relativo deve ir para FTS5");
+        assert!(parser.parse("notas dos últimos 30 dias").is_none());
+        assert!(parser.parse("semana passada 2025").is_none(), "ano solto não combina com semana passada");
+        // Palavras que não são meses não formam intervalo
+        assert!(parser.parse("de casa a casa").is_none());
+        assert!(parser.parse("mercado a varejo").is_none());
+        assert!(parser.parse("5 trimestre 2024").is_none(), "não existe 5º trimestre");
+        assert!(parser.parse("últimos dias").is_none(), "sem quantidade não é intervalo");
+        assert!(parser.parse("entre 31/02 e 15/03").is_none(), "data inválida não vira intervalo");
+        assert!(parser.parse("entre 15/03/2025 e 01/02/2025").is_none(), "intervalo invertido com anos explícitos");
+    }
+
+    #[test]
+    fn test_split_mixed_queries() {
+        let parser = DateSearchParser::new();
+        let today = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
+        let split = |query: &str| {
+            parser.split_at(query, today).map(|split| (split.text, split.date.describe()))
+        };
+        let expected = |text: &str, period: &str| Some((text.to_string(), period.to_string()));
+
+        assert_eq!(split("nota fiscal outubro 2025"), expected("nota fiscal", "outubro/2025"));
+        assert_eq!(split("Notas de Março de 2025"), expected("Notas", "março/2025"));
+        assert_eq!(split("boleto vencido 04/10/2025"), expected("boleto vencido", "04/10/2025"));
+        assert_eq!(split("rastreabilidade de outubro"), expected("rastreabilidade", "outubro/2025"));
+        assert_eq!(split("contratos do 1º trimestre 2024"), expected("contratos", "janeiro/2024 a março/2024"));
+        assert_eq!(split("extrato entre 01/02 e 15/02"), expected("extrato", "01/02/2025 a 15/02/2025"));
+        assert_eq!(split("relatório de ontem"), expected("relatório", "14/10/2025"));
+        assert_eq!(split("invoice October 2025"), expected("invoice", "outubro/2025"));
+
+        // Query só de data: texto vazio
+        assert_eq!(split("outubro 2025"), expected("", "outubro/2025"));
+
+        // Sem data: nada a separar
+        assert!(split("setor fiscal").is_none());
+        assert!(split("pedido 120042025").is_none(), "número longo não é data");
+        assert!(split("agenda de trabalho").is_none());
+    }
+
+    #[test]
+    fn test_split_false_positives_regression() {
+        let parser = DateSearchParser::new();
+        let today = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
+
+        // Mês solto no meio do texto é nome ou palavra, não período: a query vai inteira para FTS5
+        assert!(parser.split_at("contrato Marco Aurélio", today).is_none(), "Marco é nome, não março");
+        assert!(parser.split_at("may contract", today).is_none(), "may não é maio sem preposição");
+        assert!(parser.split_at("rastreabilidade outubro", today).is_none(), "mês sem ano nem preposição");
+        assert!(parser.split_at("Abril Cultural revistas", today).is_none());
+
+        // Com ano ou preposição, o mês continua sendo data
+        assert_eq!(parser.split_at("contrato Marco Aurélio março 2025", today).unwrap().text, "contrato Marco Aurélio");
+        assert_eq!(parser.split_at("invoices in May", today).unwrap().text, "invoices");
+    }
 }
@@ -7,7 +7,7 @@ use std::path::PathBuf;
 pub async fn open_file_dialog(app: AppHandle) -> Result<Vec<String>, String> {
     let files = app.dialog()
         .file()
-        .add_filter("Documentos", &["pdf", "doc", "docx", "xls", "xlsx"])
+        .add_filter("Documentos", &["pdf", "docx", "odt", "rtf", "txt", "md", "csv", "html", "htm", "xml", "eml", "xls", "xlsx", "ods"])
         .add_filter("Imagens", &["jpg", "jpeg", "png", "gif", "bmp", "webp"])
         .add_filter("Todos", &["*"])
         .set_title("Selecionar documentos")
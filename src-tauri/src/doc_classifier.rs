@@ -0,0 +1,246 @@
+// CLASSIFICADOR ESTATÍSTICO DE DOCUMENTOS (NAIVE BAYES MULTINOMIAL, OFFLINE)
+// Começa com as palavras-chave das regras de extração como exemplos de cada tipo e é
+// retreinado com os textos dos documentos cujo tipo o usuário corrigiu. Quando a
+// probabilidade do melhor tipo é baixa, vale a classificação pelas regras.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::br_validators;
+use crate::extraction_rules::{Classification, CompiledRules, RuleSet, TypeScore};
+
+// Probabilidade mínima do melhor tipo para o classificador decidir sozinho
+pub const MIN_CONFIDENCE: f32 = 0.6;
+// Suavização de Laplace
+const ALPHA: f64 = 1.0;
+
+// Modelo de cada usuário, junto das regras que o semearam (refeito quando elas mudam)
+pub type ClassifierCache = HashMap<String, (Arc<CompiledRules>, Arc<NaiveBayes>)>;
+
+const STOPWORDS: &[&str] = &[
+    "com", "das", "dos", "para", "por", "que", "uma", "como", "mais", "sem", "sob", "sobre", "este", "esta", "esse",
+    "essa", "seu", "sua", "nos", "nas", "pelo", "pela", "entre", "the", "and", "for",
+];
+
+/// Palavras do texto: minúsculas, sem acentos, com 3+ letras, sem números puros nem stopwords
+pub fn tokenize(text: &str) -> Vec<String> {
+    br_validators::fold(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.chars().count() >= 3)
+        .filter(|token| !token.chars().all(|c| c.is_ascii_digit()))
+        .filter(|token| !STOPWORDS.contains(token))
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+struct ClassStats {
+    documents: u32,
+    tokens: u64,
+    counts: HashMap<String, u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NaiveBayes {
+    classes: BTreeMap<String, ClassStats>,
+    vocabulary: HashSet<String>,
+    documents: u32,
+    // Exemplos vindos de correções do usuário (sem contar as sementes das regras)
+    corrections: u32,
+}
+
+impl NaiveBayes {
+    /// Modelo semeado com as palavras-chave das regras: cada tipo vira um exemplo
+    /// com cada termo repetido conforme o peso
+    pub fn seeded(rules: &RuleSet) -> Self {
+        let mut model = NaiveBayes::default();
+        for document_type in &rules.document_types {
+            let seed: Vec<&str> = document_type
+                .keywords
+                .iter()
+                .flat_map(|keyword| std::iter::repeat_n(keyword.term.as_str(), keyword.weight.round().max(1.0) as usize))
+                .collect();
+            model.add(&document_type.name, &seed.join(" "));
+        }
+        model
+    }
+
+    /// Acrescentar um documento com tipo corrigido pelo usuário
+    pub fn train(&mut self, document_type: &str, text: &str) {
+        self.add(document_type, text);
+        self.corrections += 1;
+    }
+
+    fn add(&mut self, document_type: &str, text: &str) {
+        let stats = self.classes.entry(document_type.to_string()).or_default();
+        stats.documents += 1;
+        self.documents += 1;
+        for token in tokenize(text) {
+            stats.tokens += 1;
+            *stats.counts.entry(token.clone()).or_insert(0) += 1;
+            self.vocabulary.insert(token);
+        }
+    }
+
+    pub fn corrections(&self) -> u32 {
+        self.corrections
+    }
+
+    /// Probabilidade de cada tipo (somam 1), da maior para a menor. Palavras fora do
+    /// vocabulário são ignoradas; sem nenhuma conhecida, sobram só as probabilidades a priori.
+    pub fn scores(&self, text: &str) -> Vec<TypeScore> {
+        if self.classes.is_empty() {
+            return Vec::new();
+        }
+
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for token in tokenize(text) {
+            if self.vocabulary.contains(&token) {
+                *counts.entry(token).or_insert(0) += 1;
+            }
+        }
+
+        let vocabulary = self.vocabulary.len() as f64;
+        let log_scores: Vec<(String, f64)> = self
+            .classes
+            .iter()
+            .map(|(name, stats)| {
+                let prior = (stats.documents as f64 / self.documents as f64).ln();
+                let denominator = stats.tokens as f64 + ALPHA * vocabulary;
+                let likelihood: f64 = counts
+                    .iter()
+                    .map(|(token, count)| {
+                        let in_class = stats.counts.get(token).copied().unwrap_or(0) as f64;
+                        *count as f64 * ((in_class + ALPHA) / denominator).ln()
+                    })
+                    .sum();
+                (name.clone(), prior + likelihood)
+            })
+            .collect();
+
+        // Softmax estável (subtrai o maior log antes da exponencial)
+        let max = log_scores.iter().map(|(_, score)| *score).fold(f64::NEG_INFINITY, f64::max);
+        let total: f64 = log_scores.iter().map(|(_, score)| (score - max).exp()).sum();
+
+        let mut scores: Vec<TypeScore> = log_scores
+            .into_iter()
+            .map(|(document_type, score)| TypeScore { document_type, score: ((score - max).exp() / total) as f32 })
+            .collect();
+        scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scores
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TypeDecision {
+    pub document_type: String,
+    // "naive_bayes" ou "regras"
+    pub method: String,
+    // Probabilidade do tipo mais provável segundo o classificador
+    pub confidence: Option<f32>,
+    // Probabilidades do classificador para todos os tipos
+    pub scores: Vec<TypeScore>,
+    // Pontuação das palavras-chave das regras
+    pub rule_scores: Vec<TypeScore>,
+}
+
+impl TypeDecision {
+    /// Decisão só pelas regras (antes de o classificador do usuário ser aplicado)
+    pub fn from_rules(classification: &Classification) -> Self {
+        TypeDecision {
+            document_type: classification.document_type.clone(),
+            method: "regras".to_string(),
+            confidence: None,
+            scores: Vec::new(),
+            rule_scores: classification.scores.clone(),
+        }
+    }
+}
+
+/// Tipo pelo classificador quando ele está confiante; senão, o das regras
+pub fn decide(model: &NaiveBayes, text: &str, rules: TypeDecision) -> TypeDecision {
+    let scores = model.scores(text);
+    let best = scores.first().cloned();
+
+    let mut decision = rules;
+    decision.confidence = best.as_ref().map(|best| best.score);
+    decision.scores = scores;
+
+    if let Some(best) = best {
+        if best.score >= MIN_CONFIDENCE {
+            decision.document_type = best.document_type;
+            decision.method = "naive_bayes".to_string();
+        }
+    }
+    decision
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extraction_rules::{DocumentTypeRule, KeywordRule};
+
+    fn rules() -> RuleSet {
+        let document_type = |name: &str, terms: &[&str]| DocumentTypeRule {
+            name: name.to_string(),
+            keywords: terms.iter().map(|term| KeywordRule { term: term.to_string(), weight: 2.0 }).collect(),
+            min_score: 1.0,
+            fields: Vec::new(),
+        };
+        RuleSet {
+            default_type: "documento_generico".to_string(),
+            document_types: vec![
+                document_type("contrato", &["contrato", "cláusula", "contratante"]),
+                document_type("recibo", &["recibo", "comprovante", "pagamento"]),
+            ],
+            fields: Vec::new(),
+        }
+    }
+
+    fn by_rules(document_type: &str) -> TypeDecision {
+        TypeDecision::from_rules(&Classification { document_type: document_type.to_string(), scores: Vec::new() })
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Cláusula 1ª: O CONTRATANTE pagará R$ 1.000,00 para a"), vec!["clausula", "contratante", "pagara"]);
+    }
+
+    #[test]
+    fn test_scores_cover_every_class_and_sum_to_one() {
+        let model = NaiveBayes::seeded(&rules());
+        let scores = model.scores("Contrato de locação. Cláusula primeira: o pagamento será mensal.");
+
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].document_type, "contrato");
+        let total: f32 = scores.iter().map(|score| score.score).sum();
+        assert!((total - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_corrections_teach_new_types() {
+        let mut model = NaiveBayes::seeded(&rules());
+        for _ in 0..3 {
+            model.train("boleto", "Boleto bancário. Beneficiário: Loja. Linha digitável. Vencimento 10/10/2025. Pagador: Maria");
+        }
+        assert_eq!(model.corrections(), 3);
+
+        let decision = decide(&model, "Boleto com vencimento amanhã, beneficiário Loja Exemplo, linha digitável abaixo", by_rules("recibo"));
+        assert_eq!(decision.document_type, "boleto");
+        assert_eq!(decision.method, "naive_bayes");
+        assert_eq!(decision.scores.len(), 3);
+    }
+
+    #[test]
+    fn test_low_confidence_falls_back_to_rules() {
+        let model = NaiveBayes::seeded(&rules());
+
+        // Nenhuma palavra conhecida: só as probabilidades a priori (empate)
+        let decision = decide(&model, "Lista de compras da semana", by_rules("documento_generico"));
+        assert_eq!(decision.document_type, "documento_generico");
+        assert_eq!(decision.method, "regras");
+        assert!(decision.confidence.unwrap() < MIN_CONFIDENCE);
+    }
+}
@@ -0,0 +1,615 @@
+// PARSER DE E-MAIL (.eml / .mbox)
+// Lê mensagens RFC 5322 com MIME: cabeçalhos (com encoded-words RFC 2047),
+// corpo em texto (text/plain ou, na falta dele, text/html) e anexos decodificados.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::text_extractors;
+
+// Limite de aninhamento de multipart, contra mensagens malformadas
+const MAX_MIME_DEPTH: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct EmailAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedEmail {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub cc: Option<String>,
+    pub subject: Option<String>,
+    pub date: Option<DateTime<FixedOffset>>,
+    pub message_id: Option<String>,
+    pub body_text: String,
+    pub attachments: Vec<EmailAttachment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentInfo {
+    pub filename: String,
+    pub content_type: String,
+    pub size: usize,
+}
+
+pub fn parse_email(raw: &[u8]) -> ParsedEmail {
+    let (headers, body) = split_headers(raw);
+
+    let mut email = ParsedEmail {
+        from: header_text(&headers, "from"),
+        to: header_text(&headers, "to"),
+        cc: header_text(&headers, "cc"),
+        subject: header_text(&headers, "subject"),
+        date: headers.get("date").and_then(|d| parse_date(d)),
+        message_id: headers.get("message-id").map(|id| id.trim().to_string()),
+        body_text: String::new(),
+        attachments: Vec::new(),
+    };
+
+    let mut html_body = None;
+    collect_parts(&headers, body, 0, &mut email, &mut html_body);
+
+    if email.body_text.trim().is_empty() {
+        if let Some(html) = html_body {
+            email.body_text = text_extractors::extract_html(&html);
+        }
+    }
+    email.body_text = email.body_text.trim().to_string();
+    email
+}
+
+/// Separa um arquivo mbox nas mensagens que ele contém
+pub fn split_mbox(raw: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+
+    for line in raw.split_inclusive(|&b| b == b'\n') {
+        if line.starts_with(b"From ") {
+            if let Some(message) = current.take() {
+                messages.push(message);
+            }
+            current = Some(Vec::new());
+            continue;
+        }
+
+        if let Some(message) = current.as_mut() {
+            // Linhas ">From " do corpo foram escapadas na gravação (mboxrd)
+            let unescaped = line.strip_prefix(b">").filter(|rest| {
+                let rest = rest.iter().skip_while(|&&b| b == b'>').copied().collect::<Vec<u8>>();
+                rest.starts_with(b"From ")
+            });
+            message.extend_from_slice(unescaped.unwrap_or(line));
+        }
+    }
+
+    if let Some(message) = current {
+        messages.push(message);
+    }
+    messages.retain(|m| m.iter().any(|b| !b.is_ascii_whitespace()));
+    messages
+}
+
+pub fn is_email_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("eml"))
+        .unwrap_or(false)
+}
+
+pub fn is_mbox_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("mbox"))
+        .unwrap_or(false)
+}
+
+/// Nome de arquivo seguro para gravar em disco (sem separadores de caminho nem caracteres reservados)
+pub fn safe_file_name(name: &str) -> String {
+    let cleaned: String = name.chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    let cleaned = cleaned.trim().trim_matches('.').to_string();
+    if cleaned.is_empty() {
+        "anexo".to_string()
+    } else {
+        cleaned.chars().take(120).collect()
+    }
+}
+
+impl ParsedEmail {
+    pub fn attachment_infos(&self) -> Vec<AttachmentInfo> {
+        self.attachments.iter()
+            .map(|a| AttachmentInfo {
+                filename: a.filename.clone(),
+                content_type: a.content_type.clone(),
+                size: a.data.len(),
+            })
+            .collect()
+    }
+
+    /// Cabeçalhos principais seguidos do corpo, para exibição e busca full-text
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+        let mut push = |label: &str, value: &Option<String>| {
+            if let Some(value) = value {
+                lines.push(format!("{}: {}", label, value));
+            }
+        };
+        push("De", &self.from);
+        push("Para", &self.to);
+        push("Cc", &self.cc);
+        push("Assunto", &self.subject);
+        if let Some(date) = self.date {
+            lines.push(format!("Data: {}", date.format("%d/%m/%Y %H:%M")));
+        }
+        if !self.attachments.is_empty() {
+            let names: Vec<&str> = self.attachments.iter().map(|a| a.filename.as_str()).collect();
+            lines.push(format!("Anexos: {}", names.join(", ")));
+        }
+
+        format!("{}\n\n{}", lines.join("\n"), self.body_text).trim().to_string()
+    }
+
+    /// Campos para `extracted_fields`
+    pub fn to_fields(&self) -> HashMap<String, String> {
+        let mut fields = HashMap::new();
+        let mut insert = |key: &str, value: &Option<String>| {
+            if let Some(value) = value.as_ref().filter(|v| !v.is_empty()) {
+                fields.insert(key.to_string(), value.clone());
+            }
+        };
+        insert("email_from", &self.from);
+        insert("email_to", &self.to);
+        insert("email_cc", &self.cc);
+        insert("email_subject", &self.subject);
+        insert("email_message_id", &self.message_id);
+
+        if let Some(date) = self.date {
+            fields.insert("email_date".to_string(), date.to_rfc3339());
+        }
+        if !self.attachments.is_empty() {
+            let names: Vec<&str> = self.attachments.iter().map(|a| a.filename.as_str()).collect();
+            fields.insert("email_attachments".to_string(), names.join(", "));
+        }
+        fields
+    }
+}
+
+// ================================
+// MIME
+// ================================
+
+type Headers = HashMap<String, String>;
+
+fn split_headers(raw: &[u8]) -> (Headers, &[u8]) {
+    let (header_end, body_start) = find_subslice(raw, b"\r\n\r\n")
+        .map(|pos| (pos, pos + 4))
+        .or_else(|| find_subslice(raw, b"\n\n").map(|pos| (pos, pos + 2)))
+        .unwrap_or((raw.len(), raw.len()));
+
+    let header_block = String::from_utf8_lossy(&raw[..header_end]);
+    let mut headers = Headers::new();
+    let mut last_name: Option<String> = None;
+
+    for line in header_block.lines() {
+        if line.starts_with(' ') || line.starts_with('\t') {
+            // Continuação de cabeçalho dobrado
+            if let Some(value) = last_name.as_ref().and_then(|name| headers.get_mut(name)) {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim().to_lowercase();
+            // Primeira ocorrência vence (Received e afins se repetem)
+            headers.entry(name.clone()).or_insert_with(|| value.trim().to_string());
+            last_name = Some(name);
+        }
+    }
+
+    (headers, &raw[body_start..])
+}
+
+fn collect_parts(headers: &Headers, body: &[u8], depth: usize, email: &mut ParsedEmail, html_body: &mut Option<String>) {
+    let (content_type, params) = headers.get("content-type")
+        .map(|value| parse_header_params(value))
+        .unwrap_or_else(|| ("text/plain".to_string(), HashMap::new()));
+
+    if content_type.starts_with("multipart/") && depth < MAX_MIME_DEPTH {
+        if let Some(boundary) = params.get("boundary") {
+            for part in split_multipart(body, boundary) {
+                let (part_headers, part_body) = split_headers(part);
+                collect_parts(&part_headers, part_body, depth + 1, email, html_body);
+            }
+            return;
+        }
+    }
+
+    let (disposition, disposition_params) = headers.get("content-disposition")
+        .map(|value| parse_header_params(value))
+        .unwrap_or_default();
+    let filename = disposition_params.get("filename")
+        .or_else(|| params.get("name"))
+        .map(|name| decode_encoded_words(name));
+
+    let data = decode_transfer_encoding(
+        body,
+        headers.get("content-transfer-encoding").map(|e| e.trim().to_lowercase()).as_deref(),
+    );
+
+    let is_attachment = disposition == "attachment" || filename.is_some() || content_type == "message/rfc822";
+    if is_attachment {
+        let filename = filename.unwrap_or_else(|| {
+            let extension = if content_type == "message/rfc822" { "eml" } else { "bin" };
+            format!("anexo-{}.{}", email.attachments.len() + 1, extension)
+        });
+        email.attachments.push(EmailAttachment { filename, content_type, data });
+        return;
+    }
+
+    let charset = params.get("charset").map(|c| c.as_str()).unwrap_or("utf-8");
+    match content_type.as_str() {
+        "text/plain" => {
+            if !email.body_text.is_empty() {
+                email.body_text.push_str("\n\n");
+            }
+            email.body_text.push_str(&decode_charset(&data, charset));
+        }
+        "text/html" if html_body.is_none() => {
+            *html_body = Some(decode_charset(&data, charset));
+        }
+        _ => {}
+    }
+}
+
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut part_start: Option<usize> = None;
+    let mut offset = 0;
+
+    for line in body.split_inclusive(|&b| b == b'\n') {
+        let trimmed = trim_line_end(line);
+        if trimmed.starts_with(delimiter.as_bytes()) {
+            if let Some(start) = part_start {
+                // A quebra de linha antes do delimitador pertence a ele
+                let end = strip_trailing_newline(&body[start..offset]);
+                parts.push(end);
+            }
+            if trimmed[delimiter.len()..].starts_with(b"--") {
+                return parts;
+            }
+            part_start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+
+    // Mensagem truncada sem delimitador final
+    if let Some(start) = part_start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+// "text/plain; charset=\"utf-8\"; name=a.pdf" → ("text/plain", {charset: utf-8, name: a.pdf})
+fn parse_header_params(value: &str) -> (String, HashMap<String, String>) {
+    let mut segments = split_params(value).into_iter();
+    let main = segments.next().unwrap_or_default().trim().to_lowercase();
+    let mut params = HashMap::new();
+
+    for segment in segments {
+        if let Some((name, value)) = segment.split_once('=') {
+            let name = name.trim().to_lowercase();
+            let value = value.trim().trim_matches('"').to_string();
+
+            // RFC 2231: filename*=UTF-8''nota%20fiscal.pdf (pode vir dividido em filename*0*, filename*1*)
+            if let Some(base) = name.split('*').next().filter(|_| name.contains('*')) {
+                let decoded = if name.ends_with('*') { decode_rfc2231(&value) } else { value };
+                params.entry(base.to_string())
+                    .and_modify(|v: &mut String| v.push_str(&decoded))
+                    .or_insert(decoded);
+            } else {
+                params.entry(name).or_insert(value);
+            }
+        }
+    }
+
+    (main, params)
+}
+
+// Separar por ';' respeitando aspas
+fn split_params(value: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in value.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            ';' if !in_quotes => segments.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    segments.push(current);
+    segments
+}
+
+fn decode_rfc2231(value: &str) -> String {
+    let mut parts = value.splitn(3, '\'');
+    let (charset, encoded) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(charset), Some(_language), Some(encoded)) => (charset, encoded),
+        _ => ("utf-8", value),
+    };
+    decode_charset(&percent_decode(encoded), charset)
+}
+
+fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = value.get(i + 1..i + 3).and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                output.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        output.push(bytes[i]);
+        i += 1;
+    }
+    output
+}
+
+// ================================
+// DECODIFICAÇÃO
+// ================================
+
+fn decode_transfer_encoding(body: &[u8], encoding: Option<&str>) -> Vec<u8> {
+    match encoding {
+        Some("base64") => decode_base64(body),
+        Some("quoted-printable") => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    }
+}
+
+pub fn decode_base64(input: &[u8]) -> Vec<u8> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' | b'-' => Some(62),
+            b'/' | b'_' => Some(63),
+            _ => None,
+        }
+    }
+
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &c in input {
+        if c == b'=' {
+            break;
+        }
+        // Quebras de linha e outros caracteres fora do alfabeto são ignorados
+        let Some(v) = value(c) else { continue };
+        buffer = (buffer << 6) | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    output
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut i = 0;
+
+    while i < input.len() {
+        if input[i] == b'=' {
+            // Quebra de linha suave
+            if input.get(i + 1) == Some(&b'\n') {
+                i += 2;
+                continue;
+            }
+            if input.get(i + 1) == Some(&b'\r') && input.get(i + 2) == Some(&b'\n') {
+                i += 3;
+                continue;
+            }
+            let hex = input.get(i + 1..i + 3)
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok());
+            if let Some(byte) = hex {
+                output.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        output.push(input[i]);
+        i += 1;
+    }
+    output
+}
+
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    match charset.trim().to_lowercase().as_str() {
+        "iso-8859-1" | "latin1" | "windows-1252" | "cp1252" | "iso-8859-15" => {
+            text_extractors::decode_windows_1252(bytes)
+        }
+        _ => text_extractors::decode_text(bytes),
+    }
+}
+
+// "=?UTF-8?B?Tm90YSBmaXNjYWw=?= recebida" → "Nota fiscal recebida"
+pub fn decode_encoded_words(value: &str) -> String {
+    let Ok(encoded_word) = Regex::new(r"=\?([^?]+)\?([BbQq])\?([^?]*)\?=") else {
+        return value.to_string();
+    };
+
+    let mut output = String::new();
+    let mut last_end = 0;
+    for captures in encoded_word.captures_iter(value) {
+        let whole = captures.get(0).unwrap();
+        let between = &value[last_end..whole.start()];
+        // Espaço entre duas encoded-words adjacentes é descartado
+        if last_end == 0 || !between.trim().is_empty() {
+            output.push_str(between);
+        }
+
+        let text = &captures[3];
+        let bytes = if captures[2].eq_ignore_ascii_case("B") {
+            decode_base64(text.as_bytes())
+        } else {
+            decode_quoted_printable(text.replace('_', " ").as_bytes())
+        };
+        // Charset pode vir com idioma (RFC 2231): "utf-8*pt"
+        let charset = captures[1].split('*').next().unwrap_or("utf-8");
+        output.push_str(&decode_charset(&bytes, charset));
+        last_end = whole.end();
+    }
+
+    output.push_str(&value[last_end..]);
+    output
+}
+
+fn header_text(headers: &Headers, name: &str) -> Option<String> {
+    headers.get(name)
+        .map(|value| decode_encoded_words(value).trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_date(value: &str) -> Option<DateTime<FixedOffset>> {
+    // Remover comentário final, ex.: "-0300 (BRT)"
+    let value = value.split('(').next().unwrap_or(value).trim();
+    DateTime::parse_from_rfc2822(value).ok()
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let mut end = line.len();
+    while end > 0 && matches!(line[end - 1], b'\n' | b'\r' | b' ' | b'\t') {
+        end -= 1;
+    }
+    &line[..end]
+}
+
+fn strip_trailing_newline(part: &[u8]) -> &[u8] {
+    part.strip_suffix(b"\r\n")
+        .or_else(|| part.strip_suffix(b"\n"))
+        .unwrap_or(part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    const EMAIL: &str = "From: =?UTF-8?Q?Jo=C3=A3o_Silva?= <joao@example.com>\r\n\
+To: financeiro@example.com\r\n\
+Subject: =?UTF-8?B?UmVjaWJvIGRlIGFsdWd1ZWw=?=\r\n \
+ janeiro\r\n\
+Date: Mon, 15 Jan 2024 23:10:00 -0300 (BRT)\r\n\
+Message-ID: <abc@example.com>\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"XYZ\"\r\n\
+\r\n\
+This is a multi-part message.\r\n\
+--XYZ\r\n\
+Content-Type: multipart/alternative; boundary=ALT\r\n\
+\r\n\
+--ALT\r\n\
+Content-Type: text/plain; charset=iso-8859-1\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Segue o recibo do aluguel de janeiro. Valor: R$ 1.500,00 =\r\n\
+(pago em 10/01/2024). Obrigado, Jo=E3o\r\n\
+--ALT\r\n\
+Content-Type: text/html; charset=utf-8\r\n\
+\r\n\
+<p>Segue o recibo</p>\r\n\
+--ALT--\r\n\
+--XYZ\r\n\
+Content-Type: application/pdf; name=\"recibo.pdf\"\r\n\
+Content-Disposition: attachment; filename*=UTF-8''recibo%20janeiro.pdf\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+JVBERi0xLjQK\r\n\
+JSVFT0YK\r\n\
+--XYZ--\r\n";
+
+    #[test]
+    fn test_parse_multipart_email() {
+        let email = parse_email(EMAIL.as_bytes());
+
+        assert_eq!(email.from.as_deref(), Some("João Silva <joao@example.com>"));
+        assert_eq!(email.subject.as_deref(), Some("Recibo de aluguel janeiro"));
+        assert_eq!(email.date.unwrap().date_naive(), NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        assert_eq!(
+            email.body_text,
+            "Segue o recibo do aluguel de janeiro. Valor: R$ 1.500,00 (pago em 10/01/2024). Obrigado, João"
+        );
+
+        assert_eq!(email.attachments.len(), 1);
+        assert_eq!(email.attachments[0].filename, "recibo janeiro.pdf");
+        assert_eq!(email.attachments[0].content_type, "application/pdf");
+        assert_eq!(email.attachments[0].data, b"%PDF-1.4\n%%EOF\n");
+
+        let fields = email.to_fields();
+        assert_eq!(fields["email_from"], "João Silva <joao@example.com>");
+        assert_eq!(fields["email_attachments"], "recibo janeiro.pdf");
+        assert!(email.to_text().starts_with("De: João Silva <joao@example.com>\nPara: financeiro@example.com"));
+    }
+
+    #[test]
+    fn test_html_only_email() {
+        let raw = "Subject: Boleto\nContent-Type: text/html\n\n<html><body><h1>Boleto</h1><p>Vencimento 20/02/2024</p></body></html>\n";
+        let email = parse_email(raw.as_bytes());
+        assert_eq!(email.body_text, "Boleto\nVencimento 20/02/2024");
+        assert!(email.date.is_none());
+        assert!(email.attachments.is_empty());
+    }
+
+    #[test]
+    fn test_split_mbox() {
+        let mbox = "From joao@example.com Mon Jan 15 23:10:00 2024\n\
+Subject: Primeira\n\n\
+Corpo um\n\
+>From the start\n\
+\n\
+From maria@example.com Tue Jan 16 08:00:00 2024\n\
+Subject: Segunda\n\n\
+Corpo dois\n";
+        let messages = split_mbox(mbox.as_bytes());
+        assert_eq!(messages.len(), 2);
+
+        let first = parse_email(&messages[0]);
+        assert_eq!(first.subject.as_deref(), Some("Primeira"));
+        assert_eq!(first.body_text, "Corpo um\nFrom the start");
+        assert_eq!(parse_email(&messages[1]).body_text, "Corpo dois");
+    }
+
+    #[test]
+    fn test_decoders() {
+        assert_eq!(decode_base64(b"Tm90YQ=="), b"Nota");
+        assert_eq!(decode_encoded_words("=?ISO-8859-1?Q?Cota=E7=E3o?= de =?utf-8?b?cHJlw6dv?="), "Cotação de preço");
+        assert_eq!(decode_encoded_words("Sem codificação"), "Sem codificação");
+    }
+}
@@ -0,0 +1,488 @@
+// REGRAS DE EXTRAÇÃO CONFIGURÁVEIS
+// Tipos de documento (palavras-chave com peso) e extratores de campos (regex, grupo,
+// normalizador, validador) lidos de extraction_rules.json na pasta de dados do app.
+// O arquivo é relido sempre que a data de modificação muda; se estiver inválido,
+// continuam valendo as últimas regras carregadas.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{br_validators, money_parser};
+
+pub const RULES_FILE_NAME: &str = "extraction_rules.json";
+
+static RULES_PATH: OnceLock<PathBuf> = OnceLock::new();
+static LOADED: Mutex<Option<LoadedRules>> = Mutex::new(None);
+
+struct LoadedRules {
+    modified: Option<SystemTime>,
+    rules: Arc<CompiledRules>,
+}
+
+// ================================
+// FORMATO DO ARQUIVO
+// ================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RuleSet {
+    // Tipo usado quando nenhum tipo atinge a pontuação mínima
+    #[serde(default = "default_document_type")]
+    pub default_type: String,
+    #[serde(default)]
+    pub document_types: Vec<DocumentTypeRule>,
+    // Extratores aplicados a qualquer tipo de documento
+    #[serde(default)]
+    pub fields: Vec<FieldRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DocumentTypeRule {
+    pub name: String,
+    #[serde(default)]
+    pub keywords: Vec<KeywordRule>,
+    #[serde(default = "default_min_score")]
+    pub min_score: f32,
+    // Extratores só deste tipo; têm prioridade sobre os universais de mesmo nome
+    #[serde(default)]
+    pub fields: Vec<FieldRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeywordRule {
+    // Comparado sem acentos e sem diferenciar maiúsculas
+    pub term: String,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldRule {
+    pub name: String,
+    pub pattern: String,
+    #[serde(default = "default_group")]
+    pub group: usize,
+    #[serde(default)]
+    pub normalizer: Option<Normalizer>,
+    #[serde(default)]
+    pub validator: Option<Validator>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Normalizer {
+    Trim,
+    CollapseSpaces,
+    Digits,
+    Uppercase,
+    Lowercase,
+    Cpf,
+    Cnpj,
+    // dd/mm/aaaa -> aaaa-mm-dd
+    DateIso,
+    // "1.234,56" ou "1,234.56" -> "1234.56"
+    Money,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Validator {
+    Cpf,
+    Cnpj,
+    CpfCnpj,
+    Date,
+    Boleto,
+    Pix,
+}
+
+fn default_document_type() -> String {
+    "documento_generico".to_string()
+}
+
+fn default_min_score() -> f32 {
+    1.0
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_group() -> usize {
+    1
+}
+
+impl Normalizer {
+    // None quando o valor não pode ser normalizado (a ocorrência é descartada)
+    fn apply(&self, value: &str) -> Option<String> {
+        let digits = || value.chars().filter(|c| c.is_ascii_digit()).collect::<String>();
+        match self {
+            Normalizer::Trim => Some(value.trim().to_string()),
+            Normalizer::CollapseSpaces => Some(value.split_whitespace().collect::<Vec<_>>().join(" ")),
+            Normalizer::Digits => Some(digits()),
+            Normalizer::Uppercase => Some(value.trim().to_uppercase()),
+            Normalizer::Lowercase => Some(value.trim().to_lowercase()),
+            Normalizer::Cpf => Some(digits()).filter(|d| d.len() == 11).map(|d| br_validators::format_cpf(&d)),
+            Normalizer::Cnpj => Some(digits()).filter(|d| d.len() == 14).map(|d| br_validators::format_cnpj(&d)),
+            Normalizer::DateIso => parse_date(value).map(|date| date.format("%Y-%m-%d").to_string()),
+            Normalizer::Money => money_parser::parse_number(value.trim().trim_end_matches(['.', ','])).map(money_parser::format_cents),
+        }
+    }
+}
+
+impl Validator {
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            Validator::Cpf => br_validators::is_valid_cpf(value),
+            Validator::Cnpj => br_validators::is_valid_cnpj(value),
+            Validator::CpfCnpj => br_validators::is_valid_cpf(value) || br_validators::is_valid_cnpj(value),
+            Validator::Date => parse_date(value).is_some(),
+            Validator::Boleto => br_validators::parse_boleto(value, chrono::Local::now().date_naive()).is_some(),
+            Validator::Pix => br_validators::parse_pix(value).is_some(),
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    ["%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y", "%Y-%m-%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+fn field(name: &str, pattern: &str, normalizer: Option<Normalizer>, validator: Option<Validator>) -> FieldRule {
+    FieldRule { name: name.to_string(), pattern: pattern.to_string(), group: 1, normalizer, validator }
+}
+
+fn keywords(terms: &[(&str, f32)]) -> Vec<KeywordRule> {
+    terms.iter().map(|(term, weight)| KeywordRule { term: term.to_string(), weight: *weight }).collect()
+}
+
+impl RuleSet {
+    /// Regras padrão, gravadas no arquivo na primeira execução para servir de modelo
+    pub fn builtin() -> Self {
+        let document_type = |name: &str, terms: &[(&str, f32)], fields: Vec<FieldRule>| DocumentTypeRule {
+            name: name.to_string(),
+            keywords: keywords(terms),
+            min_score: default_min_score(),
+            fields,
+        };
+
+        RuleSet {
+            default_type: default_document_type(),
+            document_types: vec![
+                document_type(
+                    "nota_fiscal",
+                    &[("nota fiscal", 3.0), ("nf-e", 3.0), ("danfe", 3.0), ("chave de acesso", 2.0), ("icms", 2.0), ("cfop", 2.0)],
+                    vec![
+                        field("numero_nf", r"(?:N[úu]mero|N[°º])[:\s]*(\d+)", None, None),
+                        field("serie_nf", r"(?:S[eé]rie)[:\s]*(\d+)", None, None),
+                    ],
+                ),
+                document_type(
+                    "contrato",
+                    &[("contrato", 2.0), ("partes contratantes", 3.0), ("clausula", 2.0), ("contratante", 1.0), ("contratada", 1.0)],
+                    vec![field("numero_contrato", r"(?:Contrato|Contract)[:\s]*n[°º]?\s*([A-Z0-9-/]+)", None, None)],
+                ),
+                document_type(
+                    "recibo",
+                    &[("recibo", 3.0), ("comprovante", 2.0), ("valor pago", 2.0), ("pagamento", 1.0)],
+                    Vec::new(),
+                ),
+                document_type(
+                    "documento_rh",
+                    &[("holerite", 3.0), ("funcionario", 2.0), ("salario", 2.0), ("admissao", 2.0)],
+                    Vec::new(),
+                ),
+                document_type(
+                    "documento_juridico",
+                    &[("sentenca", 3.0), ("tribunal", 2.0), ("advogado", 2.0), ("processo", 1.0)],
+                    Vec::new(),
+                ),
+                document_type("relatorio", &[("relatorio", 2.0), ("analise", 1.0)], Vec::new()),
+            ],
+            fields: vec![
+                field("valor_total", r"(?:R\$|total|valor)[:\s]*([0-9,.]+)", Some(Normalizer::Money), None),
+                field("data", r"(\d{2}/\d{2}/\d{4})", None, Some(Validator::Date)),
+            ],
+        }
+    }
+
+    /// Compilar as expressões; o erro diz qual regra está inválida
+    pub fn compile(self) -> Result<CompiledRules, String> {
+        let compile_fields = |rules: &[FieldRule]| -> Result<Vec<Regex>, String> {
+            rules
+                .iter()
+                .map(|rule| {
+                    let regex = Regex::new(&rule.pattern)
+                        .map_err(|e| format!("Regra '{}' com expressão inválida: {}", rule.name, e))?;
+                    if rule.group > regex.captures_len() - 1 {
+                        return Err(format!("Regra '{}' usa o grupo {}, mas a expressão só tem {}", rule.name, rule.group, regex.captures_len() - 1));
+                    }
+                    Ok(regex)
+                })
+                .collect()
+        };
+
+        let universal = compile_fields(&self.fields)?;
+        let by_type = self
+            .document_types
+            .iter()
+            .map(|document_type| compile_fields(&document_type.fields))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(CompiledRules { set: self, universal, by_type })
+    }
+}
+
+// ================================
+// APLICAÇÃO DAS REGRAS
+// ================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TypeScore {
+    pub document_type: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Classification {
+    pub document_type: String,
+    // Pontuação de todos os tipos, da maior para a menor
+    pub scores: Vec<TypeScore>,
+}
+
+#[derive(Debug)]
+pub struct CompiledRules {
+    set: RuleSet,
+    universal: Vec<Regex>,
+    by_type: Vec<Vec<Regex>>,
+}
+
+impl CompiledRules {
+    pub fn rule_set(&self) -> &RuleSet {
+        &self.set
+    }
+
+    /// Soma dos pesos das palavras-chave presentes; vence o maior total que atinja o
+    /// mínimo do tipo (empate: o tipo que vem antes no arquivo)
+    pub fn classify(&self, text: &str) -> Classification {
+        let folded = br_validators::fold(text);
+
+        let mut scores: Vec<TypeScore> = self
+            .set
+            .document_types
+            .iter()
+            .map(|document_type| TypeScore {
+                document_type: document_type.name.clone(),
+                score: document_type
+                    .keywords
+                    .iter()
+                    .filter(|keyword| folded.contains(&br_validators::fold(&keyword.term)))
+                    .map(|keyword| keyword.weight)
+                    .sum(),
+            })
+            .collect();
+
+        let winner = self
+            .set
+            .document_types
+            .iter()
+            .zip(&scores)
+            .filter(|(rule, score)| score.score > 0.0 && score.score >= rule.min_score)
+            .fold(None::<&TypeScore>, |best, (_, score)| match best {
+                Some(best) if best.score >= score.score => Some(best),
+                _ => Some(score),
+            })
+            .map(|score| score.document_type.clone())
+            .unwrap_or_else(|| self.set.default_type.clone());
+
+        scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+        Classification { document_type: winner, scores }
+    }
+
+    /// Campos do tipo informado e universais. Para cada nome vale a primeira ocorrência
+    /// que passar pelo normalizador e pelo validador.
+    pub fn extract_fields(&self, document_type: &str, text: &str) -> HashMap<String, String> {
+        let mut fields = HashMap::new();
+
+        let type_rules = self
+            .set
+            .document_types
+            .iter()
+            .position(|rule| rule.name == document_type)
+            .map(|index| self.set.document_types[index].fields.iter().zip(&self.by_type[index]));
+
+        for (rule, regex) in type_rules.into_iter().flatten().chain(self.set.fields.iter().zip(&self.universal)) {
+            if fields.contains_key(&rule.name) {
+                continue;
+            }
+            if let Some(value) = first_match(rule, regex, text) {
+                fields.insert(rule.name.clone(), value);
+            }
+        }
+        fields
+    }
+}
+
+fn first_match(rule: &FieldRule, regex: &Regex, text: &str) -> Option<String> {
+    regex.captures_iter(text).find_map(|captures| {
+        let raw = captures.get(rule.group)?.as_str();
+        let value = match rule.normalizer {
+            Some(normalizer) => normalizer.apply(raw)?,
+            None => raw.to_string(),
+        };
+        match rule.validator {
+            Some(validator) if !validator.accepts(&value) => None,
+            _ => Some(value),
+        }
+    })
+}
+
+// ================================
+// ARQUIVO DE REGRAS (RECARGA AUTOMÁTICA)
+// ================================
+
+/// Interpretar e compilar um conjunto de regras em JSON
+pub fn parse_rules(json: &str) -> Result<CompiledRules, String> {
+    let set: RuleSet = serde_json::from_str(json).map_err(|e| format!("JSON de regras inválido: {}", e))?;
+    set.compile()
+}
+
+fn builtin_rules() -> CompiledRules {
+    RuleSet::builtin().compile().unwrap_or_else(|e| {
+        log::error!("❌ Regras padrão inválidas: {}", e);
+        CompiledRules {
+            set: RuleSet { default_type: default_document_type(), document_types: Vec::new(), fields: Vec::new() },
+            universal: Vec::new(),
+            by_type: Vec::new(),
+        }
+    })
+}
+
+/// Registrar o arquivo de regras (pasta de dados do app), criando-o com as regras padrão
+pub fn configure_rules_path(path: PathBuf) {
+    if !path.exists() {
+        match serde_json::to_string_pretty(&RuleSet::builtin()) {
+            Ok(json) => match std::fs::write(&path, json) {
+                Ok(()) => log::info!("📝 Regras de extração padrão gravadas em {:?}", path),
+                Err(e) => log::warn!("⚠️ Não foi possível gravar {:?}: {:?}", path, e),
+            },
+            Err(e) => log::warn!("⚠️ Erro ao serializar regras padrão: {:?}", e),
+        }
+    }
+    let _ = RULES_PATH.set(path);
+}
+
+pub fn rules_path() -> Option<&'static Path> {
+    RULES_PATH.get().map(PathBuf::as_path)
+}
+
+/// Regras em vigor. Relê o arquivo quando a data de modificação muda.
+pub fn current() -> Arc<CompiledRules> {
+    let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    let path = rules_path();
+    let modified = path.and_then(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok());
+
+    if let Some(current) = loaded.as_ref() {
+        if current.modified == modified {
+            return current.rules.clone();
+        }
+    }
+
+    let rules = match (path, modified) {
+        (Some(path), Some(_)) => {
+            match std::fs::read_to_string(path).map_err(|e| format!("{:?}", e)).and_then(|json| parse_rules(&json)) {
+                Ok(rules) => {
+                    log::info!("🔄 Regras de extração carregadas de {:?}", path);
+                    rules
+                }
+                Err(e) => {
+                    log::error!("❌ Regras de extração inválidas em {:?}: {}", path, e);
+                    if let Some(current) = loaded.as_mut() {
+                        // Não tentar de novo até o arquivo mudar outra vez
+                        current.modified = modified;
+                        return current.rules.clone();
+                    }
+                    builtin_rules()
+                }
+            }
+        }
+        _ => builtin_rules(),
+    };
+
+    let rules = Arc::new(rules);
+    *loaded = Some(LoadedRules { modified, rules: rules.clone() });
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_rules_compile_and_round_trip() {
+        let json = serde_json::to_string(&RuleSet::builtin()).unwrap();
+        let rules = parse_rules(&json).unwrap();
+        assert_eq!(rules.set, RuleSet::builtin());
+    }
+
+    #[test]
+    fn test_classify_by_weight_not_first_hit() {
+        let rules = builtin_rules();
+
+        // "pagamento" sozinho não transforma um contrato em recibo
+        let classification = rules.classify("CONTRATO DE PRESTAÇÃO DE SERVIÇOS. Cláusula 3: o pagamento será mensal.");
+        assert_eq!(classification.document_type, "contrato");
+        assert_eq!(classification.scores[0].document_type, "contrato");
+        assert_eq!(classification.scores.len(), RuleSet::builtin().document_types.len());
+
+        assert_eq!(rules.classify("Lista de compras").document_type, "documento_generico");
+    }
+
+    #[test]
+    fn test_builtin_total_is_normalized_to_decimal() {
+        let rules = builtin_rules();
+        assert_eq!(rules.extract_fields("recibo", "Recibo. Total: R$ 1.234,56.")["valor_total"], "1234.56");
+        assert_eq!(rules.extract_fields("recibo", "Receipt total: 1,234.56")["valor_total"], "1234.56");
+    }
+
+    #[test]
+    fn test_extract_fields_with_normalizer_and_validator() {
+        let rules = parse_rules(
+            r#"{
+                "document_types": [
+                    { "name": "boleto", "keywords": [{ "term": "beneficiário", "weight": 2 }],
+                      "fields": [{ "name": "documento", "pattern": "CNPJ[:\\s]*([\\d./-]+)", "validator": "cnpj", "normalizer": "cnpj" }] }
+                ],
+                "fields": [{ "name": "vencimento", "pattern": "Vencimento:?\\s*(\\S+)", "normalizer": "date_iso" }]
+            }"#,
+        )
+        .unwrap();
+
+        let text = "Beneficiario: Loja\nCNPJ: 11.222.333/0001-82\nCNPJ 11222333000181\nVencimento: 32/01/2025\nVencimento 15/07/2025";
+        let classification = rules.classify(text);
+        assert_eq!(classification.document_type, "boleto");
+
+        let fields = rules.extract_fields(&classification.document_type, text);
+        // O primeiro CNPJ tem dígito errado e a primeira data não existe
+        assert_eq!(fields["documento"], "11.222.333/0001-81");
+        assert_eq!(fields["vencimento"], "2025-07-15");
+
+        // Campos do tipo não valem para outros tipos
+        assert!(!rules.extract_fields("documento_generico", text).contains_key("documento"));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_reported() {
+        let error = parse_rules(r#"{ "fields": [{ "name": "quebrado", "pattern": "(" }] }"#).unwrap_err();
+        assert!(error.contains("quebrado"));
+
+        let error = parse_rules(r#"{ "fields": [{ "name": "grupo", "pattern": "abc", "group": 1 }] }"#).unwrap_err();
+        assert!(error.contains("grupo"));
+    }
+}
@@ -0,0 +1,167 @@
+// ARMAZENAMENTO GERENCIADO DE ARQUIVOS - ENDEREÇADO POR CONTEÚDO (SHA-256)
+// Cada arquivo importado é copiado para ARKIVE/files/<user_id>/<aa>/<hash>.<ext>,
+// de forma que mover ou apagar o original não quebra o arquivo.
+
+use sha2::{Digest, Sha256};
+use std::fmt::Write as FmtWrite;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    pub stored_path: PathBuf,
+    pub file_hash: String,
+    pub file_size: i64,
+    pub already_existed: bool,
+}
+
+pub struct FileStore {
+    root: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(root: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&root)?;
+        log::info!("📦 Armazenamento de arquivos em: {}", root.display());
+        Ok(FileStore { root })
+    }
+
+    /// Diretório de arquivos de um usuário (o mesmo que o backup compacta)
+    pub fn user_dir(&self, user_id: &str) -> PathBuf {
+        self.root.join(user_id)
+    }
+
+    /// Copia o arquivo de origem para o armazenamento gerenciado.
+    /// Se já existir uma cópia com o mesmo hash, ela é reutilizada.
+    pub fn store_file(&self, user_id: &str, source: &Path) -> io::Result<StoredFile> {
+        let file_hash = hash_file(source)?;
+        self.store_file_with_hash(user_id, source, file_hash)
+    }
+
+    /// Idem, com o SHA-256 já calculado por quem chama
+    pub fn store_file_with_hash(&self, user_id: &str, source: &Path, file_hash: String) -> io::Result<StoredFile> {
+        let file_size = fs::metadata(source)?.len() as i64;
+
+        let stored_path = self.path_for(user_id, &file_hash, source.extension().and_then(|e| e.to_str()));
+
+        if stored_path.exists() {
+            log::info!("♻️ Arquivo já existe no armazenamento: {}", stored_path.display());
+            return Ok(StoredFile {
+                stored_path,
+                file_hash,
+                file_size,
+                already_existed: true,
+            });
+        }
+
+        if let Some(parent) = stored_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Copiar para arquivo temporário e renomear (evita cópias parciais)
+        let temp_path = stored_path.with_extension("partial");
+        fs::copy(source, &temp_path)?;
+        if let Err(e) = fs::rename(&temp_path, &stored_path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+
+        log::info!("✅ Arquivo armazenado: {} → {}", source.display(), stored_path.display());
+
+        Ok(StoredFile {
+            stored_path,
+            file_hash,
+            file_size,
+            already_existed: false,
+        })
+    }
+
+    /// Remove a cópia gerenciada (usado quando nenhum documento referencia mais o hash)
+    pub fn remove_file(&self, stored_path: &Path) -> io::Result<()> {
+        if !stored_path.starts_with(&self.root) {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Caminho fora do armazenamento gerenciado",
+            ));
+        }
+
+        if stored_path.exists() {
+            fs::remove_file(stored_path)?;
+            log::info!("🗑️ Arquivo removido do armazenamento: {}", stored_path.display());
+        }
+        Ok(())
+    }
+
+    fn path_for(&self, user_id: &str, file_hash: &str, extension: Option<&str>) -> PathBuf {
+        let file_name = match extension {
+            Some(ext) if !ext.is_empty() => format!("{}.{}", file_hash, ext.to_lowercase()),
+            _ => file_hash.to_string(),
+        };
+
+        self.user_dir(user_id).join(&file_hash[..2]).join(file_name)
+    }
+}
+
+/// SHA-256 do conteúdo do arquivo em hexadecimal (leitura em blocos)
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    let mut hash_string = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        write!(&mut hash_string, "{:02x}", byte).unwrap();
+    }
+    Ok(hash_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_file_is_content_addressed() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let store_dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(store_dir.path().join("files")).unwrap();
+
+        let a = source_dir.path().join("nota.PDF");
+        let b = source_dir.path().join("copia.pdf");
+        fs::write(&a, b"conteudo").unwrap();
+        fs::write(&b, b"conteudo").unwrap();
+
+        let first = store.store_file("user-1", &a).unwrap();
+        let second = store.store_file("user-1", &b).unwrap();
+
+        assert!(!first.already_existed);
+        assert!(second.already_existed);
+        assert_eq!(first.stored_path, second.stored_path);
+        assert_eq!(first.file_size, 8);
+        assert!(first.stored_path.starts_with(store.user_dir("user-1")));
+        assert!(first.stored_path.to_string_lossy().ends_with(".pdf"));
+
+        // Apagar o original não afeta a cópia gerenciada
+        fs::remove_file(&a).unwrap();
+        assert_eq!(fs::read(&first.stored_path).unwrap(), b"conteudo");
+    }
+
+    #[test]
+    fn test_remove_file_outside_store_is_rejected() {
+        let store_dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(store_dir.path().join("files")).unwrap();
+        let outside = store_dir.path().join("outro.txt");
+        fs::write(&outside, b"x").unwrap();
+
+        assert!(store.remove_file(&outside).is_err());
+        assert!(outside.exists());
+    }
+}
@@ -0,0 +1,562 @@
+// PRÉ-PROCESSAMENTO DE IMAGENS PARA OCR
+// Fotos de celular (recibos, cupons) chegam tortas, com sombra e fundo da mesa ao redor.
+// Antes do Tesseract: rotação EXIF, tons de cinza, recorte da borda, ampliação de imagens
+// pequenas, remoção de ruído, correção de inclinação e binarização adaptativa.
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GrayImage, Luma};
+use serde::{Deserialize, Serialize};
+
+// Largura mínima para o Tesseract ler caracteres pequenos com segurança
+const MIN_OCR_WIDTH: u32 = 1000;
+const MAX_UPSCALE: f32 = 3.0;
+// Inclinação máxima procurada e abaixo da qual a imagem não é girada (graus)
+const MAX_SKEW_DEGREES: f32 = 10.0;
+const MIN_SKEW_DEGREES: f32 = 0.3;
+
+/// Etapas a executar; todas ligadas por padrão. Campos ausentes no JSON ficam ligados
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PreprocessOptions {
+    pub exif_rotation: bool,
+    pub grayscale: bool,
+    pub border_crop: bool,
+    pub upscale: bool,
+    pub denoise: bool,
+    pub deskew: bool,
+    pub binarize: bool,
+}
+
+impl Default for PreprocessOptions {
+    fn default() -> Self {
+        Self {
+            exif_rotation: true,
+            grayscale: true,
+            border_crop: true,
+            upscale: true,
+            denoise: true,
+            deskew: true,
+            binarize: true,
+        }
+    }
+}
+
+impl PreprocessOptions {
+    /// Nenhuma etapa: a imagem vai ao Tesseract como está
+    pub fn none() -> Self {
+        Self {
+            exif_rotation: false,
+            grayscale: false,
+            border_crop: false,
+            upscale: false,
+            denoise: false,
+            deskew: false,
+            binarize: false,
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::none()
+    }
+
+    // Etapas que trabalham em tons de cinza convertem a imagem mesmo sem `grayscale`
+    fn needs_grayscale(&self) -> bool {
+        self.grayscale || self.border_crop || self.denoise || self.deskew || self.binarize
+    }
+}
+
+pub struct Preprocessed {
+    pub image: DynamicImage,
+    // Etapas que de fato alteraram a imagem, na ordem em que rodaram
+    pub steps: Vec<String>,
+    pub geometry: Geometry,
+}
+
+/// Recorte, ampliação e giro aplicados, para levar coordenadas do OCR (na imagem tratada)
+/// de volta à imagem original já na orientação EXIF, que é a exibida ao usuário
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geometry {
+    pub original_width: u32,
+    pub original_height: u32,
+    crop_left: u32,
+    crop_top: u32,
+    scale: f32,
+    rotation_degrees: f32,
+    // Dimensões da imagem no momento do giro (o giro mantém o tamanho)
+    rotated_width: u32,
+    rotated_height: u32,
+}
+
+impl Geometry {
+    pub fn identity(width: u32, height: u32) -> Self {
+        Self {
+            original_width: width,
+            original_height: height,
+            crop_left: 0,
+            crop_top: 0,
+            scale: 1.0,
+            rotation_degrees: 0.0,
+            rotated_width: width,
+            rotated_height: height,
+        }
+    }
+
+    /// Retângulo (left, top, width, height) da imagem tratada na imagem original
+    pub fn to_original(&self, left: u32, top: u32, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let corners = [
+            (left as f32, top as f32),
+            ((left + width) as f32, top as f32),
+            (left as f32, (top + height) as f32),
+            ((left + width) as f32, (top + height) as f32),
+        ];
+
+        // Mesmo mapeamento de `rotate`: ponto da imagem girada → ponto antes do giro
+        let (sin, cos) = self.rotation_degrees.to_radians().sin_cos();
+        let (center_x, center_y) = (self.rotated_width as f32 / 2.0, self.rotated_height as f32 / 2.0);
+        let points = corners.map(|(x, y)| {
+            let (u, v) = (x - center_x, y - center_y);
+            let (x, y) = (u * cos - v * sin + center_x, u * sin + v * cos + center_y);
+            (x / self.scale + self.crop_left as f32, y / self.scale + self.crop_top as f32)
+        });
+
+        let clamp_x = |x: f32| x.round().clamp(0.0, self.original_width as f32) as u32;
+        let clamp_y = |y: f32| y.round().clamp(0.0, self.original_height as f32) as u32;
+        let min_x = clamp_x(points.iter().map(|p| p.0).fold(f32::MAX, f32::min));
+        let max_x = clamp_x(points.iter().map(|p| p.0).fold(f32::MIN, f32::max));
+        let min_y = clamp_y(points.iter().map(|p| p.1).fold(f32::MAX, f32::min));
+        let max_y = clamp_y(points.iter().map(|p| p.1).fold(f32::MIN, f32::max));
+        (min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+}
+
+/// Aplicar as etapas habilitadas. `orientation` é a tag EXIF Orientation (1-8), se houver
+pub fn preprocess(image: DynamicImage, orientation: Option<u16>, options: &PreprocessOptions) -> Preprocessed {
+    let mut steps = Vec::new();
+    let mut image = image;
+
+    if options.exif_rotation {
+        if let Some(orientation) = orientation.filter(|&o| o > 1 && o <= 8) {
+            image = apply_orientation(image, orientation);
+            log::debug!("🔄 Orientação EXIF {} aplicada", orientation);
+            steps.push("exif_rotation".to_string());
+        }
+    }
+
+    let mut geometry = Geometry::identity(image.width(), image.height());
+
+    if !options.needs_grayscale() {
+        if options.upscale {
+            if let Some(factor) = upscale_factor(image.width()) {
+                let (width, height) = scaled(image.width(), image.height(), factor);
+                image = image.resize_exact(width, height, FilterType::CatmullRom);
+                geometry.scale = factor;
+                steps.push("upscale".to_string());
+            }
+        }
+        return Preprocessed { image, steps, geometry };
+    }
+
+    let mut gray = image.to_luma8();
+    steps.push("grayscale".to_string());
+
+    if options.border_crop {
+        if let Some((x, y, width, height)) = content_bounds(&gray) {
+            gray = imageops::crop_imm(&gray, x, y, width, height).to_image();
+            (geometry.crop_left, geometry.crop_top) = (x, y);
+            log::debug!("✂️ Borda recortada: {}x{} a partir de ({}, {})", width, height, x, y);
+            steps.push("border_crop".to_string());
+        }
+    }
+
+    if options.upscale {
+        if let Some(factor) = upscale_factor(gray.width()) {
+            let (width, height) = scaled(gray.width(), gray.height(), factor);
+            gray = imageops::resize(&gray, width, height, FilterType::CatmullRom);
+            geometry.scale = factor;
+            log::debug!("🔍 Imagem ampliada {:.1}x", factor);
+            steps.push("upscale".to_string());
+        }
+    }
+
+    if options.denoise {
+        gray = median_filter(&gray);
+        steps.push("denoise".to_string());
+    }
+
+    if options.deskew {
+        let angle = estimate_skew(&gray);
+        if angle.abs() >= MIN_SKEW_DEGREES {
+            gray = rotate(&gray, angle);
+            geometry.rotation_degrees = angle;
+            (geometry.rotated_width, geometry.rotated_height) = gray.dimensions();
+            log::debug!("📐 Inclinação corrigida: {:.1}°", angle);
+            steps.push("deskew".to_string());
+        }
+    }
+
+    if options.binarize {
+        gray = adaptive_threshold(&gray);
+        steps.push("binarize".to_string());
+    }
+
+    Preprocessed { image: DynamicImage::ImageLuma8(gray), steps, geometry }
+}
+
+fn apply_orientation(image: DynamicImage, orientation: u16) -> DynamicImage {
+    match orientation {
+        2 => image.fliph(),
+        3 => image.rotate180(),
+        4 => image.flipv(),
+        5 => image.rotate90().fliph(),
+        6 => image.rotate90(),
+        7 => image.rotate270().fliph(),
+        8 => image.rotate270(),
+        _ => image,
+    }
+}
+
+fn upscale_factor(width: u32) -> Option<f32> {
+    if width == 0 || width >= MIN_OCR_WIDTH {
+        return None;
+    }
+    Some((MIN_OCR_WIDTH as f32 / width as f32).min(MAX_UPSCALE))
+}
+
+fn scaled(width: u32, height: u32, factor: f32) -> (u32, u32) {
+    ((width as f32 * factor).round() as u32, (height as f32 * factor).round() as u32)
+}
+
+// ================================
+// EXIF
+// ================================
+
+/// Tag Orientation (0x0112) de um JPEG (segmento APP1 "Exif") ou TIFF
+pub fn exif_orientation(data: &[u8]) -> Option<u16> {
+    let tiff = if data.starts_with(&[0xFF, 0xD8]) {
+        jpeg_exif_segment(data)?
+    } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+        data
+    } else {
+        return None;
+    };
+
+    let little_endian = tiff.get(0..2)? == b"II";
+    let read_u16 = |offset: usize| -> Option<u16> {
+        let bytes = [*tiff.get(offset)?, *tiff.get(offset + 1)?];
+        Some(if little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    };
+    let read_u32 = |offset: usize| -> Option<u32> {
+        let bytes: [u8; 4] = tiff.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    };
+
+    let ifd = read_u32(4)? as usize;
+    let entries = read_u16(ifd)? as usize;
+    (0..entries)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|&entry| read_u16(entry) == Some(0x0112))
+        .and_then(|entry| read_u16(entry + 8))
+        .filter(|orientation| (1..=8).contains(orientation))
+}
+
+fn jpeg_exif_segment(data: &[u8]) -> Option<&[u8]> {
+    let mut position = 2;
+    while position + 4 <= data.len() && data[position] == 0xFF {
+        let marker = data[position + 1];
+        // Fim da imagem ou início dos dados comprimidos: não há mais metadados
+        if marker == 0xD9 || marker == 0xDA {
+            break;
+        }
+        let length = u16::from_be_bytes([data[position + 2], data[position + 3]]) as usize;
+        let segment = data.get(position + 4..position + 2 + length)?;
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return Some(&segment[6..]);
+        }
+        position += 2 + length;
+    }
+    None
+}
+
+// ================================
+// ETAPAS EM TONS DE CINZA
+// ================================
+
+fn otsu_threshold(gray: &GrayImage) -> u8 {
+    let mut histogram = [0u64; 256];
+    for pixel in gray.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+
+    let total = gray.pixels().len() as f64;
+    let sum: f64 = histogram.iter().enumerate().map(|(i, &count)| i as f64 * count as f64).sum();
+    let (mut background_weight, mut background_sum) = (0.0, 0.0);
+    let (mut best_threshold, mut best_variance) = (127u8, 0.0);
+
+    for (threshold, &count) in histogram.iter().enumerate() {
+        background_weight += count as f64;
+        if background_weight == 0.0 {
+            continue;
+        }
+        let foreground_weight = total - background_weight;
+        if foreground_weight == 0.0 {
+            break;
+        }
+        background_sum += threshold as f64 * count as f64;
+        let background_mean = background_sum / background_weight;
+        let foreground_mean = (sum - background_sum) / foreground_weight;
+        let variance = background_weight * foreground_weight * (background_mean - foreground_mean).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best_threshold = threshold as u8;
+        }
+    }
+
+    best_threshold
+}
+
+// Região do papel: linhas e colunas da borda com maioria de pixels escuros (mesa, tampa
+// do scanner) são descartadas. Não recorta se sobraria menos da metade da imagem.
+fn content_bounds(gray: &GrayImage) -> Option<(u32, u32, u32, u32)> {
+    let (width, height) = gray.dimensions();
+    if width < 4 || height < 4 {
+        return None;
+    }
+    let threshold = otsu_threshold(gray);
+    let bright = |x: u32, y: u32| gray.get_pixel(x, y)[0] > threshold;
+
+    let row_is_paper = |y: u32| (0..width).filter(|&x| bright(x, y)).count() * 2 > width as usize;
+    let column_is_paper = |x: u32| (0..height).filter(|&y| bright(x, y)).count() * 2 > height as usize;
+
+    let top = (0..height).find(|&y| row_is_paper(y))?;
+    let bottom = (0..height).rev().find(|&y| row_is_paper(y))?;
+    let left = (0..width).find(|&x| column_is_paper(x))?;
+    let right = (0..width).rev().find(|&x| column_is_paper(x))?;
+
+    let (crop_width, crop_height) = (right.checked_sub(left)? + 1, bottom.checked_sub(top)? + 1);
+    if (crop_width, crop_height) == (width, height) || crop_width * 2 < width || crop_height * 2 < height {
+        return None;
+    }
+    Some((left, top, crop_width, crop_height))
+}
+
+// Mediana 3x3: remove pontos isolados (ruído do sensor, poeira) preservando as bordas
+fn median_filter(gray: &GrayImage) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    let mut output = gray.clone();
+    if width < 3 || height < 3 {
+        return output;
+    }
+
+    let mut window = [0u8; 9];
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            let mut i = 0;
+            for dy in 0..3 {
+                for dx in 0..3 {
+                    window[i] = gray.get_pixel(x + dx - 1, y + dy - 1)[0];
+                    i += 1;
+                }
+            }
+            window.sort_unstable();
+            output.put_pixel(x, y, Luma([window[4]]));
+        }
+    }
+    output
+}
+
+// Inclinação das linhas de texto (graus, positiva = descendo para a direita) pelo perfil
+// de projeção: no ângulo certo as linhas viram picos e a soma dos quadrados é máxima
+fn estimate_skew(gray: &GrayImage) -> f32 {
+    // Estimar em uma cópia reduzida; o ângulo não depende da escala
+    let sample = if gray.width() > 800 {
+        let factor = 800.0 / gray.width() as f32;
+        let (width, height) = scaled(gray.width(), gray.height(), factor);
+        imageops::resize(gray, width, height.max(1), FilterType::Triangle)
+    } else {
+        gray.clone()
+    };
+
+    let threshold = otsu_threshold(&sample);
+    let ink: Vec<(f32, f32)> = sample.enumerate_pixels()
+        .filter(|(_, _, pixel)| pixel[0] <= threshold)
+        .map(|(x, y, _)| (x as f32, y as f32))
+        .collect();
+    if ink.len() < 50 {
+        return 0.0;
+    }
+
+    let diagonal = ((sample.width().pow(2) + sample.height().pow(2)) as f32).sqrt() as usize;
+    let score = |degrees: f32| -> f64 {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let mut rows = vec![0u32; diagonal * 2 + 1];
+        for &(x, y) in &ink {
+            let row = (y * cos - x * sin).round() as i64 + diagonal as i64;
+            if let Some(count) = rows.get_mut(row as usize) {
+                *count += 1;
+            }
+        }
+        rows.iter().map(|&count| (count as f64).powi(2)).sum()
+    };
+
+    let best = |from: f32, to: f32, step: f32| -> f32 {
+        let steps = ((to - from) / step).round() as i32;
+        (0..=steps)
+            .map(|i| from + i as f32 * step)
+            .map(|angle| (angle, score(angle)))
+            .fold((0.0, f64::MIN), |best, candidate| if candidate.1 > best.1 { candidate } else { best })
+            .0
+    };
+
+    let coarse = best(-MAX_SKEW_DEGREES, MAX_SKEW_DEGREES, 0.5);
+    best(coarse - 0.5, coarse + 0.5, 0.1)
+}
+
+// Girar em torno do centro desfazendo a inclinação; cantos expostos ficam brancos
+fn rotate(gray: &GrayImage, degrees: f32) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (center_x, center_y) = (width as f32 / 2.0, height as f32 / 2.0);
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let (u, v) = (x as f32 - center_x, y as f32 - center_y);
+        let source_x = u * cos - v * sin + center_x;
+        let source_y = u * sin + v * cos + center_y;
+        Luma([bilinear(gray, source_x, source_y).unwrap_or(255)])
+    })
+}
+
+fn bilinear(gray: &GrayImage, x: f32, y: f32) -> Option<u8> {
+    let (width, height) = gray.dimensions();
+    if x < 0.0 || y < 0.0 || x > (width - 1) as f32 || y > (height - 1) as f32 {
+        return None;
+    }
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+    let pixel = |x: u32, y: u32| gray.get_pixel(x, y)[0] as f32;
+
+    let top = pixel(x0, y0) * (1.0 - fx) + pixel(x1, y0) * fx;
+    let bottom = pixel(x0, y1) * (1.0 - fx) + pixel(x1, y1) * fx;
+    Some((top * (1.0 - fy) + bottom * fy).round() as u8)
+}
+
+// Limiar adaptativo de Bradley: cada pixel é comparado à média da vizinhança, o que
+// tolera sombras e iluminação desigual que um limiar global não tolera
+fn adaptive_threshold(gray: &GrayImage) -> GrayImage {
+    const SENSITIVITY: u64 = 15; // % abaixo da média local para ser tinta
+    let (width, height) = gray.dimensions();
+    let (w, h) = (width as usize, height as usize);
+
+    // Imagem integral com uma linha/coluna de zeros à esquerda e no topo
+    let mut integral = vec![0u64; (w + 1) * (h + 1)];
+    for y in 0..h {
+        let mut row_sum = 0u64;
+        for x in 0..w {
+            row_sum += gray.get_pixel(x as u32, y as u32)[0] as u64;
+            integral[(y + 1) * (w + 1) + x + 1] = integral[y * (w + 1) + x + 1] + row_sum;
+        }
+    }
+
+    let half = (w.max(h) / 16).max(7);
+    GrayImage::from_fn(width, height, |x, y| {
+        let (x, y) = (x as usize, y as usize);
+        let (x0, y0) = (x.saturating_sub(half), y.saturating_sub(half));
+        let (x1, y1) = ((x + half + 1).min(w), (y + half + 1).min(h));
+        let count = ((x1 - x0) * (y1 - y0)) as u64;
+        let sum = integral[y1 * (w + 1) + x1] + integral[y0 * (w + 1) + x0]
+            - integral[y0 * (w + 1) + x1] - integral[y1 * (w + 1) + x0];
+
+        let value = gray.get_pixel(x as u32, y as u32)[0] as u64;
+        Luma([if value * count * 100 <= sum * (100 - SENSITIVITY) { 0 } else { 255 }])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Página branca com linhas de "texto" inclinadas `degrees` graus
+    fn skewed_lines(width: u32, height: u32, degrees: f32) -> GrayImage {
+        let slope = degrees.to_radians().tan();
+        GrayImage::from_fn(width, height, |x, y| {
+            let baseline = y as f32 - x as f32 * slope;
+            let on_line = baseline > 20.0 && (baseline as u32 % 40) < 6;
+            let inside = x > 40 && x < width - 40;
+            Luma([if on_line && inside { 20 } else { 240 }])
+        })
+    }
+
+    #[test]
+    fn test_exif_orientation_from_jpeg() {
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x22];
+        jpeg.extend_from_slice(b"Exif\0\0MM\0*");
+        jpeg.extend_from_slice(&[0, 0, 0, 8, 0, 1]);
+        jpeg.extend_from_slice(&[0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0]);
+        jpeg.extend_from_slice(&[0, 0, 0, 0, 0xFF, 0xD9]);
+
+        assert_eq!(exif_orientation(&jpeg), Some(6));
+        assert_eq!(exif_orientation(b"\x89PNG\r\n"), None);
+    }
+
+    #[test]
+    fn test_deskew_detects_angle() {
+        let image = skewed_lines(600, 400, 3.0);
+        let angle = estimate_skew(&image);
+        assert!((angle - 3.0).abs() <= 0.2, "ângulo estimado: {}", angle);
+
+        let straightened = rotate(&image, angle);
+        assert!(estimate_skew(&straightened).abs() < MIN_SKEW_DEGREES);
+    }
+
+    #[test]
+    fn test_border_crop_and_threshold() {
+        // Papel claro com sombra em gradiente sobre uma mesa escura
+        let image = GrayImage::from_fn(200, 100, |x, y| {
+            if !(20..180).contains(&x) || !(10..90).contains(&y) {
+                Luma([15])
+            } else if (40..60).contains(&x) && (40..45).contains(&y) {
+                Luma([60])
+            } else {
+                Luma([(230 - x / 2) as u8])
+            }
+        });
+
+        assert_eq!(content_bounds(&image), Some((20, 10, 160, 80)));
+
+        let binary = adaptive_threshold(&image);
+        assert_eq!(binary.get_pixel(50, 42)[0], 0);
+        assert_eq!(binary.get_pixel(170, 70)[0], 255);
+    }
+
+    #[test]
+    fn test_steps_follow_options() {
+        let image = DynamicImage::ImageLuma8(skewed_lines(400, 300, 4.0));
+
+        let all = preprocess(image.clone(), Some(3), &PreprocessOptions::default());
+        assert_eq!(all.steps, ["exif_rotation", "grayscale", "upscale", "denoise", "deskew", "binarize"]);
+        assert_eq!(all.image.width(), 1000);
+
+        let none = preprocess(image, Some(6), &PreprocessOptions::none());
+        assert!(none.steps.is_empty());
+        assert_eq!((none.image.width(), none.image.height()), (400, 300));
+    }
+
+    #[test]
+    fn test_geometry_maps_boxes_back() {
+        let geometry = Geometry {
+            original_width: 500,
+            original_height: 400,
+            crop_left: 50,
+            crop_top: 20,
+            scale: 2.0,
+            rotation_degrees: 0.0,
+            rotated_width: 800,
+            rotated_height: 600,
+        };
+        assert_eq!(geometry.to_original(100, 60, 40, 20), (100, 50, 20, 10));
+
+        // Giro de 90° em torno do centro de uma imagem quadrada
+        let rotated = Geometry { rotation_degrees: 90.0, rotated_width: 100, rotated_height: 100, ..Geometry::identity(100, 100) };
+        assert_eq!(rotated.to_original(10, 20, 30, 10), (70, 10, 10, 30));
+    }
+}
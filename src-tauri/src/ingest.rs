@@ -0,0 +1,643 @@
+// PIPELINE DE INGESTÃO DE DOCUMENTOS
+// OCR → criação do documento → indexação para busca. Usado pelos comandos do
+// frontend e por processos em segundo plano (pastas monitoradas, importação em lote).
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::database_sqlite::{self, User};
+use crate::date_extractor::{DateDetectionResult, DateExtractor, DateSource, generate_folder_slug};
+use crate::doc_classifier::{self, NaiveBayes, TypeDecision};
+use crate::image_preprocess::PreprocessOptions;
+use crate::ocr_simple::{self, SimpleOCRResult, WordBox, create_simple_ocr_processor};
+use crate::{email_parser, extraction_rules, file_store, log_audit_event, money_parser, near_duplicate, AppState, CreateDocumentResponse, NearDuplicateInfo};
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+// Extrair texto e campos de um arquivo com o OCR simplificado
+pub async fn run_ocr(state: &AppState, user: &User, file_path: &str) -> Result<SimpleOCRResult, String> {
+    run_ocr_with(state, user, file_path, PreprocessOptions::default()).await
+}
+
+// Idem, escolhendo as etapas de pré-processamento das imagens
+pub async fn run_ocr_with(
+    state: &AppState,
+    user: &User,
+    file_path: &str,
+    preprocessing: PreprocessOptions,
+) -> Result<SimpleOCRResult, String> {
+    log::info!("🔍 Iniciando OCR simplificado para: {}", file_path);
+    
+    let processor = create_simple_ocr_processor()
+        .map_err(|e| format!("Erro ao criar OCR processor: {:?}", e))?
+        .with_preprocessing(preprocessing);
+    
+    let path = Path::new(file_path);
+    let mut result = processor.process_file(path).await
+        .map_err(|e| match e {
+            ocr_simple::SimpleOCRError::ProcessingError(msg) => msg,
+            other => format!("Erro ao processar documento: {:?}", other),
+        })?;
+    
+    // Tipo classificado pelo texto: o classificador do usuário pode corrigir o das regras
+    if let Some(by_rules) = result.classification.take() {
+        let decision = classify_for_user(state, user, &result.extracted_text, by_rules).await;
+        if decision.document_type != result.document_type {
+            let rules = extraction_rules::current();
+            for (key, value) in rules.extract_fields(&decision.document_type, &result.extracted_text) {
+                result.extracted_fields.entry(key).or_insert(value);
+            }
+            result.document_type = decision.document_type.clone();
+        }
+        result.classification = Some(decision);
+    }
+    
+    // Log da operação
+    let file_name = path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("documento_desconhecido");
+    
+    let _ = log_audit_event(
+        state,
+        &user.id,
+        &user.username,
+        "OCR_SIMPLE",
+        "DOCUMENT",
+        Some(file_name.to_string()),
+        Some(file_name.to_string()),
+        None,
+        Some(serde_json::json!({
+            "file_path": file_path,
+            "document_type": result.document_type,
+            "confidence_score": result.confidence_score,
+            "processing_time_ms": result.processing_time_ms,
+            "method": result.processing_method,
+            "preprocessing": result.preprocessing
+        })),
+        result.error_message.is_none(),
+    ).await;
+    
+    Ok(result)
+}
+
+// Modelo do usuário: sementes das regras em vigor + documentos com tipo corrigido.
+// Fica em cache até o usuário corrigir outro tipo ou o arquivo de regras mudar.
+async fn user_classifier(state: &AppState, user: &User) -> Result<Arc<NaiveBayes>, String> {
+    let rules = extraction_rules::current();
+    let mut classifiers = state.classifiers.lock().await;
+    
+    if let Some((seeded_from, model)) = classifiers.get(&user.id) {
+        if Arc::ptr_eq(seeded_from, &rules) {
+            return Ok(model.clone());
+        }
+    }
+    
+    let corrected = state.db.get_corrected_documents(&user.id)
+        .map_err(|e| format!("Erro ao buscar documentos corrigidos: {:?}", e))?;
+    
+    let mut model = NaiveBayes::seeded(rules.rule_set());
+    for (document_type, text) in &corrected {
+        model.train(document_type, text);
+    }
+    log::info!("🧮 Classificador treinado com {} documentos corrigidos", model.corrections());
+    
+    let model = Arc::new(model);
+    classifiers.insert(user.id.clone(), (rules, model.clone()));
+    Ok(model)
+}
+
+// Descartar o modelo em cache (o próximo uso retreina com as correções atuais)
+pub async fn forget_classifier(state: &AppState, user: &User) {
+    state.classifiers.lock().await.remove(&user.id);
+}
+
+// Tipo pelo classificador do usuário quando ele está confiante; senão o das regras
+pub async fn classify_for_user(state: &AppState, user: &User, text: &str, by_rules: TypeDecision) -> TypeDecision {
+    match user_classifier(state, user).await {
+        Ok(model) => doc_classifier::decide(&model, text, by_rules),
+        Err(e) => {
+            log::warn!("⚠️ Classificador indisponível, usando regras: {}", e);
+            by_rules
+        }
+    }
+}
+
+// Quantas datas candidatas são devolvidas como alternativas para a interface
+pub const DATE_ALTERNATIVES: usize = 5;
+
+// Criar o documento a partir de um arquivo já processado pelo OCR.
+// on_duplicate: "reject" (padrão) recusa arquivo idêntico já existente,
+// "link" devolve o documento existente e "allow" cria mesmo assim.
+// known_date: data estruturada do arquivo (ver SimpleOCRResult::document_date),
+// usada no lugar da extração heurística. file_hash: SHA-256 já calculado por quem chama
+#[allow(clippy::too_many_arguments)]
+pub async fn create_document_record(
+    state: &AppState,
+    user: &User,
+    file_path: &str,
+    extracted_text: &str,
+    document_type: &str,
+    known_date: Option<NaiveDate>,
+    on_duplicate: Option<&str>,
+    file_hash: Option<&str>,
+) -> Result<CreateDocumentResponse, String> {
+    log::info!("📄 Criando documento: {}", file_path);
+    
+    let path = Path::new(file_path);
+    let filename = path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    
+    // 0. VERIFICAR DUPLICATA EXATA (MESMO SHA-256)
+    let file_hash = match file_hash {
+        Some(hash) => hash.to_string(),
+        None => file_store::hash_file(path)
+            .map_err(|e| format!("Erro ao calcular hash do arquivo: {:?}", e))?,
+    };
+    
+    let existing = state.db.find_active_document_by_hash(&user.id, &file_hash)
+        .map_err(|e| format!("Erro ao verificar duplicatas: {:?}", e))?;
+    
+    if let Some(existing) = existing {
+        let policy = on_duplicate.unwrap_or("reject");
+        match policy {
+            "allow" => {
+                log::info!("♊ Duplicata de {} criada por solicitação do usuário", existing.id);
+            }
+            "link" | "reject" => {
+                let linked = policy == "link";
+                let _ = log_audit_event(
+                    state,
+                    &user.id,
+                    &user.username,
+                    if linked { "DUPLICATE_LINKED" } else { "DUPLICATE_REJECTED" },
+                    "DOCUMENT",
+                    Some(existing.id.clone()),
+                    Some(existing.name.clone()),
+                    Some(file_hash.clone()),
+                    Some(serde_json::json!({
+                        "original_path": file_path,
+                        "existing_name": existing.name,
+                    })),
+                    linked,
+                ).await;
+                
+                if !linked {
+                    return Err(format!("Documento duplicado: este arquivo já existe como '{}'", existing.name));
+                }
+                
+                log::info!("🔗 Arquivo {} vinculado ao documento existente {}", file_path, existing.id);
+                return Ok(CreateDocumentResponse {
+                    id: existing.id.clone(),
+                    name: existing.name,
+                    document_date: existing.document_date,
+                    folder_slug: existing.folder_slug,
+                    date_confidence: 1.0,
+                    date_source: "Duplicate".to_string(),
+                    duplicate_of: Some(existing.id),
+                    near_duplicates: vec![],
+                    attachment_ids: vec![],
+                    date_candidates: vec![],
+                });
+            }
+            other => return Err(format!("Opção de duplicata inválida: '{}'. Use reject, link ou allow.", other)),
+        }
+    }
+    
+    // 1. EXTRAÇÃO AUTOMÁTICA DE DATA (a melhor entre todas as candidatas, no idioma do texto)
+    let date_extractor = DateExtractor::for_text(extracted_text);
+    let (date_result, date_candidates) = match known_date {
+        Some(value) => (
+            DateDetectionResult {
+                value,
+                source: DateSource::Metadata,
+                confidence: 1.0,
+            },
+            Vec::new(),
+        ),
+        None => date_extractor.extract_date_ranked(filename, extracted_text, Some(document_type)),
+    };
+    
+    log::info!(
+        "📅 Data extraída: {} (fonte: {:?}, confidence: {:.2}, idioma: {})",
+        date_result.value.format("%Y-%m-%d"),
+        date_result.source,
+        date_result.confidence,
+        date_extractor.locale().as_str()
+    );
+    
+    // 2. GERAR FOLDER SLUG
+    let folder_slug = generate_folder_slug(&date_result.value);
+    let document_date = date_result.value.format("%Y-%m-%d").to_string();
+    
+    // 3. COPIAR ARQUIVO PARA O ARMAZENAMENTO GERENCIADO
+    // O documento passa a apontar para a cópia; o caminho original fica como proveniência
+    let stored_file = state.file_store.store_file_with_hash(&user.id, path, file_hash)
+        .map_err(|e| format!("Erro ao copiar arquivo para o armazenamento: {:?}", e))?;
+    
+    let stored_path = stored_file.stored_path.to_string_lossy().to_string();
+    let file_size = stored_file.file_size;
+    let file_type = path.extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    
+    // 4. CRIAR DOCUMENTO NO BANCO
+    let doc_id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+    
+    let document = database_sqlite::Document {
+        id: doc_id.clone(),
+        user_id: user.id.clone(),
+        name: filename.to_string(),
+        file_path: stored_path.clone(),
+        file_type: file_type.clone(),
+        file_size,
+        created_at: now,
+        updated_at: now,
+        tags: vec![],
+        document_date: Some(document_date.clone()),
+        folder_slug: Some(folder_slug.clone()),
+        original_path: Some(file_path.to_string()),
+        file_hash: Some(stored_file.file_hash.clone()),
+        deleted_at: None,
+        parent_document_id: None,
+    };
+    
+    state.db.create_document(&document)
+        .map_err(|e| format!("Erro ao criar documento no banco: {:?}", e))?;
+    
+    // Versão 1 do histórico
+    state.db.create_document_version(&database_sqlite::DocumentVersion {
+        id: Uuid::new_v4().to_string(),
+        document_id: doc_id.clone(),
+        version_number: 1,
+        file_path: stored_path.clone(),
+        file_hash: Some(stored_file.file_hash.clone()),
+        file_size,
+        file_type: file_type.clone(),
+        extracted_text: extracted_text.to_string(),
+        ocr_result: None,
+        created_at: now,
+    }).map_err(|e| format!("Erro ao registrar versão do documento: {:?}", e))?;
+    
+    // 5. SINALIZAR QUASE-DUPLICATAS (TEXTO PRATICAMENTE IGUAL)
+    let near_duplicates = find_near_duplicates(state, &user.id, &doc_id, extracted_text)?;
+    if !near_duplicates.is_empty() {
+        log::info!("♊ {} possíveis duplicatas para {}", near_duplicates.len(), doc_id);
+    }
+    
+    // 6. LOG NA TRILHA DE AUDITORIA
+    let _ = log_audit_event(
+        state,
+        &user.id,
+        &user.username,
+        "DOCUMENT_CREATE",
+        "DOCUMENT",
+        Some(doc_id.clone()),
+        Some(filename.to_string()),
+        Some(stored_file.file_hash.clone()),
+        Some(serde_json::json!({
+            "file_path": stored_path,
+            "original_path": file_path,
+            "document_type": document_type,
+            "document_date": document_date,
+            "folder_slug": folder_slug,
+            "date_source": format!("{:?}", date_result.source),
+            "date_confidence": date_result.confidence,
+            "date_locale": date_extractor.locale().as_str(),
+            "file_size": file_size,
+            "near_duplicates": near_duplicates.iter().map(|d| &d.document_id).collect::<Vec<_>>(),
+        })),
+        true,
+    ).await;
+    
+    log::info!("✅ Documento criado: {} (pasta: {})", doc_id, folder_slug);
+    
+    Ok(CreateDocumentResponse {
+        id: doc_id,
+        name: filename.to_string(),
+        document_date: Some(document_date),
+        folder_slug: Some(folder_slug),
+        date_confidence: date_result.confidence,
+        date_source: format!("{:?}", date_result.source),
+        duplicate_of: None,
+        near_duplicates,
+        attachment_ids: vec![],
+        date_candidates: date_candidates.into_iter().take(DATE_ALTERNATIVES).collect(),
+    })
+}
+
+// Indexar o texto extraído para a busca full-text
+pub async fn index_document(
+    state: &AppState,
+    user: &User,
+    document_id: &str,
+    extracted_text: &str,
+    document_type: &str,
+    extracted_fields: &serde_json::Value,
+    word_boxes: Option<&[WordBox]>,
+) -> Result<(), String> {
+    state.db.index_document_content(
+        document_id,
+        extracted_text,
+        document_type,
+        extracted_fields,
+    ).map_err(|e| format!("Erro ao indexar documento: {:?}", e))?;
+    
+    // None mantém as posições já gravadas (reindexação só do texto)
+    if let Some(word_boxes) = word_boxes {
+        state.db.save_document_words(document_id, word_boxes)
+            .map_err(|e| format!("Erro ao salvar posições das palavras: {:?}", e))?;
+    }
+    
+    // Tipo e campos do OCR ficam com a versão atual (restaurá-la depois reindexa com eles)
+    let mut ocr_snapshot = serde_json::json!({
+        "document_type": document_type,
+        "extracted_fields": extracted_fields,
+    });
+    if let Some(word_boxes) = word_boxes {
+        ocr_snapshot["word_boxes"] = serde_json::to_value(word_boxes).unwrap_or_else(|_| serde_json::json!([]));
+    }
+    state.db.fill_latest_version_ocr_result(document_id, &ocr_snapshot)
+        .map_err(|e| format!("Erro ao registrar OCR da versão: {:?}", e))?;
+    
+    let amounts = money_parser::find_amounts(extracted_text);
+    state.db.save_document_amounts(document_id, &amounts)
+        .map_err(|e| format!("Erro ao salvar valores monetários: {:?}", e))?;
+    
+    let dates = DateExtractor::for_text(extracted_text).extract_typed_dates(extracted_text);
+    state.db.save_document_dates(document_id, &dates)
+        .map_err(|e| format!("Erro ao salvar datas do documento: {:?}", e))?;
+    
+    let signature = near_duplicate::signature(&near_duplicate::shingles(extracted_text));
+    state.db.save_document_signature(document_id, &signature)
+        .map_err(|e| format!("Erro ao salvar assinatura do documento: {:?}", e))?;
+    
+    // Log da indexação
+    let _ = log_audit_event(
+        state,
+        &user.id,
+        &user.username,
+        "INDEX",
+        "DOCUMENT",
+        Some(document_id.to_string()),
+        None,
+        None,
+        Some(serde_json::json!({
+            "document_type": document_type,
+            "text_length": extracted_text.len(),
+            "fields_count": extracted_fields.as_object().map(|o| o.len()).unwrap_or(0),
+            "word_boxes": word_boxes.map(|words| words.len()),
+            "amounts": amounts.len(),
+            "dates": dates.iter().map(|d| d.kind.as_str()).collect::<Vec<_>>()
+        })),
+        true,
+    ).await;
+    
+    log::info!("📝 Documento {} indexado com sucesso", document_id);
+    Ok(())
+}
+
+// Etapas do pipeline de importação, informadas a quem acompanha o progresso
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IngestStage {
+    Ocr,
+    Create,
+    Index,
+    Attachments,
+}
+
+impl IngestStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IngestStage::Ocr => "ocr",
+            IngestStage::Create => "create",
+            IngestStage::Index => "index",
+            IngestStage::Attachments => "attachments",
+        }
+    }
+    
+    // Fração do trabalho concluída ao entrar na etapa
+    pub fn progress(&self) -> f64 {
+        match self {
+            IngestStage::Ocr => 0.1,
+            IngestStage::Create => 0.6,
+            IngestStage::Index => 0.8,
+            IngestStage::Attachments => 0.9,
+        }
+    }
+    
+    // Etapas que começam antes de o documento existir (interromper não deixa efeitos)
+    pub fn before_document(&self) -> bool {
+        matches!(self, IngestStage::Ocr | IngestStage::Create)
+    }
+}
+
+// Pipeline completo para um arquivo: OCR → documento → índice de busca
+pub async fn ingest_file(
+    state: &AppState,
+    user: &User,
+    file_path: &str,
+    on_duplicate: Option<&str>,
+    file_hash: Option<&str>,
+) -> Result<CreateDocumentResponse, String> {
+    ingest_file_with_progress(state, user, file_path, on_duplicate, file_hash, |_| Ok::<(), String>(())).await
+}
+
+// Idem, chamando `on_stage` ao entrar em cada etapa. Um erro devolvido por ele interrompe
+// o pipeline; depois que o documento é criado, convém só acompanhar (ver `before_document`)
+pub async fn ingest_file_with_progress<E, F>(
+    state: &AppState,
+    user: &User,
+    file_path: &str,
+    on_duplicate: Option<&str>,
+    file_hash: Option<&str>,
+    mut on_stage: F,
+) -> Result<CreateDocumentResponse, E>
+where
+    E: From<String>,
+    F: FnMut(IngestStage) -> Result<(), E> + Send,
+{
+    on_stage(IngestStage::Ocr)?;
+    let ocr_result = run_ocr(state, user, file_path).await?;
+    
+    on_stage(IngestStage::Create)?;
+    let mut document = create_document_record(
+        state,
+        user,
+        file_path,
+        &ocr_result.extracted_text,
+        &ocr_result.document_type,
+        ocr_result.document_date(),
+        on_duplicate,
+        file_hash,
+    ).await?;
+    
+    // Duplicata vinculada já está indexada
+    if document.duplicate_of.is_none() {
+        on_stage(IngestStage::Index)?;
+        let extracted_fields = serde_json::to_value(&ocr_result.extracted_fields)
+            .unwrap_or_else(|_| serde_json::json!({}));
+        index_document(state, user, &document.id, &ocr_result.extracted_text, &ocr_result.document_type, &extracted_fields, Some(&ocr_result.word_boxes)).await?;
+        
+        if email_parser::is_email_file(Path::new(file_path)) {
+            on_stage(IngestStage::Attachments)?;
+            document.attachment_ids = ingest_email_attachments(state, user, &document.id, file_path).await?;
+        }
+    }
+    
+    Ok(document)
+}
+
+// Cada anexo do e-mail vira um documento filho. Um anexo com problema não impede os demais;
+// anexo idêntico a um documento existente é apenas vinculado (não recebe o e-mail como pai).
+pub async fn ingest_email_attachments(
+    state: &AppState,
+    user: &User,
+    email_document_id: &str,
+    email_path: &str,
+) -> Result<Vec<String>, String> {
+    let raw = std::fs::read(email_path)
+        .map_err(|e| format!("Erro ao ler e-mail: {:?}", e))?;
+    let email = email_parser::parse_email(&raw);
+    if email.attachments.is_empty() {
+        return Ok(vec![]);
+    }
+    
+    let temp_dir = tempfile::tempdir()
+        .map_err(|e| format!("Erro ao criar pasta temporária: {:?}", e))?;
+    let mut attachment_ids = Vec::new();
+    
+    for (index, attachment) in email.attachments.iter().enumerate() {
+        // Uma subpasta por anexo evita colisão entre anexos de mesmo nome
+        let attachment_dir = temp_dir.path().join((index + 1).to_string());
+        let attachment_path = attachment_dir.join(email_parser::safe_file_name(&attachment.filename));
+        if let Err(e) = std::fs::create_dir(&attachment_dir).and_then(|_| std::fs::write(&attachment_path, &attachment.data)) {
+            log::warn!("⚠️ Erro ao gravar anexo {}: {:?}", attachment.filename, e);
+            continue;
+        }
+        let attachment_path = attachment_path.to_string_lossy().to_string();
+        
+        // Tipos sem extrator são guardados mesmo assim, sem texto
+        let supported = ocr_simple::is_supported_file(Path::new(&attachment_path))
+            && !ocr_simple::is_non_nfe_xml(Path::new(&attachment_path));
+        let ocr_result = if supported {
+            match run_ocr(state, user, &attachment_path).await {
+                Ok(result) => Some(result),
+                Err(e) => {
+                    log::warn!("⚠️ Anexo {} sem texto extraído: {}", attachment.filename, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let (extracted_text, document_type) = ocr_result.as_ref()
+            .map(|r| (r.extracted_text.clone(), r.document_type.clone()))
+            .unwrap_or_else(|| (String::new(), "anexo".to_string()));
+        
+        let document = match create_document_record(
+            state,
+            user,
+            &attachment_path,
+            &extracted_text,
+            &document_type,
+            ocr_result.as_ref().and_then(|r| r.document_date()),
+            Some("link"),
+            None,
+        ).await {
+            Ok(document) => document,
+            Err(e) => {
+                log::warn!("⚠️ Erro ao importar anexo {}: {}", attachment.filename, e);
+                continue;
+            }
+        };
+        
+        if document.duplicate_of.is_none() {
+            let original_path = format!("{}#{}", email_path, attachment.filename);
+            state.db.set_document_origin(&user.id, &document.id, Some(email_document_id), &original_path)
+                .map_err(|e| format!("Erro ao vincular anexo ao e-mail: {:?}", e))?;
+            
+            let extracted_fields = ocr_result.as_ref()
+                .and_then(|r| serde_json::to_value(&r.extracted_fields).ok())
+                .unwrap_or_else(|| serde_json::json!({}));
+            let word_boxes = ocr_result.as_ref().map(|r| r.word_boxes.as_slice());
+            index_document(state, user, &document.id, &extracted_text, &document_type, &extracted_fields, word_boxes).await?;
+        }
+        
+        attachment_ids.push(document.id);
+    }
+    
+    let _ = log_audit_event(
+        state,
+        &user.id,
+        &user.username,
+        "EMAIL_ATTACHMENTS",
+        "DOCUMENT",
+        Some(email_document_id.to_string()),
+        email.subject.clone(),
+        None,
+        Some(serde_json::json!({
+            "attachments": email.attachment_infos(),
+            "document_ids": attachment_ids,
+        })),
+        attachment_ids.len() == email.attachments.len(),
+    ).await;
+    
+    log::info!("📎 {} de {} anexos importados do e-mail {}", attachment_ids.len(), email.attachments.len(), email_document_id);
+    Ok(attachment_ids)
+}
+
+// Documentos ativos cujo texto extraído é quase igual ao informado: candidatos pelas
+// assinaturas MinHash gravadas na indexação, confirmados pelo Jaccard exato
+pub fn find_near_duplicates(
+    state: &AppState,
+    user_id: &str,
+    document_id: &str,
+    extracted_text: &str,
+) -> Result<Vec<NearDuplicateInfo>, String> {
+    let shingles = near_duplicate::shingles(extracted_text);
+    let candidate = near_duplicate::signature(&shingles);
+    if candidate.is_empty() {
+        return Ok(vec![]);
+    }
+    
+    let signatures = state.db.get_document_signatures(user_id)
+        .map_err(|e| format!("Erro ao buscar assinaturas para comparação: {:?}", e))?;
+    
+    let mut matches: Vec<(String, f64)> = signatures.into_iter()
+        .filter(|(id, _)| id != document_id)
+        .filter(|(_, signature)| {
+            near_duplicate::estimated_similarity(&candidate, signature) >= near_duplicate::NEAR_DUPLICATE_THRESHOLD
+        })
+        .map(|(id, _)| {
+            let text = state.db.get_document_content(user_id, &id)
+                .map_err(|e| format!("Erro ao buscar texto para comparação: {:?}", e))?
+                .map(|content| content.extracted_text)
+                .unwrap_or_default();
+            Ok((id, near_duplicate::jaccard(&shingles, &near_duplicate::shingles(&text))))
+        })
+        .filter(|result: &Result<(String, f64), String>| {
+            result.as_ref().map_or(true, |(_, similarity)| *similarity >= near_duplicate::NEAR_DUPLICATE_THRESHOLD)
+        })
+        .collect::<Result<_, String>>()?;
+    
+    if matches.is_empty() {
+        return Ok(vec![]);
+    }
+    matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+    
+    let documents = state.db.get_documents_by_user(user_id)
+        .map_err(|e| format!("Erro ao buscar documentos: {:?}", e))?;
+    
+    Ok(matches.into_iter()
+        .filter_map(|(id, similarity)| {
+            documents.iter().find(|d| d.id == id).map(|d| NearDuplicateInfo {
+                document_id: id,
+                name: d.name.clone(),
+                similarity,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,318 @@
+// FILA DE PROCESSAMENTO EM SEGUNDO PLANO
+// Jobs ficam persistidos na tabela `jobs` e são executados por workers assíncronos,
+// sem segurar o lock do usuário autenticado. O progresso de cada job é emitido no
+// evento "job-progress". Jobs em execução quando o app fecha voltam para a fila.
+
+use std::any::Any;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::database_sqlite::Job;
+use crate::ingest::{self, IngestStage};
+use crate::{log_audit_event, AppState};
+
+pub const JOB_KIND_INGEST: &str = "ingest";
+
+const WORKER_COUNT: usize = 2;
+const IDLE_POLL_SECS: u64 = 10;
+const DEFAULT_LIST_LIMIT: usize = 100;
+
+// Sinaliza aos workers que há trabalho novo na fila
+pub struct JobQueue {
+    notify: Notify,
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        JobQueue { notify: Notify::new() }
+    }
+
+    pub fn wake(&self) {
+        // Acordar todos que estão esperando e deixar uma permissão para quem ainda vai esperar
+        self.notify.notify_waiters();
+        self.notify.notify_one();
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestJobPayload {
+    pub file_path: String,
+    pub on_duplicate: Option<String>,
+}
+
+// Resultado de um job: Ok(result) conclui, Err(None) cancela, Err(Some(msg)) falha
+type JobOutcome = Result<serde_json::Value, Option<String>>;
+
+// ================================
+// WORKERS
+// ================================
+
+pub fn start_workers(state: AppState, app: AppHandle) {
+    match state.db.requeue_interrupted_jobs() {
+        Ok(0) => {}
+        Ok(count) => log::info!("🔁 {} jobs interrompidos voltaram para a fila", count),
+        Err(e) => log::warn!("⚠️ Erro ao recolocar jobs interrompidos na fila: {:?}", e),
+    }
+
+    for worker_id in 0..WORKER_COUNT {
+        tauri::async_runtime::spawn(run_worker(worker_id, state.clone(), app.clone()));
+    }
+    log::info!("⚙️ {} workers da fila de processamento iniciados", WORKER_COUNT);
+}
+
+async fn run_worker(worker_id: usize, state: AppState, app: AppHandle) {
+    loop {
+        let job = match state.db.claim_next_job() {
+            Ok(Some(job)) => job,
+            Ok(None) => {
+                // Esperar por novos jobs (ou reverificar periodicamente)
+                let _ = tokio::time::timeout(
+                    Duration::from_secs(IDLE_POLL_SECS),
+                    state.job_queue.notify.notified(),
+                ).await;
+                continue;
+            }
+            Err(e) => {
+                log::warn!("⚠️ Worker {}: erro ao buscar job: {:?}", worker_id, e);
+                tokio::time::sleep(Duration::from_secs(IDLE_POLL_SECS)).await;
+                continue;
+            }
+        };
+
+        log::info!("⚙️ Worker {} executando job {} ({})", worker_id, job.id, job.kind);
+        emit_job(&state, &app, &job.id);
+
+        // Cada job numa task própria: um pânico no processamento falha só este job e o
+        // worker continua atendendo a fila
+        let task = tokio::spawn(run_job(state.clone(), app.clone(), job.clone()));
+        let outcome = match task.await {
+            Ok(outcome) => outcome,
+            Err(e) if e.is_panic() => Err(Some(format!("Erro interno ao processar o job: {}", panic_message(e.into_panic())))),
+            Err(e) => Err(Some(format!("Job interrompido: {:?}", e))),
+        };
+
+        let finished = match &outcome {
+            Ok(result) => state.db.finish_job(&job.id, "completed", Some(result), None),
+            Err(None) => state.db.finish_job(&job.id, "cancelled", None, None),
+            Err(Some(error)) => state.db.finish_job(&job.id, "failed", None, Some(error)),
+        };
+        if let Err(e) = finished {
+            log::error!("❌ Erro ao finalizar job {}: {:?}", job.id, e);
+        }
+
+        match &outcome {
+            Ok(_) => log::info!("✅ Job {} concluído", job.id),
+            Err(None) => log::info!("⏹️ Job {} cancelado", job.id),
+            Err(Some(error)) => log::warn!("⚠️ Job {} falhou: {}", job.id, error),
+        }
+        emit_job(&state, &app, &job.id);
+    }
+}
+
+async fn run_job(state: AppState, app: AppHandle, job: Job) -> JobOutcome {
+    match job.kind.as_str() {
+        JOB_KIND_INGEST => run_ingest_job(&state, &app, &job).await,
+        other => Err(Some(format!("Tipo de job desconhecido: {}", other))),
+    }
+}
+
+// Mensagem do pânico (texto passado a panic!/expect), quando houver
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "pânico sem mensagem".to_string())
+}
+
+// Pipeline de importação (ingest::ingest_file_with_progress): OCR → documento → índice.
+// O cancelamento é verificado antes de cada etapa que ainda não gerou efeitos;
+// depois que o documento é criado, o job vai até o fim.
+async fn run_ingest_job(state: &AppState, app: &AppHandle, job: &Job) -> JobOutcome {
+    let payload: IngestJobPayload = serde_json::from_value(job.payload.clone())
+        .map_err(|e| Some(format!("Payload inválido: {}", e)))?;
+
+    let user = state.db.get_user_by_id(&job.user_id)
+        .map_err(|e| Some(format!("Erro ao buscar usuário: {:?}", e)))?
+        .ok_or_else(|| Some("Usuário do job não encontrado".to_string()))?;
+
+    let document = ingest::ingest_file_with_progress(
+        state,
+        &user,
+        &payload.file_path,
+        payload.on_duplicate.as_deref(),
+        None,
+        |stage: IngestStage| -> Result<(), Option<String>> {
+            if stage.before_document() {
+                check_cancelled(state, &job.id)?;
+            }
+            set_progress(state, app, &job.id, stage.progress(), stage.as_str());
+            Ok(())
+        },
+    ).await?;
+
+    serde_json::to_value(&document).map_err(|e| Some(e.to_string()))
+}
+
+fn check_cancelled(state: &AppState, job_id: &str) -> Result<(), Option<String>> {
+    match state.db.is_job_cancel_requested(job_id) {
+        Ok(true) => Err(None),
+        Ok(false) => Ok(()),
+        Err(e) => Err(Some(format!("Erro ao verificar cancelamento: {:?}", e))),
+    }
+}
+
+fn set_progress(state: &AppState, app: &AppHandle, job_id: &str, progress: f64, stage: &str) {
+    if let Err(e) = state.db.update_job_progress(job_id, progress, stage) {
+        log::warn!("⚠️ Erro ao atualizar progresso do job {}: {:?}", job_id, e);
+    }
+    emit_job(state, app, job_id);
+}
+
+// Emitir o estado atual do job para o frontend
+fn emit_job(state: &AppState, app: &AppHandle, job_id: &str) {
+    if let Ok(Some(job)) = state.db.get_job(job_id) {
+        if let Err(e) = app.emit("job-progress", job) {
+            log::warn!("⚠️ Erro ao emitir progresso do job: {:?}", e);
+        }
+    }
+}
+
+// Colocar um arquivo na fila de importação
+pub fn enqueue_ingest(state: &AppState, user_id: &str, file_path: &str, on_duplicate: Option<&str>) -> Result<Job, String> {
+    let now = Utc::now();
+    let payload = IngestJobPayload {
+        file_path: file_path.to_string(),
+        on_duplicate: on_duplicate.map(|s| s.to_string()),
+    };
+
+    let job = Job {
+        id: Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        kind: JOB_KIND_INGEST.to_string(),
+        payload: serde_json::to_value(&payload).map_err(|e| e.to_string())?,
+        status: "queued".to_string(),
+        progress: 0.0,
+        stage: None,
+        result: None,
+        error: None,
+        attempts: 0,
+        cancel_requested: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    state.db.create_job(&job)
+        .map_err(|e| format!("Erro ao criar job: {:?}", e))?;
+    state.job_queue.wake();
+    Ok(job)
+}
+
+// ================================
+// COMANDOS DA FILA
+// ================================
+
+#[tauri::command]
+pub async fn enqueue_ingest_jobs(
+    file_paths: Vec<String>,
+    on_duplicate: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Job>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let mut jobs = Vec::with_capacity(file_paths.len());
+        for file_path in &file_paths {
+            jobs.push(enqueue_ingest(&state, &user.id, file_path, on_duplicate.as_deref())?);
+        }
+
+        log::info!("📥 {} arquivos adicionados à fila de processamento", jobs.len());
+        Ok(jobs)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn list_jobs(
+    status: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Job>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        state.db.list_jobs(&user.id, status.as_deref(), limit.unwrap_or(DEFAULT_LIST_LIMIT))
+            .map_err(|e| format!("Erro ao buscar jobs: {:?}", e))
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn cancel_job(
+    job_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let cancelled = state.db.cancel_job(&user.id, &job_id)
+            .map_err(|e| format!("Erro ao cancelar job: {:?}", e))?;
+
+        if cancelled {
+            let _ = log_audit_event(
+                &state,
+                &user.id,
+                &user.username,
+                "JOB_CANCEL",
+                "SYSTEM",
+                Some(job_id.clone()),
+                None,
+                None,
+                None,
+                true,
+            ).await;
+            emit_job(&state, &app, &job_id);
+        }
+
+        Ok(cancelled)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn retry_job(
+    job_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let retried = state.db.retry_job(&user.id, &job_id)
+            .map_err(|e| format!("Erro ao reenviar job: {:?}", e))?;
+
+        if retried {
+            let _ = log_audit_event(
+                &state,
+                &user.id,
+                &user.username,
+                "JOB_RETRY",
+                "SYSTEM",
+                Some(job_id.clone()),
+                None,
+                None,
+                None,
+                true,
+            ).await;
+            state.job_queue.wake();
+            emit_job(&state, &app, &job_id);
+        }
+
+        Ok(retried)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
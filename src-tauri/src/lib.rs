@@ -7,24 +7,50 @@ use chrono::Utc;
 
 mod database_sqlite;
 mod backup;
-// mod ocr;  // Desabilitado - depende de tesseract
+#[cfg(feature = "ocr-native")]
+mod ocr;  // OCR nativo (libtesseract), só com a feature ocr-native
 mod ocr_simple;
 mod desktop;
 mod date_extractor;
 mod date_search_parser;
+mod date_locale;
+mod file_store;
+mod text_diff;
+mod near_duplicate;
+mod ingest;
+mod watch_folder;
+mod job_queue;
+mod bulk_import;
+mod text_extractors;
+mod nfe_parser;
+mod email_parser;
+mod tesseract_cli;
+mod pdf_raster;
+mod image_preprocess;
+mod br_validators;
+mod extraction_rules;
+mod doc_classifier;
+mod money_parser;
 
-use database_sqlite::{Database, User};
-use date_extractor::{DateExtractor, generate_folder_slug};
-use date_search_parser::DateSearchParser;
-// use ocr::{OCRProcessor, ExtractedMetadata, DocumentType};  // Desabilitado
-use ocr_simple::{SimpleOCRResult, create_simple_ocr_processor};
+use database_sqlite::{AmountFilter, AmountMatch, AmountTotal, Database, DateRangeFilter, TagFilter, User};
+use date_extractor::{generate_folder_slug, DateExtractor, DateKind, RankedDate, TypedDate};
+use date_search_parser::{DateSearchParser, DateSearchQuery};
+use file_store::FileStore;
+use ocr_simple::SimpleOCRResult;
 use std::path::PathBuf;
 
 // Estado da aplicação
+#[derive(Clone)]
 pub struct AppState {
     pub db: Arc<Database>,
     pub authenticated_user: Arc<Mutex<Option<User>>>,
-    // pub ocr_processor: Arc<Mutex<Option<OCRProcessor>>>,  // Desabilitado
+    pub file_store: Arc<FileStore>,
+    pub job_queue: Arc<job_queue::JobQueue>,
+    // Classificador de tipos por usuário, treinado com as correções (ver ingest::classify_for_user)
+    pub classifiers: Arc<Mutex<doc_classifier::ClassifierCache>>,
+    // Inicializado sob demanda no primeiro process_document_ocr
+    #[cfg(feature = "ocr-native")]
+    pub ocr_processor: Arc<Mutex<Option<ocr::OCRProcessor>>>,
 }
 
 impl AppState {
@@ -53,12 +79,23 @@ impl AppState {
         
         let authenticated_user = Arc::new(Mutex::new(None));
         
-        // OCR processor desabilitado - usa SimpleOCR apenas
+        // Armazenamento gerenciado de arquivos (ARKIVE/files/<user_id>/...)
+        let file_store = Arc::new(FileStore::new(data_dir.join("files"))?);
+        
+        // Regras de classificação e extração editáveis (recarregadas quando o arquivo muda)
+        extraction_rules::configure_rules_path(data_dir.join(extraction_rules::RULES_FILE_NAME));
+        
+        log::info!("🔍 Backend de OCR: {}", OCR_BACKEND);
         log::info!("✅ AppState inicializado com sucesso");
         
         Ok(AppState {
             db,
             authenticated_user,
+            file_store,
+            job_queue: Arc::new(job_queue::JobQueue::new()),
+            classifiers: Arc::new(Mutex::new(doc_classifier::ClassifierCache::new())),
+            #[cfg(feature = "ocr-native")]
+            ocr_processor: Arc::new(Mutex::new(None)),
         })
     }
 }
@@ -74,6 +111,8 @@ pub struct DocumentResponse {
     pub is_active: bool,
     pub category: String,
     pub preview_available: bool,
+    pub tags: Vec<String>,
+    pub parent_document_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -169,6 +208,11 @@ async fn login(
                     true,
                 ).await;
                 
+                // Expurgar itens da lixeira que passaram do período de retenção
+                if let Err(e) = purge_expired_trash(&state, &user).await {
+                    log::warn!("⚠️ Falha ao expurgar lixeira: {}", e);
+                }
+                
                 log::info!("✅ Login bem-sucedido: {}", username);
                 // Retornar User completo como JSON
                 let user_json = serde_json::json!({
@@ -332,13 +376,23 @@ async fn get_stats(
 
 #[tauri::command]
 async fn get_documents(
+    tags: Option<Vec<String>>,
+    match_all: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<Vec<DocumentResponse>, String> {
     let authenticated_user = state.authenticated_user.lock().await;
     if let Some(user) = authenticated_user.as_ref() {
-        let documents = state.db.get_documents_by_user(&user.id)
+        let mut documents = state.db.get_documents_by_user(&user.id)
             .map_err(|e| format!("Erro ao buscar documentos: {:?}", e))?;
         
+        // Filtro opcional por tags (qualquer uma, ou todas com match_all)
+        let tag_filter = normalize_tag_names(tags.unwrap_or_default());
+        if !tag_filter.is_empty() {
+            let allowed = state.db.get_document_ids_with_tags(&user.id, &tag_filter, match_all.unwrap_or(false))
+                .map_err(|e| format!("Erro ao filtrar por tags: {:?}", e))?;
+            documents.retain(|doc| allowed.contains(&doc.id));
+        }
+        
         let response: Vec<DocumentResponse> = documents.into_iter().map(|doc| {
             DocumentResponse {
                 id: doc.id,
@@ -349,6 +403,8 @@ async fn get_documents(
                 is_active: true,
                 category: "Documento".to_string(),
                 preview_available: false,
+                tags: doc.tags,
+                parent_document_id: doc.parent_document_id,
             }
         }).collect();
         
@@ -484,79 +540,45 @@ pub struct OCRResult {
 #[tauri::command]
 async fn process_document_simple_ocr(
     file_path: String,
+    preprocessing: Option<image_preprocess::PreprocessOptions>,
     state: State<'_, AppState>,
 ) -> Result<SimpleOCRResult, String> {
-    let authenticated_user = state.authenticated_user.lock().await;
+    // Copiar o usuário e liberar o lock: o OCR pode demorar e não deve travar outros comandos
+    let authenticated_user = state.authenticated_user.lock().await.clone();
     if let Some(user) = authenticated_user.as_ref() {
-        log::info!("🔍 Iniciando OCR simplificado para: {}", file_path);
-        
-        let processor = create_simple_ocr_processor()
-            .map_err(|e| format!("Erro ao criar OCR processor: {:?}", e))?;
-        
-        let path = std::path::Path::new(&file_path);
-        let extension = path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|s| s.to_lowercase());
-        
-        let result = match extension.as_deref() {
-            Some("pdf") => {
-                processor.process_pdf(&file_path).await
-                    .map_err(|e| format!("Erro ao processar PDF: {:?}", e))?
-            }
-            Some("xlsx") | Some("xls") | Some("xlsm") | Some("xlsb") | Some("ods") => {
-                processor.process_excel(&file_path)
-                    .map_err(|e| format!("Erro ao processar Excel: {:?}", e))?
-            }
-            Some("png") | Some("jpg") | Some("jpeg") | Some("tiff") | Some("bmp") => {
-                processor.process_image(&file_path).await
-                    .map_err(|e| format!("Erro ao processar imagem: {:?}", e))?
-            }
-            _ => {
-                return Err("Tipo de arquivo não suportado. Use PDF, Excel (.xlsx/.xls), PNG, JPG, JPEG, TIFF ou BMP.".to_string());
-            }
-        };
-        
-        // Log da operação
-        let file_name = path.file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("documento_desconhecido");
-        
-        let _ = log_audit_event(
-            &state,
-            &user.id,
-            &user.username,
-            "OCR_SIMPLE",
-            "DOCUMENT",
-            Some(file_name.to_string()),
-            Some(file_name.to_string()),
-            None,
-            Some(serde_json::json!({
-                "file_path": file_path,
-                "document_type": result.document_type,
-                "confidence_score": result.confidence_score,
-                "processing_time_ms": result.processing_time_ms,
-                "method": result.processing_method
-            })),
-            result.error_message.is_none(),
-        ).await;
-        
-        Ok(result)
+        ingest::run_ocr_with(&state, user, &file_path, preprocessing.unwrap_or_default()).await
     } else {
         Err("Usuário não autenticado".to_string())
     }
 }
 
-// Processar documento com OCR + IA - DESABILITADO (requer tesseract)
-/*
+// Backend de OCR ativo, definido em tempo de compilação pela feature ocr-native
+#[cfg(feature = "ocr-native")]
+const OCR_BACKEND: &str = "nativo (libtesseract)";
+#[cfg(not(feature = "ocr-native"))]
+const OCR_BACKEND: &str = "simplificado (Tesseract via linha de comando)";
+
+// Processar documento com OCR + IA (OCRProcessor nativo, feature ocr-native)
+#[cfg(feature = "ocr-native")]
 #[tauri::command]
 async fn process_document_ocr(
     file_path: String,
     state: State<'_, AppState>,
 ) -> Result<OCRResult, String> {
-    let authenticated_user = state.authenticated_user.lock().await;
+    let authenticated_user = state.authenticated_user.lock().await.clone();
     if let Some(user) = authenticated_user.as_ref() {
         let start_time = std::time::Instant::now();
         
+        // Determinar tipo do arquivo: o OCR nativo só trata PDF e imagens, os demais
+        // formatos (Office, XML, e-mail...) seguem pelo OCR simplificado
+        let path = std::path::Path::new(&file_path);
+        let extension = path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_lowercase());
+        if !matches!(extension.as_deref(), Some("pdf" | "png" | "jpg" | "jpeg" | "tiff" | "tif" | "bmp")) {
+            return process_document_ocr_simple(&state, user, &file_path).await;
+        }
+        
         // Inicializar OCR processor se necessário
         let mut ocr_guard = state.ocr_processor.lock().await;
         if ocr_guard.is_none() {
@@ -572,29 +594,15 @@ async fn process_document_ocr(
             }
         }
         
-        if let Some(ocr_processor) = ocr_guard.as_mut() {
-            // Determinar tipo do arquivo
-            let path = std::path::Path::new(&file_path);
-            let extension = path.extension()
-                .and_then(|ext| ext.to_str())
-                .map(|s| s.to_lowercase());
-            
-            let extracted_text = match extension.as_deref() {
-                Some("pdf") => {
-                    ocr_processor.extract_text_from_pdf(&file_path)
-                        .map_err(|e| format!("Erro ao processar PDF: {:?}", e))?
-                }
-                Some("png") | Some("jpg") | Some("jpeg") | Some("tiff") | Some("bmp") => {
-                    ocr_processor.extract_text_from_image(&file_path)
-                        .map_err(|e| format!("Erro ao processar imagem: {:?}", e))?
-                }
-                _ => {
-                    return Err("Tipo de arquivo não suportado. Use PDF, PNG, JPG, JPEG, TIFF ou BMP.".to_string());
-                }
+        if let Some(ocr_processor) = ocr_guard.as_ref() {
+            let metadata = if extension.as_deref() == Some("pdf") {
+                ocr_processor.extract_text_from_pdf(&file_path).await
+                    .map_err(|e| format!("Erro ao processar PDF: {:?}", e))?
+            } else {
+                let extracted_text = ocr_processor.extract_text_from_image(&file_path).await
+                    .map_err(|e| format!("Erro ao processar imagem: {:?}", e))?;
+                ocr_processor.analyze_document(&extracted_text)
             };
-            
-            // Analisar documento com IA
-            let metadata = ocr_processor.analyze_document(&extracted_text);
             let processing_time = start_time.elapsed().as_millis();
             
             // Log da operação na trilha de auditoria
@@ -612,9 +620,11 @@ async fn process_document_ocr(
                 Some(file_name.to_string()),
                 None,
                 Some(serde_json::json!({
-                    "document_type": format!("{:?}", metadata.document_type),
+                    "document_type": metadata.rule_type,
                     "confidence_score": metadata.confidence_score,
                     "processing_time_ms": processing_time,
+                    "processing_method": format!("{:?}", metadata.processing_method),
+                    "pages_processed": metadata.pages_processed,
                     "extracted_fields_count": metadata.extracted_fields.len()
                 })),
                 true,
@@ -622,7 +632,7 @@ async fn process_document_ocr(
             
             let result = OCRResult {
                 extracted_text: metadata.text_content,
-                document_type: format!("{:?}", metadata.document_type),
+                document_type: metadata.rule_type,
                 extracted_fields: metadata.extracted_fields,
                 confidence_score: metadata.confidence_score,
                 processing_time_ms: processing_time,
@@ -637,12 +647,127 @@ async fn process_document_ocr(
         Err("Usuário não autenticado".to_string())
     }
 }
-*/  // Fim do bloco comentado process_document_ocr
 
-// Obter tipos de documento suportados
+// Sem a feature ocr-native o mesmo comando usa o SimpleOCRProcessor
+#[cfg(not(feature = "ocr-native"))]
+#[tauri::command]
+async fn process_document_ocr(
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<OCRResult, String> {
+    let authenticated_user = state.authenticated_user.lock().await.clone();
+    if let Some(user) = authenticated_user.as_ref() {
+        process_document_ocr_simple(&state, user, &file_path).await
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// OCR pelo SimpleOCRProcessor, com o classificador do usuário
+async fn process_document_ocr_simple(state: &AppState, user: &User, file_path: &str) -> Result<OCRResult, String> {
+    let result = ingest::run_ocr(state, user, file_path).await?;
+    if let Some(error) = &result.error_message {
+        if result.extracted_text.is_empty() {
+            return Err(error.clone());
+        }
+    }
+    
+    Ok(OCRResult {
+        extracted_text: result.extracted_text,
+        document_type: result.document_type,
+        extracted_fields: result.extracted_fields,
+        confidence_score: result.confidence_score,
+        processing_time_ms: result.processing_time_ms,
+    })
+}
+
+// Obter tipos de documento suportados. Só a lista de formatos, que a tela de importação
+// exibe como está: o backend de OCR ativo é informado por `get_ocr_backend`
 #[tauri::command]
 async fn get_supported_document_types() -> Result<Vec<String>, String> {
-    Ok(ocr_simple::get_simple_supported_types())
+    // Os demais formatos (Office, XML, e-mail...) seguem pelo OCR simplificado nos dois casos
+    #[allow(unused_mut)]
+    let mut types: Vec<String> = Vec::new();
+    #[cfg(feature = "ocr-native")]
+    types.extend(ocr::get_supported_document_types());
+    for simple_type in ocr_simple::get_simple_supported_types() {
+        if !types.contains(&simple_type) {
+            types.push(simple_type);
+        }
+    }
+    Ok(types)
+}
+
+// Backend de OCR ativo, para diagnóstico na tela de importação
+#[tauri::command]
+async fn get_ocr_backend() -> Result<String, String> {
+    Ok(OCR_BACKEND.to_string())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractionRulesTestResponse {
+    pub document_id: String,
+    pub stored_document_type: String,
+    pub document_type: String,
+    pub scores: Vec<extraction_rules::TypeScore>,
+    pub extracted_fields: std::collections::HashMap<String, String>,
+    pub rules_path: Option<String>,
+}
+
+// Testar regras de extração contra o texto já extraído de um documento.
+// rules_json: conjunto de regras em edição; sem ele usa o arquivo em vigor.
+#[tauri::command]
+async fn test_extraction_rules(
+    document_id: String,
+    rules_json: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ExtractionRulesTestResponse, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    
+    if let Some(user) = authenticated_user.as_ref() {
+        let content = state.db.get_document_content(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar conteúdo do documento: {:?}", e))?
+            .ok_or_else(|| "Documento sem texto indexado".to_string())?;
+        
+        let rules = match rules_json {
+            Some(json) => std::sync::Arc::new(extraction_rules::parse_rules(&json)?),
+            None => extraction_rules::current(),
+        };
+        
+        let classification = rules.classify(&content.extracted_text);
+        let extracted_fields = rules.extract_fields(&classification.document_type, &content.extracted_text);
+        
+        Ok(ExtractionRulesTestResponse {
+            document_id,
+            stored_document_type: content.document_type,
+            document_type: classification.document_type,
+            scores: classification.scores,
+            extracted_fields,
+            rules_path: extraction_rules::rules_path().map(|path| path.to_string_lossy().to_string()),
+        })
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Probabilidade de cada tipo para um documento já indexado (classificador do usuário + regras)
+#[tauri::command]
+async fn classify_document(
+    document_id: String,
+    state: State<'_, AppState>,
+) -> Result<doc_classifier::TypeDecision, String> {
+    let authenticated_user = state.authenticated_user.lock().await.clone();
+    
+    if let Some(user) = authenticated_user.as_ref() {
+        let content = state.db.get_document_content(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar conteúdo do documento: {:?}", e))?
+            .ok_or_else(|| "Documento sem texto indexado".to_string())?;
+        
+        let by_rules = doc_classifier::TypeDecision::from_rules(&extraction_rules::current().classify(&content.extracted_text));
+        Ok(ingest::classify_for_user(&state, user, &content.extracted_text, by_rules).await)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
 }
 
 // ================================
@@ -665,491 +790,1841 @@ pub struct CreateDocumentResponse {
     pub folder_slug: Option<String>,
     pub date_confidence: f32,
     pub date_source: String,
+    pub duplicate_of: Option<String>,
+    pub near_duplicates: Vec<NearDuplicateInfo>,
+    pub attachment_ids: Vec<String>, // Documentos filhos criados a partir dos anexos (e-mail)
+    pub date_candidates: Vec<RankedDate>, // Datas alternativas, da mais para a menos provável
 }
 
+// on_duplicate: "reject" (padrão), "link" ou "allow" - ver ingest::create_document_record
+// document_date: data estruturada devolvida pelo OCR em extracted_fields.document_date (YYYY-MM-DD)
 #[tauri::command]
 async fn create_document(
     file_path: String,
     extracted_text: String,
     document_type: String,
+    document_date: Option<String>,
+    on_duplicate: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<CreateDocumentResponse, String> {
-    let authenticated_user = state.authenticated_user.lock().await;
+    // Copiar o usuário e liberar o lock: hash e cópia de arquivos grandes podem demorar
+    let authenticated_user = state.authenticated_user.lock().await.clone();
     if let Some(user) = authenticated_user.as_ref() {
-        log::info!("📄 Criando documento: {}", file_path);
-        
-        // 1. EXTRAÇÃO AUTOMÁTICA DE DATA
-        let date_extractor = DateExtractor::new();
-        let path = std::path::Path::new(&file_path);
-        let filename = path.file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("");
-        
-        let date_result = date_extractor.extract_date_auto(filename, &extracted_text);
-        
-        log::info!(
-            "📅 Data extraída: {} (fonte: {:?}, confidence: {:.2})",
-            date_result.value.format("%Y-%m-%d"),
-            date_result.source,
-            date_result.confidence
-        );
-        
-        // 2. GERAR FOLDER SLUG
-        let folder_slug = generate_folder_slug(&date_result.value);
-        let document_date = date_result.value.format("%Y-%m-%d").to_string();
-        
-        // 3. OBTER METADADOS DO ARQUIVO
-        let file_metadata = std::fs::metadata(&file_path)
-            .map_err(|e| format!("Erro ao ler metadados do arquivo: {:?}", e))?;
-        
-        let file_size = file_metadata.len() as i64;
-        let file_type = path.extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-        
-        // 4. CRIAR DOCUMENTO NO BANCO
-        let doc_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
-        
-        let document = database_sqlite::Document {
-            id: doc_id.clone(),
-            user_id: user.id.clone(),
-            name: filename.to_string(),
-            file_path: file_path.clone(),
-            file_type: file_type.clone(),
-            file_size,
-            created_at: now,
-            updated_at: now,
-            tags: vec![],
-            document_date: Some(document_date.clone()),
-            folder_slug: Some(folder_slug.clone()),
-        };
-        
-        state.db.create_document(&document)
-            .map_err(|e| format!("Erro ao criar documento no banco: {:?}", e))?;
-        
-        // 5. LOG NA TRILHA DE AUDITORIA
-        let _ = log_audit_event(
-            &state,
-            &user.id,
-            &user.username,
-            "DOCUMENT_CREATE",
-            "DOCUMENT",
-            Some(doc_id.clone()),
-            Some(filename.to_string()),
-            None,
-            Some(serde_json::json!({
-                "file_path": file_path,
-                "document_type": document_type,
-                "document_date": document_date,
-                "folder_slug": folder_slug,
-                "date_source": format!("{:?}", date_result.source),
-                "date_confidence": date_result.confidence,
-                "file_size": file_size,
-            })),
-            true,
-        ).await;
+        let known_date = document_date
+            .map(|date| chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("Data inválida: '{}'. Use o formato AAAA-MM-DD.", date)))
+            .transpose()?;
+        let mut document = ingest::create_document_record(&state, user, &file_path, &extracted_text, &document_type, known_date, on_duplicate.as_deref(), None).await?;
         
-        log::info!("✅ Documento criado: {} (pasta: {})", doc_id, folder_slug);
+        // Anexos do e-mail viram documentos filhos, como no pipeline de ingestão
+        if document.duplicate_of.is_none() && email_parser::is_email_file(std::path::Path::new(&file_path)) {
+            document.attachment_ids = ingest::ingest_email_attachments(&state, user, &document.id, &file_path).await?;
+        }
+        Ok(document)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Documentos extraídos de um documento (anexos de um e-mail)
+#[tauri::command]
+async fn list_document_attachments(
+    document_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<DocumentResponse>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let attachments = state.db.list_child_documents(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar anexos: {:?}", e))?;
         
-        Ok(CreateDocumentResponse {
-            id: doc_id,
-            name: filename.to_string(),
-            document_date: Some(document_date),
-            folder_slug: Some(folder_slug),
-            date_confidence: date_result.confidence,
-            date_source: format!("{:?}", date_result.source),
-        })
+        Ok(attachments.into_iter().map(|doc| DocumentResponse {
+            id: doc.id,
+            name: doc.name,
+            size: doc.file_size,
+            file_type: doc.file_type,
+            upload_date: doc.created_at.format("%d/%m/%Y").to_string(),
+            is_active: true,
+            category: "Anexo".to_string(),
+            preview_available: false,
+            tags: doc.tags,
+            parent_document_id: doc.parent_document_id,
+        }).collect())
     } else {
         Err("Usuário não autenticado".to_string())
     }
 }
 
 // ================================
-// COMANDOS DE ORGANIZAÇÃO POR PASTAS
+// DETECÇÃO DE DUPLICATAS
 // ================================
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct FolderInfo {
-    pub folder_slug: String,
-    pub document_count: i64,
+pub struct NearDuplicateInfo {
+    pub document_id: String,
+    pub name: String,
+    pub similarity: f64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateDocument {
+    pub id: String,
+    pub name: String,
+    pub file_size: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateCluster {
+    pub kind: String, // "exact" (mesmo arquivo) ou "near" (texto quase igual)
+    pub similarity: f64,
+    pub documents: Vec<DuplicateDocument>,
+}
+
+// Listar grupos de documentos duplicados: arquivos idênticos e textos quase iguais
 #[tauri::command]
-async fn get_available_folders(
+async fn find_duplicates(
     state: State<'_, AppState>,
-) -> Result<Vec<FolderInfo>, String> {
+) -> Result<Vec<DuplicateCluster>, String> {
     let authenticated_user = state.authenticated_user.lock().await;
     if let Some(user) = authenticated_user.as_ref() {
-        let folders = state.db.get_available_folders(&user.id)
-            .map_err(|e| format!("Erro ao buscar pastas: {:?}", e))?;
+        let documents = state.db.get_documents_by_user(&user.id)
+            .map_err(|e| format!("Erro ao buscar documentos: {:?}", e))?;
         
-        let response: Vec<FolderInfo> = folders.into_iter().map(|(slug, count)| {
-            FolderInfo {
-                folder_slug: slug,
-                document_count: count,
+        let to_duplicate = |doc: &database_sqlite::Document| DuplicateDocument {
+            id: doc.id.clone(),
+            name: doc.name.clone(),
+            file_size: doc.file_size,
+            created_at: doc.created_at.to_rfc3339(),
+        };
+        
+        let mut clusters = Vec::new();
+        
+        // Duplicatas exatas: mesmo hash de arquivo
+        let mut by_hash: std::collections::BTreeMap<&str, Vec<&database_sqlite::Document>> = std::collections::BTreeMap::new();
+        for doc in &documents {
+            if let Some(hash) = doc.file_hash.as_deref() {
+                by_hash.entry(hash).or_default().push(doc);
             }
-        }).collect();
+        }
+        for group in by_hash.values().filter(|g| g.len() > 1) {
+            clusters.push(DuplicateCluster {
+                kind: "exact".to_string(),
+                similarity: 1.0,
+                documents: group.iter().map(|doc| to_duplicate(doc)).collect(),
+            });
+        }
         
-        log::debug!("📂 Retornando {} pastas virtuais", response.len());
-        Ok(response)
+        // Quase-duplicatas: candidatos pelas assinaturas MinHash (LSH), como na importação,
+        // confirmados pelo Jaccard exato; só o texto dos candidatos é carregado
+        let signatures = state.db.get_document_signatures(&user.id)
+            .map_err(|e| format!("Erro ao buscar assinaturas para comparação: {:?}", e))?;
+        let signature_values: Vec<Vec<u64>> = signatures.iter().map(|(_, signature)| signature.clone()).collect();
+        let candidates = near_duplicate::candidate_pairs(&signature_values, near_duplicate::NEAR_DUPLICATE_THRESHOLD);
+        
+        let mut shingle_sets: std::collections::HashMap<usize, std::collections::HashSet<u64>> = std::collections::HashMap::new();
+        for &index in candidates.iter().flat_map(|(i, j)| [i, j]) {
+            if let std::collections::hash_map::Entry::Vacant(slot) = shingle_sets.entry(index) {
+                let text = state.db.get_document_content(&user.id, &signatures[index].0)
+                    .map_err(|e| format!("Erro ao buscar texto para comparação: {:?}", e))?
+                    .map(|content| content.extracted_text)
+                    .unwrap_or_default();
+                slot.insert(near_duplicate::shingles(&text));
+            }
+        }
+        let similarity_of = |i: usize, j: usize| near_duplicate::jaccard(&shingle_sets[&i], &shingle_sets[&j]);
+        let confirmed: Vec<(usize, usize)> = candidates.into_iter()
+            .filter(|&(i, j)| similarity_of(i, j) >= near_duplicate::NEAR_DUPLICATE_THRESHOLD)
+            .collect();
+        
+        for group in near_duplicate::cluster_pairs(signatures.len(), &confirmed) {
+            let docs: Vec<&database_sqlite::Document> = group.iter()
+                .filter_map(|&i| documents.iter().find(|d| d.id == signatures[i].0))
+                .collect();
+            
+            // Grupos em que todos têm o mesmo arquivo já aparecem como duplicata exata
+            let first_hash = docs.first().and_then(|d| d.file_hash.as_deref());
+            if docs.len() < 2 || (first_hash.is_some() && docs.iter().all(|d| d.file_hash.as_deref() == first_hash)) {
+                continue;
+            }
+            
+            let mut similarity: f64 = 1.0;
+            for (a, &i) in group.iter().enumerate() {
+                for &j in &group[a + 1..] {
+                    similarity = similarity.min(similarity_of(i, j));
+                }
+            }
+            
+            clusters.push(DuplicateCluster {
+                kind: "near".to_string(),
+                similarity,
+                documents: docs.into_iter().map(to_duplicate).collect(),
+            });
+        }
+        
+        log::info!("♊ {} grupos de duplicatas encontrados", clusters.len());
+        Ok(clusters)
     } else {
         Err("Usuário não autenticado".to_string())
     }
 }
 
+// Mesclar duplicatas: o documento mantido herda as tags e os demais vão para a lixeira
 #[tauri::command]
-async fn get_documents_by_folder(
-    folder_slug: String,
+async fn merge_duplicates(
+    keep_document_id: String,
+    duplicate_document_ids: Vec<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<DocumentResponse>, String> {
+) -> Result<usize, String> {
     let authenticated_user = state.authenticated_user.lock().await;
     if let Some(user) = authenticated_user.as_ref() {
-        let documents = state.db.get_documents_by_folder(&user.id, &folder_slug)
-            .map_err(|e| format!("Erro ao buscar documentos da pasta: {:?}", e))?;
+        let keep = get_active_document(&state, &user.id, &keep_document_id)?;
         
-        let response: Vec<DocumentResponse> = documents.into_iter().map(|doc| {
-            DocumentResponse {
-                id: doc.id,
-                name: doc.name,
-                size: doc.file_size,
-                file_type: doc.file_type,
-                upload_date: doc.document_date.unwrap_or_else(|| doc.created_at.format("%Y-%m-%d").to_string()),
-                is_active: true,
-                category: doc.folder_slug.unwrap_or_else(|| "Sem pasta".to_string()),
-                preview_available: false,
+        let mut tags = keep.tags.clone();
+        let mut merged = Vec::new();
+        
+        for duplicate_id in duplicate_document_ids.iter().filter(|id| **id != keep_document_id) {
+            let duplicate = match state.db.get_document_by_id(&user.id, duplicate_id)
+                .map_err(|e| format!("Erro ao buscar documento: {:?}", e))? {
+                Some(doc) if doc.deleted_at.is_none() => doc,
+                _ => continue,
+            };
+            
+            tags.extend(duplicate.tags.iter().cloned());
+            
+            if state.db.soft_delete_document(&user.id, &duplicate.id)
+                .map_err(|e| format!("Erro ao mover duplicata para a lixeira: {:?}", e))? {
+                merged.push(duplicate.id);
             }
-        }).collect();
+        }
         
-        log::debug!("📁 Retornando {} documentos da pasta {}", response.len(), folder_slug);
+        let tags = normalize_tag_names(tags);
+        if tags != keep.tags {
+            state.db.set_document_tags(&user.id, &keep.id, &tags)
+                .map_err(|e| format!("Erro ao atualizar tags: {:?}", e))?;
+        }
         
-        Ok(response)
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "MERGE",
+            "DOCUMENT",
+            Some(keep.id.clone()),
+            Some(keep.name.clone()),
+            keep.file_hash.clone(),
+            Some(serde_json::json!({
+                "merged_documents": merged,
+                "tags": tags,
+            })),
+            true,
+        ).await;
+        
+        log::info!("♊ {} duplicatas mescladas em {}", merged.len(), keep.id);
+        Ok(merged.len())
     } else {
         Err("Usuário não autenticado".to_string())
     }
 }
 
+// ================================
+// COMANDO DE EDIÇÃO DE METADADOS
+// ================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateDocumentResponse {
+    pub id: String,
+    pub name: String,
+    pub tags: Vec<String>,
+    pub document_date: Option<String>,
+    pub folder_slug: Option<String>,
+    pub updated_at: String,
+    pub changes: serde_json::Value,
+}
+
 #[tauri::command]
-async fn get_documents_by_date_range(
-    start_date: String,
-    end_date: String,
+async fn update_document(
+    document_id: String,
+    name: Option<String>,
+    tags: Option<Vec<String>>,
+    document_date: Option<String>,
+    folder_slug: Option<String>,
+    document_type: Option<String>,
     state: State<'_, AppState>,
-) -> Result<Vec<DocumentResponse>, String> {
+) -> Result<UpdateDocumentResponse, String> {
     let authenticated_user = state.authenticated_user.lock().await;
     if let Some(user) = authenticated_user.as_ref() {
-        let documents = state.db.get_documents_by_date_range(&user.id, &start_date, &end_date)
-            .map_err(|e| format!("Erro ao buscar documentos por data: {:?}", e))?;
+        let before = state.db.get_document_by_id(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?
+            .ok_or_else(|| "Documento não encontrado".to_string())?;
         
-        let response: Vec<DocumentResponse> = documents.into_iter().map(|doc| {
-            DocumentResponse {
-                id: doc.id,
-                name: doc.name,
-                size: doc.file_size,
-                file_type: doc.file_type,
-                upload_date: doc.document_date.unwrap_or_else(|| doc.created_at.format("%Y-%m-%d").to_string()),
-                is_active: true,
-                category: doc.folder_slug.unwrap_or_else(|| "Sem pasta".to_string()),
-                preview_available: false,
+        if before.deleted_at.is_some() {
+            return Err("Documento está na lixeira. Restaure-o antes de editar.".to_string());
+        }
+        
+        let mut after = before.clone();
+        
+        if let Some(name) = name {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err("Nome do documento não pode estar vazio".to_string());
             }
-        }).collect();
+            after.name = name.to_string();
+        }
         
-        log::debug!("📅 Retornando {} documentos entre {} e {}", response.len(), start_date, end_date);
-        Ok(response)
-    } else {
-        Err("Usuário não autenticado".to_string())
-    }
+        if let Some(tags) = tags {
+            after.tags = normalize_tag_names(tags);
+        }
+        
+        if let Some(date_str) = document_date {
+            let date = chrono::NaiveDate::parse_from_str(date_str.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("Data inválida: '{}'. Use o formato AAAA-MM-DD.", date_str))?;
+            after.document_date = Some(date.format("%Y-%m-%d").to_string());
+            
+            // Pasta virtual acompanha a data, a menos que outra pasta seja informada
+            if folder_slug.is_none() {
+                after.folder_slug = Some(generate_folder_slug(&date));
+            }
+        }
+        
+        if let Some(slug) = folder_slug {
+            let slug = slug.trim().trim_matches('/');
+            if slug.is_empty() {
+                return Err("Pasta não pode estar vazia".to_string());
+            }
+            after.folder_slug = Some(slug.to_string());
+        }
+        
+        // Tipo corrigido pelo usuário: vira exemplo de treino do classificador
+        let mut type_change = None;
+        if let Some(document_type) = document_type {
+            let document_type = document_type.trim().to_lowercase();
+            if document_type.is_empty() {
+                return Err("Tipo do documento não pode estar vazio".to_string());
+            }
+            let content = state.db.get_document_content(&user.id, &document_id)
+                .map_err(|e| format!("Erro ao buscar conteúdo do documento: {:?}", e))?
+                .ok_or_else(|| "Documento sem texto indexado para classificar".to_string())?;
+            if content.document_type != document_type {
+                type_change = Some((content.document_type, document_type));
+            }
+        }
+        
+        // Diff antes/depois apenas dos campos alterados
+        let mut changes = serde_json::Map::new();
+        if before.name != after.name {
+            changes.insert("name".to_string(), serde_json::json!({"before": before.name, "after": after.name}));
+        }
+        if before.tags != after.tags {
+            changes.insert("tags".to_string(), serde_json::json!({"before": before.tags, "after": after.tags}));
+        }
+        if before.document_date != after.document_date {
+            changes.insert("document_date".to_string(), serde_json::json!({"before": before.document_date, "after": after.document_date}));
+        }
+        if before.folder_slug != after.folder_slug {
+            changes.insert("folder_slug".to_string(), serde_json::json!({"before": before.folder_slug, "after": after.folder_slug}));
+        }
+        if let Some((before_type, after_type)) = &type_change {
+            changes.insert("document_type".to_string(), serde_json::json!({"before": before_type, "after": after_type}));
+        }
+        
+        if !changes.is_empty() {
+            after.updated_at = Utc::now();
+            
+            let updated = state.db.update_document(&after)
+                .map_err(|e| format!("Erro ao atualizar documento: {:?}", e))?;
+            
+            if !updated {
+                return Err("Documento não encontrado".to_string());
+            }
+            
+            if let Some((_, document_type)) = &type_change {
+                state.db.set_document_type(&user.id, &after.id, document_type)
+                    .map_err(|e| format!("Erro ao corrigir tipo do documento: {:?}", e))?;
+                ingest::forget_classifier(&state, user).await;
+            }
+            
+            let _ = log_audit_event(
+                &state,
+                &user.id,
+                &user.username,
+                "MODIFY",
+                "DOCUMENT",
+                Some(after.id.clone()),
+                Some(after.name.clone()),
+                after.file_hash.clone(),
+                Some(serde_json::json!({ "changes": changes.clone() })),
+                true,
+            ).await;
+            
+            log::info!("✏️ Documento {} atualizado ({} campos)", after.id, changes.len());
+        }
+        
+        Ok(UpdateDocumentResponse {
+            id: after.id,
+            name: after.name,
+            tags: after.tags,
+            document_date: after.document_date,
+            folder_slug: after.folder_slug,
+            updated_at: after.updated_at.to_rfc3339(),
+            changes: serde_json::Value::Object(changes),
+        })
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// ================================
+// COMANDOS DE TAGS
+// ================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagInfo {
+    pub id: String,
+    pub name: String,
+    pub document_count: i64,
+}
+
+impl From<database_sqlite::Tag> for TagInfo {
+    fn from(tag: database_sqlite::Tag) -> Self {
+        TagInfo {
+            id: tag.id,
+            name: tag.name,
+            document_count: tag.document_count,
+        }
+    }
+}
+
+// Remover espaços, vazios e duplicatas (sem diferenciar maiúsculas), mantendo a ordem
+fn normalize_tag_names(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+#[tauri::command]
+async fn list_tags(
+    state: State<'_, AppState>,
+) -> Result<Vec<TagInfo>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let tags = state.db.list_tags(&user.id)
+            .map_err(|e| format!("Erro ao buscar tags: {:?}", e))?;
+        
+        log::debug!("🏷️ Retornando {} tags", tags.len());
+        Ok(tags.into_iter().map(TagInfo::from).collect())
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+async fn create_tag(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<TagInfo, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Nome da tag não pode estar vazio".to_string());
+        }
+        
+        let tag_id = state.db.create_tag(&user.id, name)
+            .map_err(|e| format!("Erro ao criar tag: {:?}", e))?;
+        
+        let tag = state.db.get_tag(&user.id, &tag_id)
+            .map_err(|e| format!("Erro ao buscar tag: {:?}", e))?
+            .ok_or_else(|| "Tag não encontrada".to_string())?;
+        
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "TAG_CREATE",
+            "TAG",
+            Some(tag.id.clone()),
+            Some(tag.name.clone()),
+            None,
+            None,
+            true,
+        ).await;
+        
+        log::info!("🏷️ Tag criada: {}", tag.name);
+        Ok(tag.into())
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+async fn rename_tag(
+    tag_id: String,
+    new_name: String,
+    state: State<'_, AppState>,
+) -> Result<TagInfo, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err("Nome da tag não pode estar vazio".to_string());
+        }
+        
+        let before = state.db.get_tag(&user.id, &tag_id)
+            .map_err(|e| format!("Erro ao buscar tag: {:?}", e))?
+            .ok_or_else(|| "Tag não encontrada".to_string())?;
+        
+        let conflict = state.db.list_tags(&user.id)
+            .map_err(|e| format!("Erro ao buscar tags: {:?}", e))?
+            .into_iter()
+            .find(|t| t.id != tag_id && t.name.eq_ignore_ascii_case(new_name));
+        if let Some(existing) = conflict {
+            return Err(format!("Já existe a tag '{}'. Use mesclar tags para combiná-las.", existing.name));
+        }
+        
+        state.db.rename_tag(&user.id, &tag_id, new_name)
+            .map_err(|e| format!("Erro ao renomear tag: {:?}", e))?;
+        
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "TAG_RENAME",
+            "TAG",
+            Some(tag_id.clone()),
+            Some(new_name.to_string()),
+            None,
+            Some(serde_json::json!({"before": before.name, "after": new_name})),
+            true,
+        ).await;
+        
+        log::info!("🏷️ Tag renomeada: {} → {}", before.name, new_name);
+        Ok(TagInfo {
+            id: tag_id,
+            name: new_name.to_string(),
+            document_count: before.document_count,
+        })
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+async fn merge_tags(
+    source_tag_ids: Vec<String>,
+    target_tag_id: String,
+    state: State<'_, AppState>,
+) -> Result<TagInfo, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let tags = state.db.list_tags(&user.id)
+            .map_err(|e| format!("Erro ao buscar tags: {:?}", e))?;
+        
+        if !tags.iter().any(|t| t.id == target_tag_id) {
+            return Err("Tag de destino não encontrada".to_string());
+        }
+        let source_names: Vec<String> = tags.iter()
+            .filter(|t| t.id != target_tag_id && source_tag_ids.contains(&t.id))
+            .map(|t| t.name.clone())
+            .collect();
+        if source_names.is_empty() {
+            return Err("Nenhuma tag de origem válida para mesclar".to_string());
+        }
+        
+        let moved = state.db.merge_tags(&user.id, &source_tag_ids, &target_tag_id)
+            .map_err(|e| format!("Erro ao mesclar tags: {:?}", e))?;
+        
+        let target = state.db.get_tag(&user.id, &target_tag_id)
+            .map_err(|e| format!("Erro ao buscar tag: {:?}", e))?
+            .ok_or_else(|| "Tag de destino não encontrada".to_string())?;
+        
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "TAG_MERGE",
+            "TAG",
+            Some(target.id.clone()),
+            Some(target.name.clone()),
+            None,
+            Some(serde_json::json!({"merged_tags": source_names, "documents_moved": moved})),
+            true,
+        ).await;
+        
+        log::info!("🏷️ {} tags mescladas em '{}'", source_names.len(), target.name);
+        Ok(target.into())
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+async fn delete_tag(
+    tag_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let tag = state.db.get_tag(&user.id, &tag_id)
+            .map_err(|e| format!("Erro ao buscar tag: {:?}", e))?
+            .ok_or_else(|| "Tag não encontrada".to_string())?;
+        
+        let deleted = state.db.delete_tag(&user.id, &tag_id)
+            .map_err(|e| format!("Erro ao excluir tag: {:?}", e))?;
+        
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "TAG_DELETE",
+            "TAG",
+            Some(tag.id.clone()),
+            Some(tag.name.clone()),
+            None,
+            Some(serde_json::json!({"documents_untagged": tag.document_count})),
+            true,
+        ).await;
+        
+        log::info!("🏷️ Tag excluída: {}", tag.name);
+        Ok(deleted)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Adicionar e/ou remover tags de vários documentos de uma vez
+#[tauri::command]
+async fn bulk_tag_documents(
+    document_ids: Vec<String>,
+    add_tags: Option<Vec<String>>,
+    remove_tags: Option<Vec<String>>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let add_tags = normalize_tag_names(add_tags.unwrap_or_default());
+        let remove_tags = normalize_tag_names(remove_tags.unwrap_or_default());
+        
+        if document_ids.is_empty() || (add_tags.is_empty() && remove_tags.is_empty()) {
+            return Ok(0);
+        }
+        
+        let affected = state.db.bulk_tag_documents(&user.id, &document_ids, &add_tags, &remove_tags)
+            .map_err(|e| format!("Erro ao aplicar tags: {:?}", e))?;
+        
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "TAG_BULK",
+            "DOCUMENT",
+            None,
+            None,
+            None,
+            Some(serde_json::json!({
+                "document_ids": document_ids,
+                "added": add_tags,
+                "removed": remove_tags,
+                "documents_affected": affected,
+            })),
+            true,
+        ).await;
+        
+        log::info!("🏷️ Tags aplicadas em {} documentos", affected);
+        Ok(affected)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// ================================
+// COMANDOS DE ORGANIZAÇÃO POR PASTAS
+// ================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FolderInfo {
+    pub folder_slug: String,
+    pub document_count: i64,
+}
+
+#[tauri::command]
+async fn get_available_folders(
+    state: State<'_, AppState>,
+) -> Result<Vec<FolderInfo>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let folders = state.db.get_available_folders(&user.id)
+            .map_err(|e| format!("Erro ao buscar pastas: {:?}", e))?;
+        
+        let response: Vec<FolderInfo> = folders.into_iter().map(|(slug, count)| {
+            FolderInfo {
+                folder_slug: slug,
+                document_count: count,
+            }
+        }).collect();
+        
+        log::debug!("📂 Retornando {} pastas virtuais", response.len());
+        Ok(response)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+async fn get_documents_by_folder(
+    folder_slug: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<DocumentResponse>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let documents = state.db.get_documents_by_folder(&user.id, &folder_slug)
+            .map_err(|e| format!("Erro ao buscar documentos da pasta: {:?}", e))?;
+        
+        let response: Vec<DocumentResponse> = documents.into_iter().map(|doc| {
+            DocumentResponse {
+                id: doc.id,
+                name: doc.name,
+                size: doc.file_size,
+                file_type: doc.file_type,
+                upload_date: doc.document_date.unwrap_or_else(|| doc.created_at.format("%Y-%m-%d").to_string()),
+                is_active: true,
+                category: doc.folder_slug.unwrap_or_else(|| "Sem pasta".to_string()),
+                preview_available: false,
+                tags: doc.tags,
+                parent_document_id: doc.parent_document_id,
+            }
+        }).collect();
+        
+        log::debug!("📁 Retornando {} documentos da pasta {}", response.len(), folder_slug);
+        
+        Ok(response)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Tipo de data vindo da interface ("vencimento", "emissao"...); vazio ou "documento" = data principal
+fn parse_date_kind(date_kind: Option<String>) -> Result<Option<DateKind>, String> {
+    match date_kind.as_deref().map(str::trim) {
+        None | Some("") | Some("documento") => Ok(None),
+        Some(kind) => DateKind::parse(kind).map(Some).ok_or_else(|| {
+            let kinds: Vec<&str> = DateKind::ALL.iter().map(DateKind::as_str).collect();
+            format!("Tipo de data inválido: {} (use documento, {})", kind, kinds.join(", "))
+        }),
+    }
+}
+
+#[tauri::command]
+async fn get_documents_by_date_range(
+    start_date: String,
+    end_date: String,
+    date_kind: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DocumentResponse>, String> {
+    let date_kind = parse_date_kind(date_kind)?;
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let documents = state.db.get_documents_by_date_range(&user.id, &start_date, &end_date, date_kind, None)
+            .map_err(|e| format!("Erro ao buscar documentos por data: {:?}", e))?;
+        
+        let response: Vec<DocumentResponse> = documents.into_iter().map(|doc| {
+            DocumentResponse {
+                id: doc.id,
+                name: doc.name,
+                size: doc.file_size,
+                file_type: doc.file_type,
+                upload_date: doc.document_date.unwrap_or_else(|| doc.created_at.format("%Y-%m-%d").to_string()),
+                is_active: true,
+                category: doc.folder_slug.unwrap_or_else(|| "Sem pasta".to_string()),
+                preview_available: false,
+                tags: doc.tags,
+                parent_document_id: doc.parent_document_id,
+            }
+        }).collect();
+        
+        log::debug!("📅 Retornando {} documentos entre {} e {}", response.len(), start_date, end_date);
+        Ok(response)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Datas candidatas do documento (nome + texto indexado), ranqueadas e com explicação,
+// para o usuário escolher outra quando a data detectada tem confiança baixa
+#[tauri::command]
+async fn get_date_candidates(
+    document_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<RankedDate>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let document = state.db.get_document_by_id(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?
+            .ok_or_else(|| "Documento não encontrado".to_string())?;
+        let content = state.db.get_document_content(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar conteúdo do documento: {:?}", e))?;
+        
+        let (text, document_type) = content
+            .map(|content| (content.extracted_text, Some(content.document_type)))
+            .unwrap_or_default();
+        let ranked = DateExtractor::for_text(&text).rank_dates(&document.name, &text, document_type.as_deref());
+        
+        log::debug!("📅 {} datas candidatas para {}", ranked.len(), document_id);
+        Ok(ranked)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Datas com papel encontradas no texto do documento (emissão, vencimento, vigência, competência)
+#[tauri::command]
+async fn get_document_dates(
+    document_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<TypedDate>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        state.db.get_document_dates(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar datas do documento: {:?}", e))
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Valores em reais (ou outra moeda) vindos da interface -> centavos
+fn amount_filter(
+    min_amount: Option<f64>,
+    max_amount: Option<f64>,
+    role: Option<String>,
+    currency: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> AmountFilter {
+    AmountFilter {
+        min_cents: min_amount.map(|value| (value * 100.0).round() as i64),
+        max_cents: max_amount.map(|value| (value * 100.0).round() as i64),
+        role: role.filter(|role| !role.trim().is_empty()),
+        currency: currency.filter(|currency| !currency.trim().is_empty()),
+        start_date,
+        end_date,
+    }
+}
+
+// Documentos com valores monetários na faixa (ex.: totais entre R$ 1.000 e R$ 5.000 em 2024)
+#[tauri::command]
+async fn search_documents_by_amount(
+    min_amount: Option<f64>,
+    max_amount: Option<f64>,
+    role: Option<String>,
+    currency: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AmountMatch>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let filter = amount_filter(min_amount, max_amount, role, currency, start_date, end_date);
+        let matches = state.db.find_documents_by_amount(&user.id, &filter)
+            .map_err(|e| format!("Erro ao buscar documentos por valor: {:?}", e))?;
+        
+        log::debug!("💰 Retornando {} valores", matches.len());
+        Ok(matches)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Soma dos valores agrupada por mês, ano, papel ou tipo de documento
+#[tauri::command]
+async fn get_amount_report(
+    group_by: String,
+    role: Option<String>,
+    currency: Option<String>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AmountTotal>, String> {
+    if !matches!(group_by.as_str(), "month" | "year" | "role" | "document_type") {
+        return Err(format!("Agrupamento inválido: {} (use month, year, role ou document_type)", group_by));
+    }
+    
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let filter = amount_filter(None, None, role, currency, start_date, end_date);
+        state.db.get_amount_totals(&user.id, &filter, &group_by)
+            .map_err(|e| format!("Erro ao gerar relatório de valores: {:?}", e))
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Função para registrar automaticamente logs de auditoria (uso interno)
+pub async fn log_audit_event(
+    state: &AppState,
+    user_id: &str,
+    username: &str,
+    action: &str,
+    resource_type: &str,
+    resource_id: Option<String>,
+    resource_name: Option<String>,
+    file_hash: Option<String>,
+    metadata: Option<serde_json::Value>,
+    is_success: bool,
+) -> Result<(), String> {
+    state.db.create_audit_log(
+        user_id,
+        username,
+        action,
+        resource_type,
+        resource_id,
+        resource_name,
+        None, // ip_address - TODO: implementar detecção de IP
+        None, // user_agent - TODO: implementar detecção de User-Agent
+        file_hash,
+        metadata,
+        is_success,
+    ).map_err(|e| format!("Erro ao criar log de auditoria: {:?}", e))?;
+    
+    Ok(())
+}
+
+// ================================
+// COMANDOS DE BUSCA FULL-TEXT FTS5
+// ================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub results: Vec<SearchResultResponse>,
+    pub total_found: usize,
+    pub search_time_ms: u128,
+    pub indexed_docs: i64,
+    pub total_docs: i64,
+    pub interpretation: SearchInterpretation,
+}
+
+// Como a query foi entendida: texto, período ou os dois
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchInterpretation {
+    // Parte buscada no conteúdo (sem a data)
+    pub text: Option<String>,
+    // Período aplicado (YYYY-MM-DD)
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub date_kind: Option<String>,
+    // Para exibir: "textos 'nota fiscal' em outubro/2025"
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResultResponse {
+    pub document_id: String,
+    pub document_name: String,
+    pub document_type: String,
+    pub file_path: String,
+    pub relevance_score: f32,
+    pub matched_content: String,
+    pub created_at: String,
+    // Posição dos termos encontrados nas páginas (só documentos que passaram por OCR)
+    pub highlights: Vec<ocr_simple::WordBox>,
+}
+
+// Termos da consulta FTS5 para localizar as palavras nas páginas: sem operadores,
+// aspas, prefixo (*) nem termos de uma letra
+fn highlight_terms(query: &str) -> Vec<String> {
+    query.split(|c: char| c.is_whitespace() || "\"()*^:+-".contains(c))
+        .filter(|term| term.chars().count() > 1 && !["AND", "OR", "NOT", "NEAR"].contains(term))
+        .map(|term| term.to_string())
+        .collect()
+}
+
+fn search_interpretation(text: &str, date: Option<&DateSearchQuery>, date_kind: Option<DateKind>) -> SearchInterpretation {
+    let text = (!text.is_empty()).then(|| text.to_string());
+    let mut description = match (&text, date) {
+        (Some(text), Some(date)) => format!("textos '{}' em {}", text, date.describe()),
+        (None, Some(date)) => format!("documentos em {}", date.describe()),
+        (Some(text), None) => format!("textos '{}'", text),
+        (None, None) => String::new(),
+    };
+    if let (Some(kind), Some(_)) = (date_kind, date) {
+        description.push_str(&format!(" (data: {})", kind.as_str()));
+    }
+
+    SearchInterpretation {
+        text,
+        start_date: date.map(|date| date.start_date.format("%Y-%m-%d").to_string()),
+        end_date: date.map(|date| date.end_date.format("%Y-%m-%d").to_string()),
+        date_kind: date.and(date_kind).map(|kind| kind.as_str().to_string()),
+        description,
+    }
+}
+
+// Buscar documentos por texto, por data inteligente (PT-BR, inglês e espanhol) ou pelos
+// dois: "nota fiscal outubro 2025" busca "nota fiscal" só nos documentos de outubro/2025
+#[tauri::command]
+async fn search_documents(
+    query: String,
+    limit: Option<usize>,
+    use_fts: Option<bool>,
+    tags: Option<Vec<String>>,
+    match_all: Option<bool>,
+    date_kind: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SearchResponse, String> {
+    let date_kind = parse_date_kind(date_kind)?;
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let start_time = std::time::Instant::now();
+        
+        if query.trim().is_empty() {
+            return Err("Query de busca não pode estar vazia".to_string());
+        }
+        
+        // Obter estatísticas
+        let (total_docs, indexed_docs) = state.db.get_search_stats(&user.id)
+            .map_err(|e| format!("Erro ao obter estatísticas: {:?}", e))?;
+        
+        // BUSCA INTELIGENTE POR DATA
+        // Separar a data do texto: só data vai direto pelo intervalo; texto + data usa FTS5
+        // no texto restrito ao intervalo
+        let date_parser = DateSearchParser::new();
+        let date_split = date_parser.split(&query);
+        let text_query = date_split.as_ref().map(|split| split.text.clone()).unwrap_or_else(|| query.clone());
+        let interpretation = search_interpretation(&text_query, date_split.as_ref().map(|split| &split.date), date_kind);
+        let highlight_terms = highlight_terms(&text_query);
+        let date_range = date_split.as_ref().filter(|split| !split.text.is_empty()).map(|split| DateRangeFilter {
+            start_date: split.date.start_date.format("%Y-%m-%d").to_string(),
+            end_date: split.date.end_date.format("%Y-%m-%d").to_string(),
+            kind: date_kind,
+        });
+        let date_query = date_split.filter(|split| split.text.is_empty()).map(|split| split.date);
+        // Tags filtradas na própria consulta, antes do limite de resultados
+        let tag_names = normalize_tag_names(tags.unwrap_or_default());
+        let tag_filter = (!tag_names.is_empty()).then(|| TagFilter {
+            tags: tag_names.clone(),
+            match_all: match_all.unwrap_or(false),
+        });
+        log::info!("🔎 Busca interpretada como: {}", interpretation.description);
+        
+        let results = if let Some(date_query) = date_query {
+            log::info!("📅 Detectada busca por data: {} a {} ({:?})", 
+                      date_query.start_date.format("%d/%m/%Y"),
+                      date_query.end_date.format("%d/%m/%Y"),
+                      date_query.query_type);
+            
+            // Buscar documentos por intervalo de data (principal ou do tipo escolhido)
+            let mut docs = state.db.get_documents_by_date_range(
+                &user.id,
+                &date_query.start_date.format("%Y-%m-%d").to_string(),
+                &date_query.end_date.format("%Y-%m-%d").to_string(),
+                date_kind,
+                tag_filter.as_ref(),
+            ).map_err(|e| format!("Erro na busca por data: {:?}", e))?;
+            
+            // Aplicar limit se especificado
+            if let Some(limit_value) = limit {
+                docs.truncate(limit_value);
+            }
+            
+            // Converter para SearchResult format
+            docs.into_iter().map(|doc| {
+                database_sqlite::SearchResult {
+                    document_id: doc.id.clone(),
+                    document_name: doc.name.clone(),
+                    document_type: doc.file_type.clone(),
+                    file_path: doc.file_path.clone(),
+                    relevance_score: 1.0, // Busca por data tem relevância máxima
+                    matched_content: format!(
+                        "Data: {} | Pasta: {}", 
+                        doc.document_date.as_deref().unwrap_or("N/A"),
+                        doc.folder_slug.as_deref().unwrap_or("N/A")
+                    ),
+                    created_at: doc.created_at,
+                }
+            }).collect()
+        } else {
+            // Executar busca FTS5 normal (texto), restrita ao período se a query trouxe data
+            if use_fts.unwrap_or(true) {
+                // Tentar busca FTS5 primeiro
+                match state.db.search_documents(&user.id, &text_query, limit, date_range.as_ref(), tag_filter.as_ref()) {
+                    Ok(results) => results,
+                    Err(e) => {
+                        log::warn!("FTS5 falhou, usando busca simples: {:?}", e);
+                        state.db.simple_search_documents(&user.id, &text_query, limit, date_range.as_ref(), tag_filter.as_ref())
+                            .map_err(|e| format!("Erro na busca: {:?}", e))?
+                    }
+                }
+            } else {
+                // Busca simples
+                state.db.simple_search_documents(&user.id, &text_query, limit, date_range.as_ref(), tag_filter.as_ref())
+                    .map_err(|e| format!("Erro na busca simples: {:?}", e))?
+            }
+        };
+        
+        let search_time = start_time.elapsed().as_millis();
+        
+        // Log da busca na trilha de auditoria
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "SEARCH",
+            "DOCUMENT",
+            None,
+            None,
+            None,
+            Some(serde_json::json!({
+                "query": query,
+                "results_count": results.len(),
+                "search_time_ms": search_time,
+                "fts_enabled": use_fts.unwrap_or(true),
+                "tags": tag_names,
+                "date_kind": date_kind.map(|kind| kind.as_str()),
+                "interpretation": interpretation.description
+            })),
+            true,
+        ).await;
+        
+        // Converter para response format
+        let response_results: Vec<SearchResultResponse> = results.into_iter().map(|r| {
+            let highlights = state.db.find_word_boxes(&r.document_id, &highlight_terms).unwrap_or_else(|e| {
+                log::warn!("Erro ao buscar posições dos termos: {:?}", e);
+                vec![]
+            });
+            SearchResultResponse {
+                document_id: r.document_id,
+                document_name: r.document_name,
+                document_type: r.document_type,
+                file_path: r.file_path,
+                relevance_score: r.relevance_score,
+                matched_content: r.matched_content,
+                created_at: r.created_at.format("%d/%m/%Y %H:%M").to_string(),
+                highlights,
+            }
+        }).collect();
+        
+        let total_found = response_results.len();
+        log::info!("🔍 Busca '{}' concluída em {}ms - {} resultados", 
+                  query, search_time, total_found);
+        
+        Ok(SearchResponse {
+            results: response_results,
+            total_found,
+            search_time_ms: search_time,
+            indexed_docs,
+            total_docs,
+            interpretation,
+        })
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
 }
 
-// Função para registrar automaticamente logs de auditoria (uso interno)
-pub async fn log_audit_event(
-    state: &AppState,
-    user_id: &str,
-    username: &str,
-    action: &str,
-    resource_type: &str,
-    resource_id: Option<String>,
-    resource_name: Option<String>,
-    file_hash: Option<String>,
-    metadata: Option<serde_json::Value>,
-    is_success: bool,
-) -> Result<(), String> {
-    state.db.create_audit_log(
-        user_id,
-        username,
-        action,
-        resource_type,
-        resource_id,
-        resource_name,
-        None, // ip_address - TODO: implementar detecção de IP
-        None, // user_agent - TODO: implementar detecção de User-Agent
-        file_hash,
-        metadata,
-        is_success,
-    ).map_err(|e| format!("Erro ao criar log de auditoria: {:?}", e))?;
+// Indexar documento após processamento OCR
+#[tauri::command]
+async fn index_document_for_search(
+    document_id: String,
+    extracted_text: String,
+    document_type: String,
+    extracted_fields: serde_json::Value,
+    word_boxes: Option<Vec<ocr_simple::WordBox>>,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        ingest::index_document(&state, user, &document_id, &extracted_text, &document_type, &extracted_fields, word_boxes.as_deref()).await?;
+        Ok(true)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Obter estatísticas de busca
+#[tauri::command]
+async fn get_search_statistics(
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let (total_docs, indexed_docs) = state.db.get_search_stats(&user.id)
+            .map_err(|e| format!("Erro ao obter estatísticas: {:?}", e))?;
+        
+        let indexing_percentage = if total_docs > 0 {
+            (indexed_docs as f64 / total_docs as f64 * 100.0) as u32
+        } else {
+            0
+        };
+        
+        Ok(serde_json::json!({
+            "total_documents": total_docs,
+            "indexed_documents": indexed_docs,
+            "indexing_pecentage": indexing_percentage,
+            "fts5_available": true
+        }))
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// ================================
+// COMANDO DOWNLOAD NATIVO
+// ================================
+
+// Download de documento com save-as dialog nativo
+#[tauri::command]
+async fn download_document(
+    document_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        // Buscar documento no banco
+        let documents = state.db.get_documents_by_user(&user.id)
+            .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?;
+            
+        let document = documents.into_iter()
+            .find(|doc| doc.id == document_id)
+            .ok_or_else(|| "Documento não encontrado".to_string())?;
+            
+        // Dialog save-as nativo (será implementado via plugin-dialog no frontend)
+        log::info!("📥 Download solicitado: {} ({})", document.name, document.file_type);
+        
+        // Log da operação na trilha de auditoria
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "DOWNLOAD",
+            "DOCUMENT",
+            Some (document.id.clone()),
+            Some (document.name.clone()),
+            None,
+            Some(serde_json::json!({
+                "file_name": document.name,
+                "file_size": document.file_size,
+                "file_type": document.file_type
+            })),
+            true,
+        ).await;
+        
+        Ok(true)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// ================================
+// COMANDOS DE HISTÓRICO DE VERSÕES
+// ================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentVersionResponse {
+    pub id: String,
+    pub document_id: String,
+    pub version_number: i64,
+    pub file_hash: Option<String>,
+    pub file_size: i64,
+    pub file_type: String,
+    pub text_length: usize,
+    pub created_at: String,
+    pub is_current: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionDiffResponse {
+    pub from_version: i64,
+    pub to_version: i64,
+    pub summary: text_diff::DiffSummary,
+    pub lines: Vec<text_diff::DiffLine>,
+}
+
+fn version_response(version: database_sqlite::DocumentVersion, current_number: i64) -> DocumentVersionResponse {
+    DocumentVersionResponse {
+        is_current: version.version_number == current_number,
+        id: version.id,
+        document_id: version.document_id,
+        version_number: version.version_number,
+        file_hash: version.file_hash,
+        file_size: version.file_size,
+        file_type: version.file_type,
+        text_length: version.extracted_text.chars().count(),
+        created_at: version.created_at.to_rfc3339(),
+    }
+}
+
+// Buscar documento ativo (fora da lixeira) do usuário
+fn get_active_document(state: &AppState, user_id: &str, document_id: &str) -> Result<database_sqlite::Document, String> {
+    let document = state.db.get_document_by_id(user_id, document_id)
+        .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?
+        .ok_or_else(|| "Documento não encontrado".to_string())?;
     
-    Ok(())
+    if document.deleted_at.is_some() {
+        return Err("Documento está na lixeira. Restaure-o antes de continuar.".to_string());
+    }
+    Ok(document)
+}
+
+// Tornar a versão a atual: documento aponta para o arquivo e o índice de busca (texto, valores,
+// datas e posições das palavras) passa a ser o dela. Versão sem nada extraído (OCR falhou no
+// envio) mantém o índice anterior
+async fn apply_current_version(state: &AppState, user: &User, version: &database_sqlite::DocumentVersion) -> Result<(), String> {
+    let updated = state.db.set_document_current_version(&user.id, version)
+        .map_err(|e| format!("Erro ao atualizar documento: {:?}", e))?;
+    if !updated {
+        return Err("Documento não encontrado".to_string());
+    }
+    
+    if version.extracted_text.trim().is_empty() {
+        log::warn!("⚠️ Versão {} sem texto extraído; índice de busca anterior mantido", version.version_number);
+        return Ok(());
+    }
+    
+    // Versões registradas sem o OCR usam o tipo e os campos do índice atual
+    let current = state.db.get_document_content(&user.id, &version.document_id)
+        .map_err(|e| format!("Erro ao buscar conteúdo do documento: {:?}", e))?;
+    let ocr_result = version.ocr_result.as_ref();
+    
+    let document_type = ocr_result
+        .and_then(|r| r.get("document_type"))
+        .and_then(|t| t.as_str())
+        .map(|t| t.to_string())
+        .or_else(|| current.as_ref().map(|c| c.document_type.clone()))
+        .unwrap_or_else(|| extraction_rules::current().rule_set().default_type.clone());
+    let extracted_fields = ocr_result
+        .and_then(|r| r.get("extracted_fields"))
+        .cloned()
+        .or_else(|| current.as_ref().and_then(|c| serde_json::from_str(&c.extracted_fields).ok()))
+        .unwrap_or_else(|| serde_json::json!({}));
+    // Sem posições registradas, as do arquivo anterior não valem para este: ficam vazias
+    let word_boxes: Vec<ocr_simple::WordBox> = ocr_result
+        .and_then(|r| r.get("word_boxes"))
+        .and_then(|boxes| serde_json::from_value(boxes.clone()).ok())
+        .unwrap_or_default();
+    
+    ingest::index_document(state, user, &version.document_id, &version.extracted_text, &document_type, &extracted_fields, Some(&word_boxes)).await
+}
+
+#[tauri::command]
+async fn list_document_versions(
+    document_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<DocumentVersionResponse>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let versions = state.db.list_document_versions(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar versões: {:?}", e))?;
+        
+        let current_number = versions.first().map(|v| v.version_number).unwrap_or(0);
+        Ok(versions.into_iter().map(|v| version_response(v, current_number)).collect())
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Enviar um novo arquivo como nova versão de um documento existente
+#[tauri::command]
+async fn upload_document_version(
+    document_id: String,
+    file_path: String,
+    state: State<'_, AppState>,
+) -> Result<DocumentVersionResponse, String> {
+    // Copiar o usuário e liberar o lock: o OCR da nova versão pode demorar
+    let authenticated_user = state.authenticated_user.lock().await.clone();
+    if let Some(user) = authenticated_user.as_ref() {
+        let document = get_active_document(&state, &user.id, &document_id)?;
+        let path = std::path::Path::new(&file_path);
+        
+        let stored_file = state.file_store.store_file(&user.id, path)
+            .map_err(|e| format!("Erro ao copiar arquivo para o armazenamento: {:?}", e))?;
+        
+        // OCR da nova versão (falha no OCR não impede o versionamento do arquivo).
+        // Resultado com erro ou sem texto conta como falha: o índice anterior é mantido
+        let ocr_result = match ingest::run_ocr(&state, user, &file_path).await {
+            Ok(result) if result.error_message.is_none() && !result.extracted_text.trim().is_empty() => Some(result),
+            Ok(result) => {
+                log::warn!("⚠️ OCR da nova versão sem texto: {}", result.error_message.unwrap_or_default());
+                None
+            }
+            Err(e) => {
+                log::warn!("⚠️ OCR da nova versão falhou: {}", e);
+                None
+            }
+        };
+        
+        let version_number = state.db.next_version_number(&document_id)
+            .map_err(|e| format!("Erro ao calcular número da versão: {:?}", e))?;
+        
+        let version = database_sqlite::DocumentVersion {
+            id: Uuid::new_v4().to_string(),
+            document_id: document_id.clone(),
+            version_number,
+            file_path: stored_file.stored_path.to_string_lossy().to_string(),
+            file_hash: Some(stored_file.file_hash.clone()),
+            file_size: stored_file.file_size,
+            file_type: path.extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            extracted_text: ocr_result.as_ref().map(|r| r.extracted_text.clone()).unwrap_or_default(),
+            ocr_result: ocr_result.as_ref().and_then(|r| serde_json::to_value(r).ok()),
+            created_at: Utc::now(),
+        };
+        
+        state.db.create_document_version(&version)
+            .map_err(|e| format!("Erro ao registrar versão: {:?}", e))?;
+        apply_current_version(&state, user, &version).await?;
+        
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "VERSION_UPLOAD",
+            "DOCUMENT",
+            Some(document_id.clone()),
+            Some(document.name.clone()),
+            version.file_hash.clone(),
+            Some(serde_json::json!({
+                "version_number": version_number,
+                "previous_hash": document.file_hash,
+                "original_path": file_path,
+                "file_size": version.file_size,
+            })),
+            true,
+        ).await;
+        
+        log::info!("🗂️ Documento {} agora na versão {}", document_id, version_number);
+        Ok(version_response(version, version_number))
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Copiar o arquivo de uma versão para o destino escolhido pelo usuário
+#[tauri::command]
+async fn download_document_version(
+    document_id: String,
+    version_id: String,
+    destination_path: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let document = state.db.get_document_by_id(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?
+            .ok_or_else(|| "Documento não encontrado".to_string())?;
+        
+        let version = state.db.get_document_version(&user.id, &document_id, &version_id)
+            .map_err(|e| format!("Erro ao buscar versão: {:?}", e))?
+            .ok_or_else(|| "Versão não encontrada".to_string())?;
+        
+        std::fs::copy(&version.file_path, &destination_path)
+            .map_err(|e| format!("Erro ao copiar arquivo da versão: {:?}", e))?;
+        
+        log::info!("📥 Download da versão {} de {}", version.version_number, document.name);
+        
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "DOWNLOAD",
+            "DOCUMENT",
+            Some(document.id.clone()),
+            Some(document.name.clone()),
+            version.file_hash.clone(),
+            Some(serde_json::json!({
+                "version_number": version.version_number,
+                "destination_path": destination_path,
+                "file_size": version.file_size,
+            })),
+            true,
+        ).await;
+        
+        Ok(true)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Restaurar uma versão antiga: cria uma nova versão com o mesmo conteúdo (o histórico nunca é reescrito)
+#[tauri::command]
+async fn restore_document_version(
+    document_id: String,
+    version_id: String,
+    state: State<'_, AppState>,
+) -> Result<DocumentVersionResponse, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let document = get_active_document(&state, &user.id, &document_id)?;
+        
+        let source = state.db.get_document_version(&user.id, &document_id, &version_id)
+            .map_err(|e| format!("Erro ao buscar versão: {:?}", e))?
+            .ok_or_else(|| "Versão não encontrada".to_string())?;
+        
+        let version_number = state.db.next_version_number(&document_id)
+            .map_err(|e| format!("Erro ao calcular número da versão: {:?}", e))?;
+        
+        let version = database_sqlite::DocumentVersion {
+            id: Uuid::new_v4().to_string(),
+            version_number,
+            created_at: Utc::now(),
+            ..source.clone()
+        };
+        
+        state.db.create_document_version(&version)
+            .map_err(|e| format!("Erro ao registrar versão: {:?}", e))?;
+        apply_current_version(&state, user, &version).await?;
+        
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "VERSION_RESTORE",
+            "DOCUMENT",
+            Some(document_id.clone()),
+            Some(document.name.clone()),
+            version.file_hash.clone(),
+            Some(serde_json::json!({
+                "restored_from": source.version_number,
+                "version_number": version_number,
+            })),
+            true,
+        ).await;
+        
+        log::info!("↩️ Documento {} restaurado da versão {} (nova versão {})", document_id, source.version_number, version_number);
+        Ok(version_response(version, version_number))
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+// Diff linha a linha do texto extraído entre duas versões
+#[tauri::command]
+async fn diff_document_versions(
+    document_id: String,
+    from_version_id: String,
+    to_version_id: String,
+    state: State<'_, AppState>,
+) -> Result<VersionDiffResponse, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let versions = state.db.list_document_versions(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar versões: {:?}", e))?;
+        
+        let find = |id: &str| versions.iter()
+            .find(|v| v.id == id)
+            .ok_or_else(|| format!("Versão não encontrada: {}", id));
+        let from = find(&from_version_id)?;
+        let to = find(&to_version_id)?;
+        
+        let lines = text_diff::diff_lines(&from.extracted_text, &to.extracted_text);
+        let summary = text_diff::summarize(&lines);
+        
+        log::debug!("🔀 Diff v{} → v{}: +{} -{}", from.version_number, to.version_number, summary.added, summary.removed);
+        
+        Ok(VersionDiffResponse {
+            from_version: from.version_number,
+            to_version: to.version_number,
+            summary,
+            lines,
+        })
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
 }
 
 // ================================
-// COMANDOS DE BUSCA FULL-TEXT FTS5
+// COMANDOS DA LIXEIRA
 // ================================
 
+const TRASH_RETENTION_SETTING: &str = "trash_retention_days";
+const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+// Cem anos: valores maiores não cabem nas contas de data
+const MAX_TRASH_RETENTION_DAYS: i64 = 36500;
+
 #[derive(Debug, Serialize, Deserialize)]
-pub struct SearchResponse {
-    pub results: Vec<SearchResultResponse>,
-    pub total_found: usize,
-    pub search_time_ms: u128,
-    pub indexed_docs: i64,
-    pub total_docs: i64,
+pub struct TrashItemResponse {
+    pub id: String,
+    pub name: String,
+    pub size: i64,
+    pub file_type: String,
+    pub deleted_at: String,
+    pub purge_after: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SearchResultResponse {
-    pub document_id: String,
-    pub document_name: String,
-    pub document_type: String,
-    pub file_path: String,
-    pub relevance_score: f32,
-    pub matched_content: String,
-    pub created_at: String,
+fn trash_retention_days(state: &AppState, user_id: &str) -> i64 {
+    state.db.get_user_setting(user_id, TRASH_RETENTION_SETTING)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse::<i64>().ok())
+        .map(|days| days.clamp(1, MAX_TRASH_RETENTION_DAYS))
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+}
+
+// Expurgar documento da lixeira e remover o arquivo gerenciado se ninguém mais o usa
+async fn purge_document_internal(
+    state: &AppState,
+    user: &User,
+    document: &database_sqlite::Document,
+    reason: &str,
+) -> Result<bool, String> {
+    // Arquivos de todas as versões (o atual incluído), antes que o histórico seja apagado
+    let mut files: Vec<(String, String)> = state.db.list_document_versions(&user.id, &document.id)
+        .map_err(|e| format!("Erro ao buscar versões: {:?}", e))?
+        .into_iter()
+        .filter_map(|v| v.file_hash.map(|hash| (hash, v.file_path)))
+        .collect();
+    if let Some(file_hash) = document.file_hash.clone() {
+        files.push((file_hash, document.file_path.clone()));
+    }
+    files.sort();
+    files.dedup();
+    
+    let purged = state.db.purge_document(&user.id, &document.id)
+        .map_err(|e| format!("Erro ao expurgar documento: {:?}", e))?;
+    
+    if !purged {
+        return Ok(false);
+    }
+    
+    let mut files_removed = 0;
+    for (file_hash, file_path) in &files {
+        let remaining = state.db.count_documents_with_hash(&user.id, file_hash)
+            .map_err(|e| format!("Erro ao verificar referências do arquivo: {:?}", e))?;
+        
+        if remaining == 0 {
+            match state.file_store.remove_file(std::path::Path::new(file_path)) {
+                Ok(_) => files_removed += 1,
+                Err(e) => log::warn!("⚠️ Não foi possível remover arquivo {}: {:?}", file_path, e),
+            }
+        }
+    }
+    
+    let _ = log_audit_event(
+        state,
+        &user.id,
+        &user.username,
+        "PURGE",
+        "DOCUMENT",
+        Some(document.id.clone()),
+        Some(document.name.clone()),
+        document.file_hash.clone(),
+        Some(serde_json::json!({
+            "reason": reason,
+            "deleted_at": document.deleted_at.map(|d| d.to_rfc3339()),
+            "files_removed": files_removed,
+        })),
+        true,
+    ).await;
+    
+    Ok(true)
 }
 
-// Buscar documentos por texto OU por data inteligente em PT-BR
+// Expurgar automaticamente documentos que passaram do período de retenção
+async fn purge_expired_trash(state: &AppState, user: &User) -> Result<usize, String> {
+    let retention_days = trash_retention_days(state, &user.id);
+    let limit = match chrono::Duration::try_days(retention_days).and_then(|retention| Utc::now().checked_sub_signed(retention)) {
+        Some(limit) => limit,
+        None => return Ok(0),
+    };
+    
+    let expired = state.db.get_expired_trash(&user.id, limit)
+        .map_err(|e| format!("Erro ao buscar lixeira expirada: {:?}", e))?;
+    
+    let mut purged_count = 0;
+    for document in &expired {
+        if purge_document_internal(state, user, document, "retention").await? {
+            purged_count += 1;
+        }
+    }
+    
+    if purged_count > 0 {
+        log::info!("🧹 {} documentos expurgados da lixeira (retenção: {} dias)", purged_count, retention_days);
+    }
+    
+    Ok(purged_count)
+}
+
+// Mover documento para a lixeira
 #[tauri::command]
-async fn search_documents(
-    query: String,
-    limit: Option<usize>,
-    use_fts: Option<bool>,
+async fn delete_document(
+    document_id: String,
     state: State<'_, AppState>,
-) -> Result<SearchResponse, String> {
+) -> Result<bool, String> {
     let authenticated_user = state.authenticated_user.lock().await;
     if let Some(user) = authenticated_user.as_ref() {
-        let start_time = std::time::Instant::now();
-        
-        if query.trim().is_empty() {
-            return Err("Query de busca não pode estar vazia".to_string());
-        }
-        
-        // Obter estatísticas
-        let (total_docs, indexed_docs) = state.db.get_search_stats(&user.id)
-            .map_err(|e| format!("Erro ao obter estatísticas: {:?}", e))?;
+        let document = state.db.get_document_by_id(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?
+            .ok_or_else(|| "Documento não encontrado".to_string())?;
         
-        // BUSCA INTELIGENTE POR DATA EM PT-BR
-        // Tentar detectar se a query é uma busca por data antes de usar FTS5
-        let date_parser = DateSearchParser::new();
-        let results = if let Some(date_query) = date_parser.parse(&query) {
-            log::info!("📅 Detectada busca por data: {} a {} ({:?})", 
-                      date_query.start_date.format("%d/%m/%Y"),
-                      date_query.end_date.format("%d/%m/%Y"),
-                      date_query.query_type);
-            
-            // Buscar documentos por intervalo de data
-            let mut docs = state.db.get_documents_by_date_range(
-                &user.id,
-                &date_query.start_date.format("%Y-%m-%d").to_string(),
-                &date_query.end_date.format("%Y-%m-%d").to_string()
-            ).map_err(|e| format!("Erro na busca por data: {:?}", e))?;
-            
-            // Aplicar limit se especificado
-            if let Some(limit_value) = limit {
-                docs.truncate(limit_value);
-            }
-            
-            // Converter para SearchResult format
-            docs.into_iter().map(|doc| {
-                database_sqlite::SearchResult {
-                    document_id: doc.id.clone(),
-                    document_name: doc.name.clone(),
-                    document_type: doc.file_type.clone(),
-                    file_path: doc.file_path.clone(),
-                    relevance_score: 1.0, // Busca por data tem relevância máxima
-                    matched_content: format!(
-                        "Data: {} | Pasta: {}", 
-                        doc.document_date.as_deref().unwrap_or("N/A"),
-                        doc.folder_slug.as_deref().unwrap_or("N/A")
-                    ),
-                    created_at: chrono::NaiveDateTime::parse_from_str(&doc.created_at, "%Y-%m-%d %H:%M:%S")
-                        .unwrap_or_else(|_| chrono::Local::now().naive_local()),
-                }
-            }).collect()
-        } else {
-            // Executar busca FTS5 normal (texto)
-            if use_fts.unwrap_or(true) {
-                // Tentar busca FTS5 primeiro
-                match state.db.search_documents(&user.id, &query, limit) {
-                    Ok(results) => results,
-                    Err(e) => {
-                        log::warn!("FTS5 falhou, usando busca simples: {:?}", e);
-                        state.db.simple_search_documents(&user.id, &query, limit)
-                            .map_err(|e| format!("Erro na busca: {:?}", e))?
-                    }
-                }
-            } else {
-                // Busca simples
-                state.db.simple_search_documents(&user.id, &query, limit)
-                    .map_err(|e| format!("Erro na busca simples: {:?}", e))?
-            }
-        };
+        let deleted = state.db.soft_delete_document(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao excluir documento: {:?}", e))?;
         
-        let search_time = start_time.elapsed().as_millis();
+        if !deleted {
+            return Err("Documento já está na lixeira".to_string());
+        }
         
-        // Log da busca na trilha de auditoria
         let _ = log_audit_event(
             &state,
             &user.id,
             &user.username,
-            "SEARCH",
+            "DELETE",
             "DOCUMENT",
-            None,
-            None,
-            None,
+            Some(document.id.clone()),
+            Some(document.name.clone()),
+            document.file_hash.clone(),
             Some(serde_json::json!({
-                "query": query,
-                "results_count": results.len(),
-                "search_time_ms": search_time,
-                "fts_enabled": use_fts.unwrap_or(true)
+                "soft_delete": true,
+                "retention_days": trash_retention_days(&state, &user.id),
             })),
             true,
         ).await;
         
-        // Converter para response format
-        let response_results: Vec<SearchResultResponse> = results.into_iter().map(|r| {
-            SearchResultResponse {
-                document_id: r.document_id,
-                document_name: r.document_name,
-                document_type: r.document_type,
-                file_path: r.file_path,
-                relevance_score: r.relevance_score,
-                matched_content: r.matched_content,
-                created_at: r.created_at.format("%d/%m/%Y %H:%M").to_string(),
-            }
-        }).collect();
+        log::info!("🗑️ Documento {} movido para a lixeira", document_id);
+        Ok(true)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+async fn list_trash(
+    state: State<'_, AppState>,
+) -> Result<Vec<TrashItemResponse>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        purge_expired_trash(&state, user).await?;
         
-        let total_found = response_results.len();
-        log::info!("🔍 Busca '{}' concluída em {}ms - {} resultados", 
-                  query, search_time, total_found);
+        let retention_days = trash_retention_days(&state, &user.id);
+        let documents = state.db.list_trash(&user.id)
+            .map_err(|e| format!("Erro ao listar lixeira: {:?}", e))?;
         
-        Ok(SearchResponse {
-            results: response_results,
-            total_found,
-            search_time_ms: search_time,
-            indexed_docs,
-            total_docs,
-        })
+        let response: Vec<TrashItemResponse> = documents.into_iter().filter_map(|doc| {
+            let deleted_at = doc.deleted_at?;
+            Some(TrashItemResponse {
+                id: doc.id,
+                name: doc.name,
+                size: doc.file_size,
+                file_type: doc.file_type,
+                deleted_at: deleted_at.format("%d/%m/%Y %H:%M").to_string(),
+                purge_after: chrono::Duration::try_days(retention_days)
+                    .and_then(|retention| deleted_at.checked_add_signed(retention))
+                    .map(|date| date.format("%d/%m/%Y").to_string())
+                    .unwrap_or_default(),
+            })
+        }).collect();
+        
+        Ok(response)
     } else {
         Err("Usuário não autenticado".to_string())
     }
 }
 
-// Indexar documento após processamento OCR
 #[tauri::command]
-async fn index_document_for_search(
+async fn restore_document(
     document_id: String,
-    extracted_text: String,
-    document_type: String,
-    extracted_fields: serde_json::Value,
     state: State<'_, AppState>,
 ) -> Result<bool, String> {
     let authenticated_user = state.authenticated_user.lock().await;
     if let Some(user) = authenticated_user.as_ref() {
-        state.db.index_document_content(
-            &document_id,
-            &extracted_text,
-            &document_type,
-            &extracted_fields,
-        ).map_err(|e| format!("Erro ao indexar documento: {:?}", e))?;
-        
-        // Log da indexação
-        let doc_id_clone = document_id.clone();
+        let document = state.db.get_document_by_id(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?
+            .ok_or_else(|| "Documento não encontrado".to_string())?;
+        
+        let restored = state.db.restore_document(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao restaurar documento: {:?}", e))?;
+        
+        if !restored {
+            return Err("Documento não está na lixeira".to_string());
+        }
+        
         let _ = log_audit_event(
             &state,
             &user.id,
             &user.username,
-            "INDEX",
+            "RESTORE",
             "DOCUMENT",
-            Some(document_id),
-            None,
-            None,
+            Some(document.id.clone()),
+            Some(document.name.clone()),
+            document.file_hash.clone(),
             Some(serde_json::json!({
-                "document_type": document_type,
-                "text_length": extracted_text.len(),
-                "fields_count": extracted_fields.as_object().map(|o| o.len()).unwrap_or(0)
+                "deleted_at": document.deleted_at.map(|d| d.to_rfc3339()),
             })),
             true,
         ).await;
         
-        log::info!("📝 Documento {} indexado com sucesso", doc_id_clone);
+        log::info!("♻️ Documento {} restaurado da lixeira", document_id);
         Ok(true)
     } else {
         Err("Usuário não autenticado".to_string())
     }
 }
 
-// Obter estatísticas de busca
 #[tauri::command]
-async fn get_search_statistics(
+async fn purge_document(
+    document_id: String,
     state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<bool, String> {
     let authenticated_user = state.authenticated_user.lock().await;
     if let Some(user) = authenticated_user.as_ref() {
-        let (total_docs, indexed_docs) = state.db.get_search_stats(&user.id)
-            .map_err(|e| format!("Erro ao obter estatísticas: {:?}", e))?;
+        let document = state.db.get_document_by_id(&user.id, &document_id)
+            .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?
+            .ok_or_else(|| "Documento não encontrado".to_string())?;
         
-        let indexing_percentage = if total_docs > 0 {
-            (indexed_docs as f64 / total_docs as f64 * 100.0) as u32
-        } else {
-            0
-        };
+        if document.deleted_at.is_none() {
+            return Err("Mova o documento para a lixeira antes de excluí-lo definitivamente".to_string());
+        }
         
-        Ok(serde_json::json!({
-            "total_documents": total_docs,
-            "indexed_documents": indexed_docs,
-            "indexing_pecentage": indexing_percentage,
-            "fts5_available": true
-        }))
+        purge_document_internal(&state, user, &document, "manual").await
     } else {
         Err("Usuário não autenticado".to_string())
     }
 }
 
-// ================================
-// COMANDO DOWNLOAD NATIVO
-// ================================
+#[tauri::command]
+async fn get_trash_retention_days(
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        Ok(trash_retention_days(&state, &user.id))
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
 
-// Download de documento com save-as dialog nativo
 #[tauri::command]
-async fn download_document(
-    document_id: String,
+async fn set_trash_retention_days(
+    days: i64,
     state: State<'_, AppState>,
 ) -> Result<bool, String> {
     let authenticated_user = state.authenticated_user.lock().await;
     if let Some(user) = authenticated_user.as_ref() {
-        // Buscar documento no banco
-        let documents = state.db.get_documents_by_user(&user.id)
-            .map_err(|e| format!("Erro ao buscar documento: {:?}", e))?;
-            
-        let document = documents.into_iter()
-            .find(|doc| doc.id == document_id)
-            .ok_or_else(|| "Documento não encontrado".to_string())?;
-            
-        // Dialog save-as nativo (será implementado via plugin-dialog no frontend)
-        log::info!("📥 Download solicitado: {} ({})", document.name, document.file_type);
+        if days < 1 {
+            return Err("Retenção da lixeira deve ser de pelo menos 1 dia".to_string());
+        }
+        if days > MAX_TRASH_RETENTION_DAYS {
+            return Err(format!("Retenção da lixeira deve ser de no máximo {} dias", MAX_TRASH_RETENTION_DAYS));
+        }
+        
+        let previous_days = trash_retention_days(&state, &user.id);
+        state.db.set_user_setting(&user.id, TRASH_RETENTION_SETTING, &days.to_string())
+            .map_err(|e| format!("Erro ao salvar retenção da lixeira: {:?}", e))?;
         
-        // Log da operação na trilha de auditoria
         let _ = log_audit_event(
             &state,
             &user.id,
             &user.username,
-            "DOWNLOAD",
-            "DOCUMENT",
-            Some (document.id.clone()),
-            Some (document.name.clone()),
+            "MODIFY",
+            "SYSTEM",
+            None,
+            Some(TRASH_RETENTION_SETTING.to_string()),
             None,
             Some(serde_json::json!({
-                "file_name": document.name,
-                "file_size": document.file_size,
-                "file_type": document.file_type
+                "before": previous_days,
+                "after": days,
             })),
             true,
         ).await;
@@ -1211,6 +2686,16 @@ pub fn run() {
                 }
             }
             
+            // Tessdata empacotado com o instalador (OCR de PDFs escaneados)
+            if let Ok(resource_dir) = app.path().resource_dir() {
+                tesseract_cli::configure_tessdata_dir(resource_dir.join("tessdata"));
+            }
+            
+            // Serviços em segundo plano: fila de processamento e pastas monitoradas
+            let background_state = app.state::<AppState>().inner().clone();
+            job_queue::start_workers(background_state.clone(), app.handle().clone());
+            tauri::async_runtime::spawn(watch_folder::run_watch_service(background_state, app.handle().clone()));
+            
             log::info!("✅ Setup concluído com sucesso");
             Ok(())
         })
@@ -1225,19 +2710,57 @@ pub fn run() {
             get_recent_activities,
             get_audit_logs,
             verify_audit_chain,
-            // process_document_ocr,  // Desabilitado - requer tesseract
+            process_document_ocr,
             process_document_simple_ocr,
             get_supported_document_types,
+            get_ocr_backend,
+            test_extraction_rules,
+            classify_document,
             create_document,
+            list_document_attachments,
+            update_document,
+            list_tags,
+            create_tag,
+            rename_tag,
+            merge_tags,
+            delete_tag,
+            bulk_tag_documents,
+            find_duplicates,
+            merge_duplicates,
+            list_document_versions,
+            upload_document_version,
+            download_document_version,
+            restore_document_version,
+            diff_document_versions,
             get_available_folders,
             get_documents_by_folder,
             get_documents_by_date_range,
+            get_document_dates,
+            get_date_candidates,
+            search_documents_by_amount,
+            get_amount_report,
             search_documents,
             index_document_for_search,
             get_search_statistics,
             backup::verify_backup_file,
             backup::list_available_backups,
             download_document,
+            delete_document,
+            list_trash,
+            restore_document,
+            purge_document,
+            get_trash_retention_days,
+            set_trash_retention_days,
+            bulk_import::import_directory,
+            bulk_import::import_mailbox,
+            job_queue::enqueue_ingest_jobs,
+            job_queue::list_jobs,
+            job_queue::cancel_job,
+            job_queue::retry_job,
+            watch_folder::list_watch_folders,
+            watch_folder::add_watch_folder,
+            watch_folder::update_watch_folder,
+            watch_folder::remove_watch_folder,
             desktop::open_file_dialog,
             desktop::save_backup_dialog,
             desktop::open_in_explorer,
@@ -0,0 +1,278 @@
+// PARSER DE VALORES MONETÁRIOS
+// Converte "1.234,56", "1,234.56", "R$ 1.234" ou "99.90 EUR" em centavos + moeda e
+// identifica o papel de cada valor (total, desconto, imposto, parcela...) pelo rótulo
+// que aparece antes dele na mesma linha.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::br_validators;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MonetaryAmount {
+    // total, subtotal, desconto, imposto, parcela, acrescimo, frete ou valor (sem rótulo)
+    pub role: String,
+    // Código ISO 4217 (BRL quando o texto não indica a moeda)
+    pub currency: String,
+    // Valor em centavos
+    pub amount_cents: i64,
+    // Trecho original ("R$ 1.234,56")
+    pub raw: String,
+    // Posição (em bytes) no texto
+    pub position: usize,
+}
+
+pub const DEFAULT_ROLE: &str = "valor";
+pub const DEFAULT_CURRENCY: &str = "BRL";
+
+// Quanto texto antes do valor é considerado ao procurar o rótulo
+const LABEL_WINDOW_CHARS: usize = 60;
+
+// Rótulo -> papel. Comparado sem acentos e no início de palavra.
+const ROLE_LABELS: &[(&str, &str)] = &[
+    ("total", "total"),
+    ("valor total", "total"),
+    ("valor a pagar", "total"),
+    ("valor do documento", "total"),
+    ("valor cobrado", "total"),
+    ("valor pago", "total"),
+    ("valor liquido", "total"),
+    ("subtotal", "subtotal"),
+    ("desconto", "desconto"),
+    ("abatimento", "desconto"),
+    ("imposto", "imposto"),
+    ("tributo", "imposto"),
+    ("icms", "imposto"),
+    ("ipi", "imposto"),
+    ("iss", "imposto"),
+    ("pis", "imposto"),
+    ("cofins", "imposto"),
+    ("irrf", "imposto"),
+    ("inss", "imposto"),
+    ("csll", "imposto"),
+    ("parcela", "parcela"),
+    ("prestacao", "parcela"),
+    ("mensalidade", "parcela"),
+    ("juros", "acrescimo"),
+    ("multa", "acrescimo"),
+    ("mora", "acrescimo"),
+    ("frete", "frete"),
+];
+
+// Número com separador de milhar opcional e até 2 casas decimais
+const NUMBER: &str = r"\d{1,3}(?:[.,]\d{3})+(?:[.,]\d{1,2})?|\d+(?:[.,]\d{1,2})?";
+
+impl MonetaryAmount {
+    /// Valor com ponto decimal ("1234.56")
+    pub fn amount(&self) -> String {
+        format_cents(self.amount_cents)
+    }
+}
+
+pub fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02}", sign, cents.abs() / 100, cents.abs() % 100)
+}
+
+/// Código da moeda para um símbolo, código ou nome ("R$", "US$", "€", "eur", "reais")
+pub fn currency_code(symbol: &str) -> Option<&'static str> {
+    let code = match br_validators::fold(symbol.trim()).as_str() {
+        "r$" | "brl" | "real" | "reais" => "BRL",
+        "$" | "us$" | "u$" | "usd" | "dolar" | "dolares" => "USD",
+        "€" | "eur" | "euro" | "euros" => "EUR",
+        "£" | "gbp" => "GBP",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Interpretar um número nos formatos brasileiro e internacional, em centavos.
+/// Com os dois separadores, o último é o decimal; com um só, ele é decimal se aparece
+/// uma vez seguido de 1 ou 2 dígitos ("12,5", "1234.56") e milhar nos demais casos
+/// ("1.234", "1,234,567").
+pub fn parse_number(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    if raw.is_empty() || !raw.chars().all(|c| c.is_ascii_digit() || c == '.' || c == ',') {
+        return None;
+    }
+
+    let decimal_index = match (raw.rfind('.'), raw.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(index), None) | (None, Some(index)) => {
+            let separator = &raw[index..index + 1];
+            let decimals = raw.len() - index - 1;
+            (raw.matches(separator).count() == 1 && decimals != 3).then_some(index)
+        }
+        (None, None) => None,
+    };
+
+    let (integer, fraction) = match decimal_index {
+        Some(index) => (&raw[..index], &raw[index + 1..]),
+        None => (raw, ""),
+    };
+    if fraction.len() > 2 || fraction.contains(['.', ',']) {
+        return None;
+    }
+
+    // Grupos de milhar: o primeiro com 1 a 3 dígitos, os demais com exatamente 3
+    let groups: Vec<&str> = integer.split(['.', ',']).collect();
+    if groups.len() > 1 && (groups[0].is_empty() || groups[0].len() > 3 || groups[1..].iter().any(|group| group.len() != 3)) {
+        return None;
+    }
+
+    let digits: String = integer.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.len() > 15 || (digits.is_empty() && fraction.is_empty()) {
+        return None;
+    }
+    let units: i64 = if digits.is_empty() { 0 } else { digits.parse().ok()? };
+    let cents: i64 = format!("{:0<2}", fraction).parse().ok()?;
+    Some(units * 100 + cents)
+}
+
+// Número colado em outro número, data ou percentual ("10.10.2025", "5,00%") não é valor
+fn isolated(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    let glued = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '/' | '%'));
+    // Ponto ou vírgula finais de frase são aceitos
+    let after_ok = !glued(after) || (matches!(after, Some('.') | Some(',')) && !text[end + 1..].starts_with(|c: char| c.is_ascii_digit()));
+    !glued(before) && after_ok
+}
+
+fn role_before(text: &str, start: usize, floor: usize) -> Option<&'static str> {
+    let line_start = text[..start].rfind('\n').map(|index| index + 1).unwrap_or(0);
+    let mut window: Vec<char> = text[line_start.max(floor)..start].chars().rev().take(LABEL_WINDOW_CHARS).collect();
+    window.reverse();
+    br_validators::closest_label(&window.into_iter().collect::<String>(), ROLE_LABELS)
+}
+
+struct Candidate {
+    start: usize,
+    end: usize,
+    number: (usize, usize),
+    currency: Option<&'static str>,
+}
+
+/// Todos os valores monetários do texto, na ordem em que aparecem. Números sem moeda
+/// só contam com duas casas decimais e um rótulo conhecido na linha ("Desconto: 10,00").
+pub fn find_amounts(text: &str) -> Vec<MonetaryAmount> {
+    let patterns = [
+        // Moeda antes: R$ 1.234,56 / US$ 10 / EUR 99.90
+        format!(r"(?i)(R\$|US\$|U\$|\$|€|£|\b(?:BRL|USD|EUR|GBP)\b)\s*({})", NUMBER),
+        // Moeda depois: 100,00 reais / 99.90 EUR
+        format!(r"(?i)({})\s*(reais|d[oó]lares|euros|\b(?:BRL|USD|EUR|GBP)\b)", NUMBER),
+        // Sem moeda, com centavos
+        r"\d{1,3}(?:[.,]\d{3})+[.,]\d{2}|\d+[.,]\d{2}".to_string(),
+    ];
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for (kind, pattern) in patterns.iter().enumerate() {
+        let Ok(regex) = Regex::new(pattern) else { continue };
+        for captures in regex.captures_iter(text) {
+            let Some(whole) = captures.get(0) else { continue };
+            let (number, currency) = match kind {
+                0 => (captures.get(2), captures.get(1)),
+                1 => (captures.get(1), captures.get(2)),
+                _ => (captures.get(0), None),
+            };
+            let Some(number) = number else { continue };
+            if !isolated(text, number.start(), number.end()) {
+                continue;
+            }
+            if candidates.iter().any(|c| number.start() < c.number.1 && c.number.0 < number.end()) {
+                continue;
+            }
+            candidates.push(Candidate {
+                start: whole.start(),
+                end: whole.end(),
+                number: (number.start(), number.end()),
+                currency: currency.and_then(|symbol| currency_code(symbol.as_str())),
+            });
+        }
+    }
+    candidates.sort_by_key(|candidate| candidate.start);
+
+    let mut amounts = Vec::new();
+    let mut floor = 0;
+    for candidate in candidates {
+        let role = role_before(text, candidate.start, floor);
+        floor = candidate.end;
+
+        // Número solto só vale com rótulo
+        if candidate.currency.is_none() && role.is_none() {
+            continue;
+        }
+        let Some(amount_cents) = parse_number(&text[candidate.number.0..candidate.number.1]) else { continue };
+
+        amounts.push(MonetaryAmount {
+            role: role.unwrap_or(DEFAULT_ROLE).to_string(),
+            currency: candidate.currency.unwrap_or(DEFAULT_CURRENCY).to_string(),
+            amount_cents,
+            raw: text[candidate.start..candidate.end].to_string(),
+            position: candidate.start,
+        });
+    }
+    amounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number_formats() {
+        assert_eq!(parse_number("1.234,56"), Some(123456));
+        assert_eq!(parse_number("1,234.56"), Some(123456));
+        assert_eq!(parse_number("1234,56"), Some(123456));
+        assert_eq!(parse_number("1234.5"), Some(123450));
+        assert_eq!(parse_number("1.234"), Some(123400));
+        assert_eq!(parse_number("1,234,567"), Some(123456700));
+        assert_eq!(parse_number("1.234.567,89"), Some(123456789));
+        assert_eq!(parse_number("0,99"), Some(99));
+        assert_eq!(parse_number("150"), Some(15000));
+        assert_eq!(parse_number("1,2,3"), None);
+        assert_eq!(parse_number("12.345,678"), None);
+        assert_eq!(parse_number("abc"), None);
+    }
+
+    #[test]
+    fn test_format_cents() {
+        assert_eq!(format_cents(123456), "1234.56");
+        assert_eq!(format_cents(5), "0.05");
+        assert_eq!(format_cents(-1050), "-10.50");
+    }
+
+    #[test]
+    fn test_find_amounts_with_roles_and_currencies() {
+        let text = "Subtotal: R$ 1.300,00\nDesconto: 65,44\nICMS R$ 12,00\n\
+                    Valor total: R$ 1.234,56\nParcela 1/3 de R$ 411,52\nFrete internacional US$ 1,234.50\n\
+                    Comissão 15,00\nEmitido em 10.10.2025, alíquota 5,00%";
+        let amounts = find_amounts(text);
+        let summary: Vec<(&str, &str, i64)> = amounts
+            .iter()
+            .map(|a| (a.role.as_str(), a.currency.as_str(), a.amount_cents))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("subtotal", "BRL", 130000),
+                ("desconto", "BRL", 6544),
+                ("imposto", "BRL", 1200),
+                ("total", "BRL", 123456),
+                ("parcela", "BRL", 41152),
+                ("frete", "USD", 123450),
+            ]
+        );
+        assert_eq!(amounts[3].raw, "R$ 1.234,56");
+        assert_eq!(amounts[3].amount(), "1234.56");
+    }
+
+    #[test]
+    fn test_currency_after_number_and_unlabelled() {
+        let amounts = find_amounts("Pagamento de 99.90 EUR recebido. Total pago: 150 reais. Pedido 12345.");
+        assert_eq!(amounts.len(), 2);
+        assert_eq!((amounts[0].role.as_str(), amounts[0].currency.as_str(), amounts[0].amount_cents), ("valor", "EUR", 9990));
+        assert_eq!((amounts[1].role.as_str(), amounts[1].currency.as_str(), amounts[1].amount_cents), ("total", "BRL", 15000));
+    }
+}
@@ -0,0 +1,224 @@
+// DETECÇÃO DE QUASE-DUPLICATAS POR SHINGLING
+// Compara o texto extraído dos documentos usando conjuntos de k palavras consecutivas
+// (shingles) e similaridade de Jaccard. Serve para achar o mesmo documento escaneado
+// duas vezes, em que o arquivo (e o hash) é diferente mas o texto é quase igual.
+// Cada documento guarda uma assinatura MinHash dos seus shingles, que estima o Jaccard
+// sem recarregar e reprocessar o texto dos demais documentos a cada importação. Pares
+// candidatos saem das assinaturas (LSH por faixas) e são confirmados pelo Jaccard exato.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Palavras por shingle
+pub const SHINGLE_SIZE: usize = 5;
+/// Similaridade mínima para considerar dois textos quase-duplicatas
+pub const NEAR_DUPLICATE_THRESHOLD: f64 = 0.8;
+/// Textos com menos palavras que isso não são comparados (similaridade pouco confiável)
+const MIN_WORDS: usize = 10;
+/// Funções de hash da assinatura MinHash (erro padrão da estimativa ≈ 1/√128 ≈ 0,09)
+pub const SIGNATURE_SIZE: usize = 128;
+/// Faixas do LSH: assinaturas iguais em ao menos uma faixa de LSH_ROWS posições viram candidatas.
+/// Com 32 x 4, pares com Jaccard 0,8 quase sempre colidem e pares abaixo de ~0,4 raramente
+const LSH_BANDS: usize = 32;
+const LSH_ROWS: usize = SIGNATURE_SIZE / LSH_BANDS;
+
+/// Conjunto de shingles de um texto (vazio se o texto for curto demais)
+pub fn shingles(text: &str) -> HashSet<u64> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    if words.len() < MIN_WORDS {
+        return HashSet::new();
+    }
+
+    words
+        .windows(SHINGLE_SIZE)
+        .map(shingle_hash)
+        .collect()
+}
+
+// FNV-1a das palavras do shingle: estável entre versões do Rust, pois as assinaturas são gravadas
+fn shingle_hash(words: &[String]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for word in words {
+        for byte in word.bytes().chain(std::iter::once(0x1f)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+// Mistura do SplitMix64, usada para derivar as funções de hash da assinatura
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Assinatura MinHash de um conjunto de shingles (vazia se o conjunto for vazio)
+pub fn signature(shingles: &HashSet<u64>) -> Vec<u64> {
+    if shingles.is_empty() {
+        return Vec::new();
+    }
+    (0..SIGNATURE_SIZE as u64)
+        .map(|i| {
+            let seed = mix(i);
+            shingles.iter().map(|s| mix(s ^ seed)).min().unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+/// Jaccard estimado pela fração de posições iguais de duas assinaturas (0.0 a 1.0)
+pub fn estimated_similarity(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).filter(|(x, y)| x == y).count() as f64 / a.len() as f64
+}
+
+/// Similaridade de Jaccard entre dois conjuntos de shingles (0.0 a 1.0)
+pub fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    let intersection = small.iter().filter(|s| large.contains(s)).count();
+    let union = a.len() + b.len() - intersection;
+
+    intersection as f64 / union as f64
+}
+
+/// Pares (i < j) de assinaturas que coincidem em alguma faixa do LSH e cuja similaridade
+/// estimada atinge o limiar, sem comparar todas as assinaturas entre si
+pub fn candidate_pairs(signatures: &[Vec<u64>], threshold: f64) -> Vec<(usize, usize)> {
+    let mut pairs = BTreeSet::new();
+    for band in 0..LSH_BANDS {
+        let mut buckets: HashMap<&[u64], Vec<usize>> = HashMap::new();
+        for (i, signature) in signatures.iter().enumerate() {
+            if signature.len() == SIGNATURE_SIZE {
+                buckets.entry(&signature[band * LSH_ROWS..(band + 1) * LSH_ROWS]).or_default().push(i);
+            }
+        }
+        for bucket in buckets.values().filter(|b| b.len() > 1) {
+            for (a, &i) in bucket.iter().enumerate() {
+                for &j in &bucket[a + 1..] {
+                    pairs.insert((i, j));
+                }
+            }
+        }
+    }
+
+    pairs.into_iter()
+        .filter(|&(i, j)| estimated_similarity(&signatures[i], &signatures[j]) >= threshold)
+        .collect()
+}
+
+/// Agrupa os itens ligados (direta ou transitivamente) pelos pares informados, entre `len` itens.
+/// Retorna apenas grupos com mais de um item, como índices
+pub fn cluster_pairs(len: usize, pairs: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..len).collect();
+
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        let mut node = i;
+        while parent[node] != root {
+            let next = parent[node];
+            parent[node] = root;
+            node = next;
+        }
+        root
+    }
+
+    for &(i, j) in pairs {
+        let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+        if ri != rj {
+            parent[rj] = ri;
+        }
+    }
+
+    let mut groups: std::collections::BTreeMap<usize, Vec<usize>> = std::collections::BTreeMap::new();
+    for i in 0..len {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    groups.into_values().filter(|g| g.len() > 1).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIBO: &str = "Recibo de pagamento referente ao aluguel do imóvel situado na Rua das Flores número 123 \
+                          no valor de mil e duzentos reais pago em dinheiro ao locador João da Silva";
+
+    #[test]
+    fn test_identical_texts_are_fully_similar() {
+        let a = shingles(RECIBO);
+        assert!(!a.is_empty());
+        assert_eq!(jaccard(&a, &shingles(RECIBO)), 1.0);
+    }
+
+    #[test]
+    fn test_ocr_noise_is_still_near_duplicate() {
+        // Mesmo recibo com diferença de caixa/pontuação e uma palavra trocada no final
+        let rescanned = RECIBO.to_uppercase().replace("dinheiro", "espécie") + ".";
+        let similarity = jaccard(&shingles(RECIBO), &shingles(&rescanned));
+        assert!(similarity >= NEAR_DUPLICATE_THRESHOLD, "similaridade {}", similarity);
+    }
+
+    #[test]
+    fn test_different_texts_and_short_texts() {
+        let other = "Contrato de prestação de serviços de consultoria em tecnologia firmado entre as partes \
+                     com vigência de doze meses a contar da assinatura";
+        assert!(jaccard(&shingles(RECIBO), &shingles(other)) < 0.1);
+        assert!(shingles("Nota fiscal 123").is_empty());
+        assert_eq!(jaccard(&shingles("curto"), &shingles("curto")), 0.0);
+    }
+
+    #[test]
+    fn test_signature_estimates_jaccard() {
+        let other = "Contrato de prestação de serviços de consultoria em tecnologia firmado entre as partes \
+                     com vigência de doze meses a contar da assinatura";
+        let rescanned = RECIBO.to_uppercase().replace("dinheiro", "espécie") + ".";
+        let recibo = signature(&shingles(RECIBO));
+        assert_eq!(recibo.len(), SIGNATURE_SIZE);
+
+        assert_eq!(estimated_similarity(&recibo, &signature(&shingles(RECIBO))), 1.0);
+        assert!(estimated_similarity(&recibo, &signature(&shingles(other))) < 0.1);
+
+        let exact = jaccard(&shingles(RECIBO), &shingles(&rescanned));
+        let estimated = estimated_similarity(&recibo, &signature(&shingles(&rescanned)));
+        assert!((exact - estimated).abs() < 0.15, "exato {} estimado {}", exact, estimated);
+
+        assert!(signature(&shingles("curto")).is_empty());
+        assert_eq!(estimated_similarity(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_cluster_groups_transitively() {
+        let other = "Contrato de prestação de serviços de consultoria em tecnologia firmado entre as partes \
+                     com vigência de doze meses a contar da assinatura";
+        let items = vec![
+            shingles(RECIBO),
+            shingles(other),
+            shingles(&RECIBO.to_lowercase()),
+            shingles(""),
+        ];
+
+        let signatures: Vec<Vec<u64>> = items.iter().map(signature).collect();
+        let pairs = candidate_pairs(&signatures, NEAR_DUPLICATE_THRESHOLD);
+        assert_eq!(pairs, vec![(0, 2)]);
+        assert!(jaccard(&items[0], &items[2]) >= NEAR_DUPLICATE_THRESHOLD);
+        assert_eq!(cluster_pairs(items.len(), &pairs), vec![vec![0, 2]]);
+        assert_eq!(cluster_pairs(4, &[(0, 2), (2, 3)]), vec![vec![0, 2, 3]]);
+    }
+}
@@ -0,0 +1,367 @@
+// PARSER DE NOTA FISCAL ELETRÔNICA (NF-e modelo 55 / NFC-e modelo 65)
+// Lê o XML autorizado (nfeProc ou NFe) e devolve os dados estruturados da nota,
+// os campos para `extracted_fields` e um texto no formato do DANFE para a busca.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NfeParty {
+    // CNPJ ou CPF, só dígitos
+    pub document: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NfeItem {
+    pub number: u32,
+    pub code: Option<String>,
+    pub description: String,
+    pub ncm: Option<String>,
+    pub cfop: Option<String>,
+    pub unit: Option<String>,
+    pub quantity: Option<String>,
+    pub unit_value: Option<String>,
+    pub total_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NfeDocument {
+    pub access_key: Option<String>,
+    pub model: Option<String>,
+    pub number: Option<String>,
+    pub series: Option<String>,
+    pub issue_date: Option<NaiveDate>,
+    pub nature_of_operation: Option<String>,
+    pub issuer: NfeParty,
+    pub recipient: NfeParty,
+    pub items: Vec<NfeItem>,
+    pub icms_base: Option<String>,
+    pub icms_value: Option<String>,
+    pub products_value: Option<String>,
+    pub total_value: Option<String>,
+    pub protocol: Option<String>,
+}
+
+/// O conteúdo parece um XML de NF-e/NFC-e?
+pub fn is_nfe_xml(content: &str) -> bool {
+    let head: String = content.chars().take(4096).collect();
+    head.contains("<infNFe") || head.contains(":infNFe") || head.contains("<nfeProc") || head.contains("<NFe")
+}
+
+pub fn parse_nfe(xml: &str) -> Option<NfeDocument> {
+    let inf_nfe = element(xml, "infNFe")?;
+    let ide = element(inf_nfe, "ide").unwrap_or("");
+    let emit = element(inf_nfe, "emit").unwrap_or("");
+    let dest = element(inf_nfe, "dest").unwrap_or("");
+    let icms_tot = element(inf_nfe, "ICMSTot").unwrap_or("");
+
+    // Chave de acesso: atributo Id="NFe<44 dígitos>" ou <chNFe> do protocolo
+    let access_key = Regex::new(r#"Id="NFe(\d{44})""#).ok()
+        .and_then(|re| re.captures(xml).map(|c| c[1].to_string()))
+        .or_else(|| text(xml, "chNFe").filter(|k| k.len() == 44 && k.chars().all(|c| c.is_ascii_digit())));
+
+    // dhEmi (versões 3.10/4.00, com fuso) ou dEmi (versão 2.00, só data)
+    let issue_date = text(ide, "dhEmi")
+        .and_then(|dh| {
+            DateTime::parse_from_rfc3339(&dh).ok()
+                .map(|dt| dt.date_naive())
+                .or_else(|| NaiveDate::parse_from_str(dh.get(..10)?, "%Y-%m-%d").ok())
+        })
+        .or_else(|| text(ide, "dEmi").and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok()));
+
+    let items = elements(inf_nfe, "det")
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, det)| {
+            let prod = element(det, "prod")?;
+            Some(NfeItem {
+                number: index as u32 + 1,
+                code: text(prod, "cProd"),
+                description: text(prod, "xProd").unwrap_or_default(),
+                ncm: text(prod, "NCM"),
+                cfop: text(prod, "CFOP"),
+                unit: text(prod, "uCom"),
+                quantity: text(prod, "qCom"),
+                unit_value: text(prod, "vUnCom"),
+                total_value: text(prod, "vProd"),
+            })
+        })
+        .collect();
+
+    Some(NfeDocument {
+        access_key,
+        model: text(ide, "mod"),
+        number: text(ide, "nNF"),
+        series: text(ide, "serie"),
+        issue_date,
+        nature_of_operation: text(ide, "natOp"),
+        issuer: party(emit),
+        recipient: party(dest),
+        items,
+        icms_base: text(icms_tot, "vBC"),
+        icms_value: text(icms_tot, "vICMS"),
+        products_value: text(icms_tot, "vProd"),
+        total_value: text(icms_tot, "vNF"),
+        protocol: element(xml, "infProt").and_then(|p| text(p, "nProt")),
+    })
+}
+
+fn party(block: &str) -> NfeParty {
+    NfeParty {
+        document: text(block, "CNPJ").or_else(|| text(block, "CPF")),
+        name: text(block, "xNome"),
+    }
+}
+
+impl NfeDocument {
+    pub fn kind(&self) -> &'static str {
+        match self.model.as_deref() {
+            Some("65") => "NFC-e",
+            _ => "NF-e",
+        }
+    }
+
+    /// CFOPs distintos, na ordem dos itens
+    pub fn cfops(&self) -> Vec<String> {
+        let mut cfops: Vec<String> = Vec::new();
+        for cfop in self.items.iter().filter_map(|item| item.cfop.clone()) {
+            if !cfops.contains(&cfop) {
+                cfops.push(cfop);
+            }
+        }
+        cfops
+    }
+
+    /// Campos para `extracted_fields` (valores no formato do XML: ponto decimal, só dígitos)
+    pub fn to_fields(&self) -> HashMap<String, String> {
+        let mut fields = HashMap::new();
+        let mut insert = |key: &str, value: &Option<String>| {
+            if let Some(value) = value.as_ref().filter(|v| !v.is_empty()) {
+                fields.insert(key.to_string(), value.clone());
+            }
+        };
+
+        insert("chave_acesso", &self.access_key);
+        insert("modelo", &self.model);
+        insert("numero_nf", &self.number);
+        insert("serie", &self.series);
+        insert("natureza_operacao", &self.nature_of_operation);
+        insert("emitente_nome", &self.issuer.name);
+        insert("destinatario_nome", &self.recipient.name);
+        insert("icms_base_calculo", &self.icms_base);
+        insert("icms_valor", &self.icms_value);
+        insert("valor_produtos", &self.products_value);
+        insert("valor_total", &self.total_value);
+        insert("protocolo", &self.protocol);
+
+        // CNPJ tem 14 dígitos, CPF 11
+        let document_key = |prefix: &str, document: &str| {
+            format!("{}_{}", prefix, if document.len() == 11 { "cpf" } else { "cnpj" })
+        };
+        if let Some(document) = &self.issuer.document {
+            fields.insert(document_key("emitente", document), document.clone());
+            fields.insert("cnpj".to_string(), document.clone());
+        }
+        if let Some(document) = &self.recipient.document {
+            fields.insert(document_key("destinatario", document), document.clone());
+        }
+
+        if let Some(date) = self.issue_date {
+            fields.insert("data_emissao".to_string(), date.format("%Y-%m-%d").to_string());
+            fields.insert("data".to_string(), date.format("%d/%m/%Y").to_string());
+        }
+
+        let cfops = self.cfops();
+        if !cfops.is_empty() {
+            fields.insert("cfop".to_string(), cfops.join(", "));
+        }
+
+        fields.insert("tipo_nota".to_string(), self.kind().to_string());
+        fields.insert("itens_count".to_string(), self.items.len().to_string());
+        if let Ok(items) = serde_json::to_string(&self.items) {
+            fields.insert("itens".to_string(), items);
+        }
+
+        fields
+    }
+
+    /// Texto no layout do DANFE, usado como texto extraído e indexado na busca
+    pub fn danfe_text(&self) -> String {
+        let mut lines = vec![format!("{} - NOTA FISCAL ELETRÔNICA", self.kind())];
+        let mut push = |label: &str, value: Option<&String>| {
+            if let Some(value) = value {
+                lines.push(format!("{}: {}", label, value));
+            }
+        };
+
+        push("Chave de acesso", self.access_key.as_ref());
+        push("Número", self.number.as_ref());
+        push("Série", self.series.as_ref());
+        push("Natureza da operação", self.nature_of_operation.as_ref());
+        push("Protocolo de autorização", self.protocol.as_ref());
+        if let Some(date) = self.issue_date {
+            lines.push(format!("Data de emissão: {}", date.format("%d/%m/%Y")));
+        }
+
+        lines.push(String::new());
+        lines.push("EMITENTE".to_string());
+        lines.extend(party_lines(&self.issuer));
+        lines.push(String::new());
+        lines.push("DESTINATÁRIO".to_string());
+        lines.extend(party_lines(&self.recipient));
+
+        if !self.items.is_empty() {
+            lines.push(String::new());
+            lines.push("PRODUTOS / SERVIÇOS".to_string());
+            for item in &self.items {
+                let mut parts = vec![format!("{}. {}", item.number, item.description)];
+                if let Some(code) = &item.code {
+                    parts.push(format!("Código {}", code));
+                }
+                if let Some(ncm) = &item.ncm {
+                    parts.push(format!("NCM {}", ncm));
+                }
+                if let Some(cfop) = &item.cfop {
+                    parts.push(format!("CFOP {}", cfop));
+                }
+                if let Some(quantity) = &item.quantity {
+                    parts.push(format!("Qtd {} {}", quantity, item.unit.as_deref().unwrap_or("")).trim_end().to_string());
+                }
+                if let Some(total) = &item.total_value {
+                    parts.push(format!("Valor R$ {}", total));
+                }
+                lines.push(parts.join(" | "));
+            }
+        }
+
+        lines.push(String::new());
+        lines.push("CÁLCULO DO IMPOSTO".to_string());
+        let mut push = |label: &str, value: Option<&String>| {
+            if let Some(value) = value {
+                lines.push(format!("{}: R$ {}", label, value));
+            }
+        };
+        push("Base de cálculo do ICMS", self.icms_base.as_ref());
+        push("Valor do ICMS", self.icms_value.as_ref());
+        push("Valor total dos produtos", self.products_value.as_ref());
+        push("Valor total da nota", self.total_value.as_ref());
+
+        lines.join("\n")
+    }
+}
+
+fn party_lines(party: &NfeParty) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(name) = &party.name {
+        lines.push(format!("Nome/Razão social: {}", name));
+    }
+    if let Some(document) = &party.document {
+        let label = if document.len() == 11 { "CPF" } else { "CNPJ" };
+        lines.push(format!("{}: {}", label, document));
+    }
+    lines
+}
+
+// ================================
+// LEITURA DE ELEMENTOS XML
+// ================================
+
+// O XML da NF-e tem leiaute fixo e sem elementos homônimos aninhados, então basta
+// localizar abertura e fechamento (com ou sem prefixo de namespace).
+fn element_regex(name: &str) -> Option<Regex> {
+    Regex::new(&format!(r"(?s)<(?:[\w-]+:)?{0}(?:\s[^>]*)?>(.*?)</(?:[\w-]+:)?{0}>", regex::escape(name))).ok()
+}
+
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    element_regex(name)?.captures(xml).and_then(|c| c.get(1)).map(|m| m.as_str())
+}
+
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    element_regex(name)
+        .map(|re| re.captures_iter(xml).filter_map(|c| c.get(1)).map(|m| m.as_str()).collect())
+        .unwrap_or_default()
+}
+
+fn text(xml: &str, name: &str) -> Option<String> {
+    element(xml, name)
+        .map(|value| crate::text_extractors::decode_entities(value.trim()))
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NFE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<nfeProc versao="4.00" xmlns="http://www.portalfiscal.inf.br/nfe">
+  <NFe><infNFe Id="NFe35240112345678000195550010000001231000001234" versao="4.00">
+    <ide><cUF>35</cUF><natOp>Venda de mercadoria</natOp><mod>55</mod><serie>1</serie><nNF>123</nNF>
+      <dhEmi>2024-01-15T23:30:00-03:00</dhEmi></ide>
+    <emit><CNPJ>12345678000195</CNPJ><xNome>Papelaria Exemplo &amp; Cia LTDA</xNome>
+      <enderEmit><xLgr>Rua A</xLgr></enderEmit></emit>
+    <dest><CPF>12345678909</CPF><xNome>Maria da Silva</xNome></dest>
+    <det nItem="1"><prod><cProd>001</cProd><xProd>Caderno universitário</xProd><NCM>48202000</NCM>
+      <CFOP>5102</CFOP><uCom>UN</uCom><qCom>2.0000</qCom><vUnCom>15.50</vUnCom><vProd>31.00</vProd></prod></det>
+    <det nItem="2"><prod><cProd>002</cProd><xProd>Caneta azul</xProd><NCM>96081000</NCM>
+      <CFOP>5405</CFOP><uCom>UN</uCom><qCom>10.0000</qCom><vUnCom>2.00</vUnCom><vProd>20.00</vProd></prod></det>
+    <total><ICMSTot><vBC>31.00</vBC><vICMS>5.58</vICMS><vProd>51.00</vProd><vNF>51.00</vNF></ICMSTot></total>
+  </infNFe></NFe>
+  <protNFe><infProt><chNFe>35240112345678000195550010000001231000001234</chNFe><nProt>135240000012345</nProt></infProt></protNFe>
+</nfeProc>"#;
+
+    #[test]
+    fn test_parse_nfe() {
+        assert!(is_nfe_xml(NFE_XML));
+        let nfe = parse_nfe(NFE_XML).unwrap();
+
+        assert_eq!(nfe.access_key.as_deref(), Some("35240112345678000195550010000001231000001234"));
+        assert_eq!(nfe.kind(), "NF-e");
+        assert_eq!(nfe.number.as_deref(), Some("123"));
+        // Data no fuso da emissão, não em UTC
+        assert_eq!(nfe.issue_date, NaiveDate::from_ymd_opt(2024, 1, 15));
+        assert_eq!(nfe.issuer.name.as_deref(), Some("Papelaria Exemplo & Cia LTDA"));
+        assert_eq!(nfe.recipient.document.as_deref(), Some("12345678909"));
+        assert_eq!(nfe.items.len(), 2);
+        assert_eq!(nfe.items[1].description, "Caneta azul");
+        assert_eq!(nfe.cfops(), vec!["5102", "5405"]);
+        assert_eq!(nfe.icms_value.as_deref(), Some("5.58"));
+        assert_eq!(nfe.total_value.as_deref(), Some("51.00"));
+        assert_eq!(nfe.protocol.as_deref(), Some("135240000012345"));
+    }
+
+    #[test]
+    fn test_nfe_fields_and_text() {
+        let nfe = parse_nfe(NFE_XML).unwrap();
+        let fields = nfe.to_fields();
+
+        assert_eq!(fields["emitente_cnpj"], "12345678000195");
+        assert_eq!(fields["destinatario_cpf"], "12345678909");
+        assert_eq!(fields["data_emissao"], "2024-01-15");
+        assert_eq!(fields["cfop"], "5102, 5405");
+        assert_eq!(fields["itens_count"], "2");
+
+        let text = nfe.danfe_text();
+        assert!(text.contains("Chave de acesso: 35240112345678000195550010000001231000001234"));
+        assert!(text.contains("2. Caneta azul | Código 002 | NCM 96081000 | CFOP 5405 | Qtd 10.0000 UN | Valor R$ 20.00"));
+        assert!(text.contains("Valor do ICMS: R$ 5.58"));
+    }
+
+    #[test]
+    fn test_legacy_nfce_without_protocol() {
+        let xml = r#"<NFe><infNFe versao="2.00" Id="NFe35100112345678000195650010000000011000000019">
+            <ide><mod>65</mod><dEmi>2010-01-20</dEmi></ide>
+            <emit><CNPJ>12345678000195</CNPJ></emit>
+            <total><ICMSTot><vNF>9.90</vNF></ICMSTot></total></infNFe></NFe>"#;
+        let nfe = parse_nfe(xml).unwrap();
+
+        assert_eq!(nfe.kind(), "NFC-e");
+        assert_eq!(nfe.issue_date, NaiveDate::from_ymd_opt(2010, 1, 20));
+        assert_eq!(nfe.recipient, NfeParty { document: None, name: None });
+        assert!(nfe.items.is_empty());
+        assert!(parse_nfe("<html><body>nota</body></html>").is_none());
+    }
+}
@@ -1,14 +1,18 @@
 // Simplified OCR system that actually works
 // This is a practical implementation focused on reliability over advanced features
 
+use std::io::Read;
 use std::path::Path;
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
-use regex::Regex;
-use tokio::process::Command;
+use chrono::NaiveDate;
 use lopdf;
 use calamine::{open_workbook_auto, Reader, Sheets, DataType};
 
+use crate::doc_classifier::TypeDecision;
+use crate::image_preprocess::{self, Geometry, PreprocessOptions, Preprocessed};
+use crate::{br_validators, email_parser, extraction_rules, nfe_parser, pdf_raster, tesseract_cli, text_extractors};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SimpleOCRResult {
     pub extracted_text: String,
@@ -18,6 +22,53 @@ pub struct SimpleOCRResult {
     pub processing_method: String,
     pub processing_time_ms: u128,
     pub error_message: Option<String>,
+    // Resultado por página (PDFs); vazio nos demais formatos
+    #[serde(default)]
+    pub pages: Vec<PageOCRResult>,
+    // Etapas de pré-processamento aplicadas antes do Tesseract (ver image_preprocess)
+    #[serde(default)]
+    pub preprocessing: Vec<String>,
+    // Palavras reconhecidas pelo OCR com posição na página, para destacar resultados de busca
+    #[serde(default)]
+    pub word_boxes: Vec<WordBox>,
+    // Como o tipo foi decidido (regras e, na ingestão, o classificador do usuário);
+    // None quando o tipo vem do formato do arquivo (e-mail, NF-e, erro...)
+    #[serde(default)]
+    pub classification: Option<TypeDecision>,
+}
+
+// Retângulo em pixels da página (page_width x page_height): a imagem original, ou nos
+// PDFs escaneados a página na resolução da sua primeira imagem
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WordBox {
+    pub page_number: u32,
+    pub text: String,
+    pub confidence: f32,
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+    pub page_width: u32,
+    pub page_height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageOCRResult {
+    pub page_number: u32,
+    pub method: String, // "text" (texto embarcado), "ocr" ou "empty"
+    pub confidence: f32,
+    pub characters: usize,
+}
+
+// Campo preenchido por extratores de formatos estruturados (NF-e, e-mail) com a data
+// do documento em YYYY-MM-DD; tem precedência sobre a heurística do DateExtractor
+pub const DOCUMENT_DATE_FIELD: &str = "document_date";
+
+impl SimpleOCRResult {
+    pub fn document_date(&self) -> Option<NaiveDate> {
+        self.extracted_fields.get(DOCUMENT_DATE_FIELD)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+    }
 }
 
 #[derive(Debug)]
@@ -45,12 +96,43 @@ impl From<std::io::Error> for SimpleOCRError {
     }
 }
 
-pub struct SimpleOCRProcessor;
+pub struct SimpleOCRProcessor {
+    preprocessing: PreprocessOptions,
+}
 
 impl SimpleOCRProcessor {
     pub fn new() -> Result<Self, SimpleOCRError> {
         log::info!("🔧 Inicializando Simple OCR Processor");
-        Ok(SimpleOCRProcessor)
+        Ok(SimpleOCRProcessor {
+            preprocessing: PreprocessOptions::default(),
+        })
+    }
+
+    // Etapas de pré-processamento das imagens enviadas ao Tesseract
+    pub fn with_preprocessing(mut self, options: PreprocessOptions) -> Self {
+        self.preprocessing = options;
+        self
+    }
+
+    // Escolher o extrator adequado pela extensão do arquivo
+    pub async fn process_file<P: AsRef<Path>>(&self, file_path: P) -> Result<SimpleOCRResult, SimpleOCRError> {
+        let file_path = file_path.as_ref();
+        let extension = file_path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_lowercase());
+
+        match extension.as_deref() {
+            Some("pdf") => self.process_pdf(file_path).await,
+            Some("xlsx") | Some("xls") | Some("xlsm") | Some("xlsb") | Some("ods") => self.process_excel(file_path),
+            Some("png") | Some("jpg") | Some("jpeg") | Some("tiff") | Some("bmp") => self.process_image(file_path).await,
+            Some("docx") | Some("odt") | Some("rtf") | Some("txt") | Some("md") | Some("markdown")
+            | Some("csv") | Some("html") | Some("htm") => self.process_text_document(file_path),
+            Some("xml") => self.process_nfe_xml(file_path),
+            Some("eml") => self.process_email(file_path),
+            _ => Err(SimpleOCRError::ProcessingError(
+                "Tipo de arquivo não suportado. Use PDF, Excel (.xlsx/.xls), Word (.docx), ODT, RTF, TXT, MD, CSV, HTML, NF-e (.xml), E-mail (.eml), PNG, JPG, JPEG, TIFF ou BMP.".to_string()
+            )),
+        }
     }
 
     // Processar imagem usando tesseract via comando do sistema (mais confiável)
@@ -70,39 +152,72 @@ impl SimpleOCRProcessor {
                 processing_method: "tesseract_unavailable".to_string(),
                 processing_time_ms: start_time.elapsed().as_millis(),
                 error_message: Some("Tesseract OCR não disponível no sistema".to_string()),
+                pages: vec![],
+                preprocessing: vec![],
+                word_boxes: vec![],
+                classification: None,
             });
         }
 
-        // Executar tesseract via comando do sistema
-        let output = Command::new("tesseract")
-            .arg(image_path.to_str().unwrap())
-            .arg("stdout")
-            .arg("-l")
-            .arg("por+eng")
-            .output()
-            .await?;
-
-        let text = if output.status.success() {
-            String::from_utf8_lossy(&output.stdout).trim().to_string()
-        } else {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            log::warn!("⚠️ Tesseract error: {}", error_msg);
-            
-            return Ok(SimpleOCRResult {
-                extracted_text: String::new(),
-                document_type: "unknown".to_string(),
-                extracted_fields: HashMap::new(),
-                confidence_score: 0.0,
-                processing_method: "tesseract_failed".to_string(),
-                processing_time_ms: start_time.elapsed().as_millis(),
-                error_message: Some(format!("Tesseract failed: {}", error_msg)),
-            });
+        // Pré-processar (foto de celular torta, sombra...) e gravar a versão tratada
+        let work_dir = tempfile::tempdir()?;
+        let mut preprocessing = Vec::new();
+        let mut geometry = None;
+        let mut ocr_path = image_path.to_path_buf();
+        if !self.preprocessing.is_none() {
+            let bytes = std::fs::read(image_path)?;
+            match image::load_from_memory(&bytes) {
+                Ok(image) => {
+                    let orientation = image_preprocess::exif_orientation(&bytes);
+                    let prepared = self.preprocess(image, orientation).await;
+                    let prepared_path = work_dir.path().join("preprocessed.png");
+                    match prepared.image.save(&prepared_path) {
+                        Ok(()) => {
+                            ocr_path = prepared_path;
+                            preprocessing = prepared.steps;
+                            geometry = Some(prepared.geometry);
+                        }
+                        Err(e) => log::warn!("⚠️ Erro ao gravar imagem pré-processada: {:?}", e),
+                    }
+                }
+                Err(e) => log::warn!("⚠️ Imagem não decodificada, OCR sem pré-processamento: {:?}", e),
+            }
+        }
+
+        // Executar tesseract via comando do sistema (saída TSV: texto + caixas + confiança)
+        let recognized = match tesseract_cli::recognize(&ocr_path).await {
+            Ok(recognized) => recognized,
+            Err(error_msg) => {
+                log::warn!("⚠️ Tesseract error: {}", error_msg);
+                
+                return Ok(SimpleOCRResult {
+                    extracted_text: String::new(),
+                    document_type: "unknown".to_string(),
+                    extracted_fields: HashMap::new(),
+                    confidence_score: 0.0,
+                    processing_method: "tesseract_failed".to_string(),
+                    processing_time_ms: start_time.elapsed().as_millis(),
+                    error_message: Some(error_msg),
+                    pages: vec![],
+                    preprocessing,
+                    word_boxes: vec![],
+                    classification: None,
+                });
+            }
         };
 
-        // Analisar texto extraído usando heurísticas
-        let document_type = self.classify_document_type(&text);
-        let extracted_fields = self.extract_fields(&text);
-        let confidence_score = self.calculate_confidence(&text, &extracted_fields);
+        let geometry = geometry.unwrap_or_else(|| {
+            let (width, height) = image::image_dimensions(image_path).unwrap_or((0, 0));
+            Geometry::identity(width, height)
+        });
+        let word_boxes = to_word_boxes(1, &recognized.words, &geometry, None);
+        let text = recognized.text.trim().to_string();
+
+        // Analisar texto extraído usando heurísticas; confiança vem das palavras do OCR
+        let classification = self.classify_document_type(&text);
+        let document_type = classification.document_type.clone();
+        let extracted_fields = self.extract_fields(&document_type, &text);
+        let confidence_score = recognized.confidence.unwrap_or(0.0);
 
         log::info!("✅ OCR concluído: {} caracteres extraídos", text.len());
 
@@ -114,6 +229,10 @@ impl SimpleOCRProcessor {
             processing_method: "tesseract_system".to_string(),
             processing_time_ms: start_time.elapsed().as_millis(),
             error_message: None,
+            pages: vec![],
+            preprocessing,
+            word_boxes,
+            classification: Some(classification),
         })
     }
 
@@ -138,61 +257,143 @@ impl SimpleOCRProcessor {
                         processing_method: "pdf_encrypted".to_string(),
                         processing_time_ms: start_time.elapsed().as_millis(),
                         error_message: Some("PDF protegido por senha. Desproteja o arquivo antes de processar.".to_string()),
+                        pages: vec![],
+                        preprocessing: vec![],
+                        word_boxes: vec![],
+                        classification: None,
                     });
                 }
 
-                // Extrair texto de todas as páginas
+                // Página a página: texto embarcado quando existe, Tesseract nas páginas escaneadas
                 let pages = doc.get_pages();
-                let page_nums: Vec<u32> = pages.keys().cloned().collect();
-                
-                log::debug!("📖 PDF tem {} página(s)", page_nums.len());
-
-                match doc.extract_text(&page_nums) {
-                    Ok(text) => {
-                        let trimmed_text = text.trim();
-                        
-                        if !trimmed_text.is_empty() {
-                            // Sucesso! PDF tem texto embarcado
-                            log::info!("✅ Texto extraído com sucesso: {} caracteres", trimmed_text.len());
-                            
-                            let document_type = self.classify_document_type(trimmed_text);
-                            let extracted_fields = self.extract_fields(trimmed_text);
-                            let confidence_score = self.calculate_confidence(trimmed_text, &extracted_fields);
-
-                            return Ok(SimpleOCRResult {
-                                extracted_text: trimmed_text.to_string(),
-                                document_type,
-                                extracted_fields,
-                                confidence_score,
-                                processing_method: "pdf_text_lopdf".to_string(),
-                                processing_time_ms: start_time.elapsed().as_millis(),
-                                error_message: None,
-                            });
-                        } else {
-                            // PDF sem texto = provavelmente escaneado
-                            log::warn!("⚠️ PDF sem texto embarcado - provavelmente escaneado");
+                log::debug!("📖 PDF tem {} página(s)", pages.len());
+
+                let tesseract_available = tesseract_cli::is_available().await;
+                let work_dir = tempfile::tempdir()?;
+                let mut page_texts: Vec<(u32, String)> = Vec::new();
+                let mut page_results: Vec<PageOCRResult> = Vec::new();
+                let mut pages_without_ocr: Vec<u32> = Vec::new();
+                let mut preprocessing_steps: Vec<String> = Vec::new();
+                let mut skipped_images: Vec<String> = Vec::new();
+                let mut word_boxes: Vec<WordBox> = Vec::new();
+
+                for (&page_number, &page_id) in &pages {
+                    let embedded = doc.extract_text(&[page_number]).unwrap_or_default();
+                    let embedded = embedded.trim();
+
+                    let (text, method, confidence) = if has_embedded_text(embedded) {
+                        (embedded.to_string(), "text", 1.0)
+                    } else if tesseract_available {
+                        match self.ocr_pdf_page(&doc, page_id, page_number, work_dir.path(), &mut preprocessing_steps, &mut skipped_images).await {
+                            Some((text, confidence, boxes)) => {
+                                word_boxes.extend(boxes);
+                                (text, "ocr", confidence)
+                            }
+                            None => (String::new(), "empty", 0.0),
                         }
-                    }
-                    Err(e) => {
-                        log::warn!("⚠️ Erro ao extrair texto: {:?}", e);
+                    } else {
+                        pages_without_ocr.push(page_number);
+                        (String::new(), "empty", 0.0)
+                    };
+
+                    page_results.push(PageOCRResult {
+                        page_number,
+                        method: method.to_string(),
+                        confidence,
+                        characters: text.chars().count(),
+                    });
+                    if !text.is_empty() {
+                        page_texts.push((page_number, text));
                     }
                 }
 
-                // PDF parece ser escaneado - orientar usuário
-                Ok(SimpleOCRResult {
-                    extracted_text: String::new(),
-                    document_type: "scanned_pdf".to_string(),
-                    extracted_fields: HashMap::new(),
-                    confidence_score: 0.0,
-                    processing_method: "pdf_scanned_needs_ocr".to_string(),
-                    processing_time_ms: start_time.elapsed().as_millis(),
-                    error_message: Some(
+                let ocr_pages = page_results.iter().filter(|p| p.method == "ocr").count();
+                let text_pages = page_results.iter().filter(|p| p.method == "text").count();
+
+                if page_texts.is_empty() {
+                    log::warn!("⚠️ PDF sem texto embarcado e sem texto reconhecido");
+                    let error_message = if tesseract_available {
+                        let mut message = "PDF escaneado sem texto reconhecível: nenhuma página pôde ser lida pelo OCR.".to_string();
+                        if !skipped_images.is_empty() {
+                            message.push_str(&format!("\n\nImagens ignoradas: {}", skipped_images.join("; ")));
+                        }
+                        message
+                    } else {
                         "PDF escaneado detectado (sem texto embarcado).\n\n\
                         Para processar este tipo de arquivo, instale o Tesseract OCR:\n\
                         🔗 Download: https://github.com/UB-Mannheim/tesseract/wiki\n\n\
                         Após instalação, o sistema processará automaticamente PDFs escaneados."
                             .to_string()
-                    ),
+                    };
+                    return Ok(SimpleOCRResult {
+                        extracted_text: String::new(),
+                        document_type: "scanned_pdf".to_string(),
+                        extracted_fields: HashMap::new(),
+                        confidence_score: 0.0,
+                        processing_method: "pdf_scanned_needs_ocr".to_string(),
+                        processing_time_ms: start_time.elapsed().as_millis(),
+                        error_message: Some(error_message),
+                        pages: page_results,
+                        preprocessing: preprocessing_steps,
+                        word_boxes,
+                        classification: None,
+                    });
+                }
+
+                // Com OCR envolvido, marcar o início de cada página no texto mesclado
+                let text = if ocr_pages > 0 && pages.len() > 1 {
+                    page_texts.iter()
+                        .map(|(number, text)| format!("--- Página {} ---\n{}", number, text))
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                } else {
+                    page_texts.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>().join("\n")
+                };
+
+                let processing_method = match (text_pages, ocr_pages) {
+                    (_, 0) => "pdf_text_lopdf",
+                    (0, _) => "pdf_ocr_tesseract",
+                    _ => "pdf_hybrid",
+                };
+
+                let classification = self.classify_document_type(&text);
+                let document_type = classification.document_type.clone();
+                let extracted_fields = self.extract_fields(&document_type, &text);
+                let confidence_score = if ocr_pages > 0 {
+                    weighted_page_confidence(&page_results)
+                } else {
+                    self.calculate_confidence(&text, &extracted_fields)
+                };
+
+                let mut warnings = Vec::new();
+                if !pages_without_ocr.is_empty() {
+                    warnings.push(format!(
+                        "Páginas escaneadas não processadas (Tesseract OCR não encontrado): {}",
+                        pages_without_ocr.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ")
+                    ));
+                }
+                if !skipped_images.is_empty() {
+                    warnings.push(format!("Imagens ignoradas: {}", skipped_images.join("; ")));
+                }
+                let error_message = (!warnings.is_empty()).then(|| warnings.join("\n"));
+
+                log::info!(
+                    "✅ PDF processado ({}): {} páginas com texto, {} com OCR, {} caracteres",
+                    processing_method, text_pages, ocr_pages, text.len()
+                );
+
+                Ok(SimpleOCRResult {
+                    extracted_text: text,
+                    document_type,
+                    extracted_fields,
+                    confidence_score,
+                    processing_method: processing_method.to_string(),
+                    processing_time_ms: start_time.elapsed().as_millis(),
+                    error_message,
+                    pages: page_results,
+                    preprocessing: preprocessing_steps,
+                    word_boxes,
+                    classification: Some(classification),
                 })
             }
             Err(e) => {
@@ -206,80 +407,99 @@ impl SimpleOCRProcessor {
                     processing_method: "pdf_load_failed".to_string(),
                     processing_time_ms: start_time.elapsed().as_millis(),
                     error_message: Some(format!("Erro ao ler PDF: {}. Arquivo pode estar corrompido.", e)),
+                    pages: vec![],
+                    preprocessing: vec![],
+                    word_boxes: vec![],
+                    classification: None,
                 })
             }
         }
     }
 
+    // Pré-processamento é CPU pesado: rodar fora do runtime assíncrono
+    async fn preprocess(&self, image: image::DynamicImage, orientation: Option<u16>) -> Preprocessed {
+        let options = self.preprocessing.clone();
+        let fallback = image.clone();
+        tokio::task::spawn_blocking(move || image_preprocess::preprocess(image, orientation, &options))
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("⚠️ Pré-processamento interrompido: {:?}", e);
+                let geometry = Geometry::identity(fallback.width(), fallback.height());
+                Preprocessed { image: fallback, steps: vec![], geometry }
+            })
+    }
+
     // Verificar se tesseract está disponível
     async fn is_tesseract_available(&self) -> bool {
-        match Command::new("tesseract").arg("--version").output().await {
-            Ok(output) => output.status.success(),
-            Err(_) => false,
-        }
+        tesseract_cli::is_available().await
     }
 
-    // HEURÍSTICA: Classificar tipo de documento
-    fn classify_document_type(&self, text: &str) -> String {
-        let text_lower = text.to_lowercase();
-        
-        if text_lower.contains("nota fiscal") || text_lower.contains("nf-e") || text_lower.contains("icms") {
-            "nota_fiscal".to_string()
-        } else if text_lower.contains("contrato") || text_lower.contains("clausula") || text_lower.contains("cláusula") {
-            "contrato".to_string()
-        } else if text_lower.contains("recibo") || text_lower.contains("comprovante") || text_lower.contains("pagamento") {
-            "recibo".to_string()
-        } else if text_lower.contains("funcionário") || text_lower.contains("salário") || text_lower.contains("admissão") {
-            "documento_rh".to_string()
-        } else if text_lower.contains("processo") || text_lower.contains("tribunal") || text_lower.contains("advogado") {
-            "documento_juridico".to_string()
-        } else if text_lower.contains("relatório") || text_lower.contains("análise") || text_lower.contains("relatorio") {
-            "relatorio".to_string()
-        } else {
-            "documento_generico".to_string()
+    // OCR de uma página escaneada a partir das imagens que ela contém.
+    // Retorna o texto, a confiança média e as caixas das palavras, ou None se nada foi reconhecido
+    async fn ocr_pdf_page(
+        &self,
+        doc: &lopdf::Document,
+        page_id: lopdf::ObjectId,
+        page_number: u32,
+        work_dir: &Path,
+        preprocessing_steps: &mut Vec<String>,
+        skipped_images: &mut Vec<String>,
+    ) -> Option<(String, f32, Vec<WordBox>)> {
+        let page_images = pdf_raster::page_images(doc, page_id);
+        skipped_images.extend(page_images.skipped.into_iter().map(|reason| format!("página {}: {}", page_number, reason)));
+        let images = page_images.images;
+        if images.is_empty() {
+            log::warn!("⚠️ Página {} sem texto e sem imagem legível", page_number);
+            return None;
         }
-    }
 
-    // HEURÍSTICA: Extrair campos principais
-    fn extract_fields(&self, text: &str) -> HashMap<String, String> {
-        let mut fields = HashMap::new();
+        let mut texts = Vec::new();
+        let mut boxes: Vec<WordBox> = Vec::new();
 
-        // CNPJ
-        if let Ok(cnpj_regex) = Regex::new(r"(?:CNPJ[:\s]*)?(\d{2}\.?\d{3}\.?\d{3}/?\d{4}-?\d{2})") {
-            if let Some(captures) = cnpj_regex.captures(text) {
-                if let Some(cnpj) = captures.get(1) {
-                    fields.insert("cnpj".to_string(), cnpj.as_str().to_string());
+        for (index, page_image) in images.into_iter().enumerate() {
+            let prepared = self.preprocess(page_image.image, None).await;
+            for step in prepared.steps {
+                if !preprocessing_steps.contains(&step) {
+                    preprocessing_steps.push(step);
                 }
             }
-        }
 
-        // CPF
-        if let Ok(cpf_regex) = Regex::new(r"(?:CPF[:\s]*)?(\d{3}\.?\d{3}\.?\d{3}-?\d{2})") {
-            if let Some(captures) = cpf_regex.captures(text) {
-                if let Some(cpf) = captures.get(1) {
-                    fields.insert("cpf".to_string(), cpf.as_str().to_string());
-                }
+            let image_path = work_dir.join(format!("pagina-{}-{}.png", page_number, index + 1));
+            if let Err(e) = prepared.image.save(&image_path) {
+                log::warn!("⚠️ Erro ao gravar imagem da página {}: {:?}", page_number, e);
+                continue;
             }
-        }
 
-        // Valores monetários
-        if let Ok(valor_regex) = Regex::new(r"(?:R\$|total|valor)[:\s]*([0-9,.]+)") {
-            if let Some(captures) = valor_regex.captures(text) {
-                if let Some(valor) = captures.get(1) {
-                    fields.insert("valor_total".to_string(), valor.as_str().to_string());
+            match tesseract_cli::recognize(&image_path).await {
+                Ok(recognized) if !recognized.text.trim().is_empty() => {
+                    boxes.extend(to_word_boxes(page_number, &recognized.words, &prepared.geometry, Some(&page_image.placement)));
+                    texts.push(recognized.text);
                 }
+                Ok(_) => {}
+                Err(e) => log::warn!("⚠️ OCR da página {} falhou: {}", page_number, e),
             }
         }
 
-        // Datas
-        if let Ok(data_regex) = Regex::new(r"(\d{2}/\d{2}/\d{4})") {
-            if let Some(captures) = data_regex.captures(text) {
-                if let Some(data) = captures.get(1) {
-                    fields.insert("data".to_string(), data.as_str().to_string());
-                }
-            }
+        if texts.is_empty() {
+            return None;
         }
 
+        let confidence = mean_word_confidence(&boxes);
+        log::debug!("🔍 Página {} reconhecida (confiança {:.2})", page_number, confidence);
+        Some((texts.join("\n\n"), confidence, boxes))
+    }
+
+    // Tipo de documento pelas regras configuráveis (extraction_rules.json)
+    fn classify_document_type(&self, text: &str) -> TypeDecision {
+        TypeDecision::from_rules(&extraction_rules::current().classify(text))
+    }
+
+    // Campos pelas regras configuráveis; CPF/CNPJ com papel, boleto e PIX pelos validadores
+    fn extract_fields(&self, document_type: &str, text: &str) -> HashMap<String, String> {
+        let mut fields = extraction_rules::current().extract_fields(document_type, text);
+        for (key, value) in br_validators::document_fields(text, chrono::Local::now().date_naive()) {
+            fields.entry(key).or_insert(value);
+        }
         fields
     }
 
@@ -379,6 +599,10 @@ impl SimpleOCRProcessor {
                 processing_method: "excel_empty".to_string(),
                 processing_time_ms: start_time.elapsed().as_millis(),
                 error_message: Some("Planilha Excel vazia ou sem dados legíveis".to_string()),
+                pages: vec![],
+                preprocessing: vec![],
+                word_boxes: vec![],
+                classification: None,
             });
         }
 
@@ -390,8 +614,9 @@ impl SimpleOCRProcessor {
         extracted_values.insert("row_count".to_string(), row_count.to_string());
 
         // Classificar e extrair campos
-        let document_type = self.classify_document_type(trimmed_text);
-        let mut extracted_fields = self.extract_fields(trimmed_text);
+        let classification = self.classify_document_type(trimmed_text);
+        let document_type = classification.document_type.clone();
+        let mut extracted_fields = self.extract_fields(&document_type, trimmed_text);
         extracted_fields.extend(extracted_values);
 
         let confidence_score = self.calculate_confidence(trimmed_text, &extracted_fields);
@@ -404,20 +629,268 @@ impl SimpleOCRProcessor {
             processing_method: "excel_calamine".to_string(),
             processing_time_ms: start_time.elapsed().as_millis(),
             error_message: None,
+            pages: vec![],
+            preprocessing: vec![],
+            word_boxes: vec![],
+            classification: Some(classification),
+        })
+    }
+
+    // Processar documentos de texto (.docx, .odt, .rtf, .txt, .md, .csv, .html)
+    pub fn process_text_document<P: AsRef<Path>>(&self, file_path: P) -> Result<SimpleOCRResult, SimpleOCRError> {
+        let start_time = std::time::Instant::now();
+        let file_path = file_path.as_ref();
+        let extension = file_path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|s| s.to_lowercase())
+            .unwrap_or_default();
+
+        log::info!("📝 Processando documento de texto: {:?}", file_path);
+
+        let (text, method) = match extension.as_str() {
+            "docx" => (text_extractors::extract_docx(file_path), "docx_xml"),
+            "odt" => (text_extractors::extract_odt(file_path), "odt_xml"),
+            "rtf" => (read_text(file_path).map(|t| text_extractors::extract_rtf(&t)), "rtf_parser"),
+            "csv" => (read_text(file_path).map(|t| text_extractors::extract_csv(&t)), "csv_parser"),
+            "html" | "htm" => (read_text(file_path).map(|t| text_extractors::extract_html(&t)), "html_parser"),
+            "md" | "markdown" => (read_text(file_path).map(|t| t.trim().to_string()), "markdown_text"),
+            _ => (read_text(file_path).map(|t| t.trim().to_string()), "plain_text"),
+        };
+
+        let text = text.map_err(|e| match e.kind() {
+            std::io::ErrorKind::InvalidData => SimpleOCRError::ProcessingError(e.to_string()),
+            _ => SimpleOCRError::IOError(e),
+        })?;
+
+        if text.is_empty() {
+            log::warn!("⚠️ Documento de texto vazio: {:?}", file_path);
+            return Ok(SimpleOCRResult {
+                extracted_text: String::new(),
+                document_type: "documento_vazio".to_string(),
+                extracted_fields: HashMap::new(),
+                confidence_score: 0.0,
+                processing_method: format!("{}_empty", method),
+                processing_time_ms: start_time.elapsed().as_millis(),
+                error_message: Some("Documento vazio ou sem texto legível".to_string()),
+                pages: vec![],
+                preprocessing: vec![],
+                word_boxes: vec![],
+                classification: None,
+            });
+        }
+
+        log::info!("✅ Documento de texto processado ({}): {} caracteres", method, text.len());
+
+        let classification = self.classify_document_type(&text);
+        let document_type = classification.document_type.clone();
+        let extracted_fields = self.extract_fields(&document_type, &text);
+        let confidence_score = self.calculate_confidence(&text, &extracted_fields);
+
+        Ok(SimpleOCRResult {
+            extracted_text: text,
+            document_type,
+            extracted_fields,
+            confidence_score,
+            processing_method: method.to_string(),
+            processing_time_ms: start_time.elapsed().as_millis(),
+            error_message: None,
+            pages: vec![],
+            preprocessing: vec![],
+            word_boxes: vec![],
+            classification: Some(classification),
+        })
+    }
+
+    // Processar XML de NF-e/NFC-e: campos estruturados em vez de heurísticas
+    pub fn process_nfe_xml<P: AsRef<Path>>(&self, xml_path: P) -> Result<SimpleOCRResult, SimpleOCRError> {
+        let start_time = std::time::Instant::now();
+        let xml_path = xml_path.as_ref();
+
+        log::info!("🧾 Processando XML de NF-e: {:?}", xml_path);
+
+        let content = read_text(xml_path)?;
+        let nfe = nfe_parser::is_nfe_xml(&content)
+            .then(|| nfe_parser::parse_nfe(&content))
+            .flatten()
+            .ok_or_else(|| SimpleOCRError::ProcessingError(
+                "Arquivo XML não é uma NF-e/NFC-e reconhecida".to_string()
+            ))?;
+
+        let mut extracted_fields = nfe.to_fields();
+        if let Some(date) = nfe.issue_date {
+            extracted_fields.insert(DOCUMENT_DATE_FIELD.to_string(), date.format("%Y-%m-%d").to_string());
+        }
+
+        // Chave de acesso e totais vêm do XML assinado: confiança máxima quando presentes
+        let confidence_score = if nfe.access_key.is_some() && nfe.total_value.is_some() { 1.0 } else { 0.8 };
+
+        log::info!(
+            "✅ {} processada: chave {}, {} itens",
+            nfe.kind(),
+            nfe.access_key.as_deref().unwrap_or("não encontrada"),
+            nfe.items.len()
+        );
+
+        Ok(SimpleOCRResult {
+            extracted_text: nfe.danfe_text(),
+            document_type: "nota_fiscal".to_string(),
+            extracted_fields,
+            confidence_score,
+            processing_method: "nfe_xml".to_string(),
+            processing_time_ms: start_time.elapsed().as_millis(),
+            error_message: None,
+            pages: vec![],
+            preprocessing: vec![],
+            word_boxes: vec![],
+            classification: None,
+        })
+    }
+
+    // Processar e-mail (.eml): cabeçalhos e corpo; os anexos viram documentos próprios na ingestão
+    pub fn process_email<P: AsRef<Path>>(&self, email_path: P) -> Result<SimpleOCRResult, SimpleOCRError> {
+        let start_time = std::time::Instant::now();
+        let email_path = email_path.as_ref();
+
+        log::info!("📧 Processando e-mail: {:?}", email_path);
+
+        let raw = std::fs::read(email_path)?;
+        let email = email_parser::parse_email(&raw);
+        let text = email.to_text();
+
+        if text.is_empty() {
+            return Err(SimpleOCRError::ProcessingError("E-mail vazio ou em formato não reconhecido".to_string()));
+        }
+
+        let mut extracted_fields = self.extract_fields("email", &email.body_text);
+        extracted_fields.extend(email.to_fields());
+        if let Some(date) = email.date {
+            extracted_fields.insert(DOCUMENT_DATE_FIELD.to_string(), date.date_naive().format("%Y-%m-%d").to_string());
+        }
+
+        log::info!("✅ E-mail processado: {} caracteres, {} anexos", text.len(), email.attachments.len());
+
+        let confidence_score = self.calculate_confidence(&text, &extracted_fields);
+
+        Ok(SimpleOCRResult {
+            extracted_text: text,
+            document_type: "email".to_string(),
+            extracted_fields,
+            confidence_score,
+            processing_method: "email_mime".to_string(),
+            processing_time_ms: start_time.elapsed().as_millis(),
+            error_message: None,
+            pages: vec![],
+            preprocessing: vec![],
+            word_boxes: vec![],
+            classification: None,
         })
     }
 }
 
+// Página com texto embarcado de verdade (e não só um carimbo ou número de página)
+const MIN_PAGE_TEXT_CHARS: usize = 20;
+
+fn has_embedded_text(text: &str) -> bool {
+    text.chars().filter(|c| c.is_alphanumeric()).count() >= MIN_PAGE_TEXT_CHARS
+}
+
+// Palavras do Tesseract com o retângulo levado de volta à imagem original e, nos PDFs,
+// à posição da imagem na página
+fn to_word_boxes(
+    page_number: u32,
+    words: &[tesseract_cli::OcrWord],
+    geometry: &Geometry,
+    placement: Option<&pdf_raster::Placement>,
+) -> Vec<WordBox> {
+    let (page_width, page_height) = match placement {
+        Some(placement) => (placement.page_width(), placement.page_height()),
+        None => (geometry.original_width, geometry.original_height),
+    };
+    words.iter()
+        .map(|word| {
+            let (left, top, width, height) = geometry.to_original(word.left, word.top, word.width, word.height);
+            let (left, top, width, height) = match placement {
+                Some(placement) => placement.to_page(left, top, width, height),
+                None => (left, top, width, height),
+            };
+            WordBox {
+                page_number,
+                text: word.text.clone(),
+                confidence: word.confidence,
+                left,
+                top,
+                width,
+                height,
+                page_width,
+                page_height,
+            }
+        })
+        .collect()
+}
+
+fn mean_word_confidence(words: &[WordBox]) -> f32 {
+    if words.is_empty() {
+        return 0.0;
+    }
+    words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32
+}
+
+// Confiança do documento: média das páginas ponderada pela quantidade de texto
+fn weighted_page_confidence(pages: &[PageOCRResult]) -> f32 {
+    let total: usize = pages.iter().map(|p| p.characters).sum();
+    if total == 0 {
+        return 0.0;
+    }
+    pages.iter().map(|p| p.confidence * p.characters as f32).sum::<f32>() / total as f32
+}
+
+fn read_text(path: &Path) -> std::io::Result<String> {
+    std::fs::read(path).map(|bytes| text_extractors::decode_text(&bytes))
+}
+
 // Funções públicas para uso
 pub fn create_simple_ocr_processor() -> Result<SimpleOCRProcessor, SimpleOCRError> {
     SimpleOCRProcessor::new()
 }
 
+// Extensões aceitas por process_file
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "pdf", "xlsx", "xls", "xlsm", "xlsb", "ods", "png", "jpg", "jpeg", "tiff", "bmp",
+    "docx", "odt", "rtf", "txt", "md", "markdown", "csv", "html", "htm", "xml", "eml",
+];
+
+pub fn is_supported_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// XML só é processado quando é NF-e/NFC-e; os demais devem ser ignorados, não tratados como falha
+pub fn is_non_nfe_xml(path: &Path) -> bool {
+    let is_xml = path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"));
+    if !is_xml {
+        return false;
+    }
+
+    // O cabeçalho basta para is_nfe_xml
+    let mut head = Vec::new();
+    let read = std::fs::File::open(path).and_then(|file| file.take(4096).read_to_end(&mut head));
+    read.is_err() || !nfe_parser::is_nfe_xml(&text_extractors::decode_text(&head))
+}
+
 pub fn get_simple_supported_types() -> Vec<String> {
     vec![
         "Imagens (PNG, JPEG, TIFF) com Tesseract OCR".to_string(),
         "PDFs com texto extraível".to_string(),
+        "PDFs escaneados (OCR por página com Tesseract)".to_string(),
+        "Planilhas (XLSX, XLS, ODS, CSV)".to_string(),
+        "Documentos de texto (DOCX, ODT, RTF, TXT, MD, HTML)".to_string(),
         "Nota Fiscal".to_string(),
+        "NF-e / NFC-e (XML)".to_string(),
+        "E-mails (EML, MBOX) com anexos".to_string(),
         "Contrato".to_string(),
         "Recibo".to_string(),
         "Documento RH".to_string(),
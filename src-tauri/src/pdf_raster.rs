@@ -0,0 +1,488 @@
+// IMAGENS DAS PÁGINAS DE PDFs ESCANEADOS
+// Sem um renderizador de PDF embarcado, a página escaneada é obtida a partir das imagens
+// que ela contém (scanners gravam uma imagem por página): JPEG (DCTDecode), pixels
+// compactados (FlateDecode, com ou sem preditor PNG) ou sem compressão. Cores em cinza, RGB,
+// CMYK ou ICCBased (tratado pelo número de componentes /N do perfil).
+// A posição de cada imagem vem da matriz (cm) ativa quando o conteúdo da página a desenha (Do).
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use image::{DynamicImage, GrayImage, RgbImage};
+use lopdf::content::Content;
+use lopdf::{Document, Object, ObjectId};
+
+/// Imagens legíveis da página e o motivo de cada imagem ignorada (filtro ou cor não suportados)
+#[derive(Default)]
+pub struct PageImages {
+    pub images: Vec<PageImage>,
+    pub skipped: Vec<String>,
+}
+
+/// Imagem decodificada e onde ela é desenhada na página
+pub struct PageImage {
+    pub image: DynamicImage,
+    pub placement: Placement,
+}
+
+/// Posição de uma imagem na página, para levar retângulos em pixels da imagem
+/// ao espaço da página (pixels na resolução da primeira imagem da página)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    // Matriz (a b c d e f) do quadrado unitário da imagem para pontos da página
+    matrix: [f32; 6],
+    image_width: u32,
+    image_height: u32,
+    // MediaBox da página em pontos: x0, y0, x1, y1
+    page_box: [f32; 4],
+    // Pixels por ponto do espaço da página
+    scale: f32,
+}
+
+impl Placement {
+    pub fn page_width(&self) -> u32 {
+        ((self.page_box[2] - self.page_box[0]) * self.scale).round().max(1.0) as u32
+    }
+
+    pub fn page_height(&self) -> u32 {
+        ((self.page_box[3] - self.page_box[1]) * self.scale).round().max(1.0) as u32
+    }
+
+    /// Retângulo em pixels da imagem (origem no canto superior esquerdo) → pixels da página
+    pub fn to_page(&self, left: u32, top: u32, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let [a, b, c, d, e, f] = self.matrix;
+        let [x0, _, _, y1] = self.page_box;
+        let corners = [
+            (left as f32, top as f32),
+            ((left + width) as f32, top as f32),
+            (left as f32, (top + height) as f32),
+            ((left + width) as f32, (top + height) as f32),
+        ];
+        // No PDF a imagem ocupa o quadrado unitário com a primeira linha no topo (v = 1)
+        let points = corners.map(|(x, y)| {
+            let (u, v) = (x / self.image_width.max(1) as f32, 1.0 - y / self.image_height.max(1) as f32);
+            let (px, py) = (a * u + c * v + e, b * u + d * v + f);
+            ((px - x0) * self.scale, (y1 - py) * self.scale)
+        });
+
+        let (page_width, page_height) = (self.page_width() as f32, self.page_height() as f32);
+        let clamp_x = |x: f32| x.round().clamp(0.0, page_width) as u32;
+        let clamp_y = |y: f32| y.round().clamp(0.0, page_height) as u32;
+        let min_x = clamp_x(points.iter().map(|p| p.0).fold(f32::MAX, f32::min));
+        let max_x = clamp_x(points.iter().map(|p| p.0).fold(f32::MIN, f32::max));
+        let min_y = clamp_y(points.iter().map(|p| p.1).fold(f32::MAX, f32::min));
+        let max_y = clamp_y(points.iter().map(|p| p.1).fold(f32::MIN, f32::max));
+        (min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+}
+
+/// Imagens da página decodificadas, na ordem em que aparecem nos recursos
+pub fn page_images(doc: &Document, page_id: ObjectId) -> PageImages {
+    let images = match doc.get_page_images(page_id) {
+        Ok(images) => images,
+        // Página sem XObjects (ou com recursos herdados que não conseguimos ler)
+        Err(_) => return PageImages::default(),
+    };
+
+    let page_box = page_box(doc, page_id);
+    let matrices = image_matrices(doc, page_id);
+    let full_page = [page_box[2] - page_box[0], 0.0, 0.0, page_box[3] - page_box[1], page_box[0], page_box[1]];
+
+    // Escala da página pela primeira imagem: um scan de página inteira mantém seus pixels
+    let scale = images.iter()
+        .find_map(|image| {
+            let [a, b, ..] = matrices.get(&image.id).copied().unwrap_or(full_page);
+            let extent = (a * a + b * b).sqrt();
+            (extent > 0.0 && image.width > 0).then(|| image.width as f32 / extent)
+        })
+        .unwrap_or(1.0);
+
+    let mut result = PageImages::default();
+    for image in &images {
+        let color_space = color_space_name(doc, image.origin_dict).or_else(|| image.color_space.clone());
+        let decoded = decode_image(
+            image.content,
+            image.filters.as_deref().unwrap_or(&[]),
+            image.width,
+            image.height,
+            color_space.as_deref(),
+            image.bits_per_component.unwrap_or(8),
+            image.origin_dict.get(b"DecodeParms").and_then(Object::as_dict).ok(),
+        );
+        let decoded = match decoded {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                log::warn!("⚠️ Imagem {:?} ignorada: {}", image.id, e);
+                result.skipped.push(e);
+                continue;
+            }
+        };
+        let placement = Placement {
+                // Imagem desenhada fora do conteúdo da página (ex.: num Form XObject): página inteira
+                matrix: matrices.get(&image.id).copied().unwrap_or(full_page),
+                image_width: decoded.width(),
+                image_height: decoded.height(),
+                page_box,
+            scale,
+        };
+        result.images.push(PageImage { image: decoded, placement });
+    }
+    result
+}
+
+// Espaço de cor da imagem; ICCBased vira o espaço Device equivalente ao /N do perfil
+fn color_space_name(doc: &Document, dict: &lopdf::Dictionary) -> Option<String> {
+    let (_, color_space) = doc.dereference(dict.get(b"ColorSpace").ok()?).ok()?;
+    let array = color_space.as_array().ok()?;
+    if array.first()?.as_name().ok()? != b"ICCBased" {
+        return None;
+    }
+    let (_, profile) = doc.dereference(array.get(1)?).ok()?;
+    let components = profile.as_stream().ok()?.dict.get(b"N").and_then(Object::as_i64).ok()?;
+    match components {
+        1 => Some("DeviceGray".to_string()),
+        3 => Some("DeviceRGB".to_string()),
+        4 => Some("DeviceCMYK".to_string()),
+        _ => None,
+    }
+}
+
+// MediaBox da página, herdada dos nós Pages se preciso (A4 quando ausente)
+fn page_box(doc: &Document, page_id: ObjectId) -> [f32; 4] {
+    let mut node = doc.get_dictionary(page_id).ok();
+    while let Some(dict) = node {
+        if let Ok(Object::Array(values)) = dict.get(b"MediaBox").and_then(|o| doc.dereference(o)).map(|(_, o)| o) {
+            let numbers: Vec<f32> = values.iter().filter_map(|v| v.as_float().ok()).collect();
+            if let [x0, y0, x1, y1] = numbers[..] {
+                return [x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)];
+            }
+        }
+        node = dict.get(b"Parent").and_then(Object::as_reference).and_then(|id| doc.get_dictionary(id)).ok();
+    }
+    [0.0, 0.0, 595.0, 842.0]
+}
+
+// Matriz ativa (CTM) no primeiro `Do` de cada imagem no conteúdo da página
+fn image_matrices(doc: &Document, page_id: ObjectId) -> HashMap<ObjectId, [f32; 6]> {
+    let mut matrices = HashMap::new();
+    let names: HashMap<Vec<u8>, ObjectId> = doc.get_dictionary(page_id)
+        .and_then(|page| doc.get_dict_in_dict(page, b"Resources"))
+        .and_then(|resources| doc.get_dict_in_dict(resources, b"XObject"))
+        .map(|xobjects| xobjects.iter()
+            .filter_map(|(name, value)| value.as_reference().ok().map(|id| (name.clone(), id)))
+            .collect())
+        .unwrap_or_default();
+    let content = match doc.get_page_content(page_id).ok().and_then(|data| Content::decode(&data).ok()) {
+        Some(content) => content,
+        None => return matrices,
+    };
+
+    let mut ctm = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+    let mut stack = Vec::new();
+    for operation in &content.operations {
+        match operation.operator.as_str() {
+            "q" => stack.push(ctm),
+            "Q" => ctm = stack.pop().unwrap_or(ctm),
+            "cm" => {
+                let values: Vec<f32> = operation.operands.iter().filter_map(|o| o.as_float().ok()).collect();
+                if let [a, b, c, d, e, f] = values[..] {
+                    ctm = multiply([a, b, c, d, e, f], ctm);
+                }
+            }
+            "Do" => {
+                let id = operation.operands.first()
+                    .and_then(|o| o.as_name().ok())
+                    .and_then(|name| names.get(name));
+                if let Some(id) = id {
+                    matrices.entry(*id).or_insert(ctm);
+                }
+            }
+            _ => {}
+        }
+    }
+    matrices
+}
+
+// m × n (a matriz de `cm` é concatenada antes da CTM atual)
+fn multiply(m: [f32; 6], n: [f32; 6]) -> [f32; 6] {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+fn decode_image(
+    content: &[u8],
+    filters: &[String],
+    width: i64,
+    height: i64,
+    color_space: Option<&str>,
+    bits_per_component: i64,
+    decode_params: Option<&lopdf::Dictionary>,
+) -> Result<DynamicImage, String> {
+    let mut data = content.to_vec();
+
+    for filter in filters {
+        match filter.as_str() {
+            "FlateDecode" => {
+                data = inflate(&data)?;
+                if let Some(params) = decode_params {
+                    let predictor = params.get(b"Predictor").and_then(Object::as_i64).unwrap_or(1);
+                    if predictor >= 10 {
+                        let colors = params.get(b"Colors").and_then(Object::as_i64).unwrap_or(1).max(1) as usize;
+                        let columns = params.get(b"Columns").and_then(Object::as_i64).unwrap_or(width).max(1) as usize;
+                        let bits = params.get(b"BitsPerComponent").and_then(Object::as_i64).unwrap_or(bits_per_component) as usize;
+                        data = unfilter_png_rows(&data, (colors * bits).div_ceil(8), (columns * colors * bits).div_ceil(8))?;
+                    }
+                }
+            }
+            "DCTDecode" => {
+                return image::load_from_memory_with_format(&data, image::ImageFormat::Jpeg)
+                    .map_err(|e| format!("JPEG inválido: {}", e));
+            }
+            other => return Err(format!("Filtro não suportado: {}", other)),
+        }
+    }
+
+    raw_pixels_to_image(data, width as u32, height as u32, color_space, bits_per_component)
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut output = Vec::with_capacity(data.len() * 4);
+    flate2::read::ZlibDecoder::new(data)
+        .read_to_end(&mut output)
+        .map_err(|e| format!("Erro ao descompactar imagem: {}", e))?;
+    Ok(output)
+}
+
+// Desfazer os filtros PNG por linha (preditores 10-15 do PDF)
+fn unfilter_png_rows(data: &[u8], bytes_per_pixel: usize, row_length: usize) -> Result<Vec<u8>, String> {
+    let stride = row_length + 1;
+    let mut output = Vec::with_capacity(data.len() / stride * row_length);
+    let mut previous = vec![0u8; row_length];
+
+    for row in data.chunks(stride) {
+        if row.len() < stride {
+            break;
+        }
+        let filter = row[0];
+        let mut current = row[1..].to_vec();
+
+        for i in 0..row_length {
+            let left = if i >= bytes_per_pixel { current[i - bytes_per_pixel] } else { 0 };
+            let up = previous[i];
+            let up_left = if i >= bytes_per_pixel { previous[i - bytes_per_pixel] } else { 0 };
+            let predicted = match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                other => return Err(format!("Preditor PNG inválido: {}", other)),
+            };
+            current[i] = current[i].wrapping_add(predicted);
+        }
+
+        output.extend_from_slice(&current);
+        previous = current;
+    }
+
+    Ok(output)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn raw_pixels_to_image(
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    color_space: Option<&str>,
+    bits_per_component: i64,
+) -> Result<DynamicImage, String> {
+    let pixels = width as usize * height as usize;
+
+    match (color_space.unwrap_or("DeviceGray"), bits_per_component) {
+        ("DeviceGray" | "CalGray", 8) => GrayImage::from_raw(width, height, truncate(data, pixels)?)
+            .map(DynamicImage::ImageLuma8)
+            .ok_or_else(|| "Dimensões inválidas".to_string()),
+        // Bilevel (comum em scanners): 1 bit por pixel, linhas alinhadas em bytes, 0 = preto
+        ("DeviceGray" | "CalGray", 1) => {
+            let row_bytes = (width as usize).div_ceil(8);
+            let data = truncate(data, row_bytes * height as usize)?;
+            let mut gray = Vec::with_capacity(pixels);
+            for row in data.chunks(row_bytes) {
+                for x in 0..width as usize {
+                    let bit = (row[x / 8] >> (7 - x % 8)) & 1;
+                    gray.push(if bit == 1 { 255 } else { 0 });
+                }
+            }
+            GrayImage::from_raw(width, height, gray)
+                .map(DynamicImage::ImageLuma8)
+                .ok_or_else(|| "Dimensões inválidas".to_string())
+        }
+        ("DeviceRGB" | "CalRGB", 8) => RgbImage::from_raw(width, height, truncate(data, pixels * 3)?)
+            .map(DynamicImage::ImageRgb8)
+            .ok_or_else(|| "Dimensões inválidas".to_string()),
+        ("DeviceCMYK", 8) => {
+            let data = truncate(data, pixels * 4)?;
+            let rgb: Vec<u8> = data.chunks(4)
+                .flat_map(|p| {
+                    let k = 255 - p[3] as u16;
+                    [0, 1, 2].map(|i| ((255 - p[i] as u16) * k / 255) as u8)
+                })
+                .collect();
+            RgbImage::from_raw(width, height, rgb)
+                .map(DynamicImage::ImageRgb8)
+                .ok_or_else(|| "Dimensões inválidas".to_string())
+        }
+        (space, bits) => Err(format!("Espaço de cor não suportado: {} ({} bits)", space, bits)),
+    }
+}
+
+fn truncate(mut data: Vec<u8>, expected: usize) -> Result<Vec<u8>, String> {
+    if data.len() < expected {
+        return Err(format!("Imagem incompleta: {} de {} bytes", data.len(), expected));
+    }
+    data.truncate(expected);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, Stream};
+
+    // PDF de uma página com uma imagem cinza 4x2 compactada com preditor PNG
+    fn pdf_with_image() -> (Document, ObjectId) {
+        let mut doc = Document::with_version("1.5");
+
+        // Linha 1 com filtro Sub, linha 2 com filtro Up
+        let filtered = vec![1, 0, 10, 10, 10, 2, 40, 40, 40, 40];
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &filtered).unwrap();
+
+        let image_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => 4,
+                "Height" => 2,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 8,
+                "Filter" => "FlateDecode",
+                "DecodeParms" => dictionary! { "Predictor" => 15, "Columns" => 4 },
+            },
+            encoder.finish().unwrap(),
+        ));
+        let pages_id = doc.new_object_id();
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Resources" => dictionary! { "XObject" => dictionary! { "Im1" => image_id } },
+        });
+        doc.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page_id.into()],
+            "Count" => 1,
+        }));
+
+        (doc, page_id)
+    }
+
+    #[test]
+    fn test_flate_image_with_predictor() {
+        let (doc, page_id) = pdf_with_image();
+        let images = page_images(&doc, page_id).images;
+
+        assert_eq!(images.len(), 1);
+        let gray = images[0].image.to_luma8();
+        assert_eq!(gray.dimensions(), (4, 2));
+        assert_eq!(gray.into_raw(), vec![0, 10, 20, 30, 40, 50, 60, 70]);
+    }
+
+    #[test]
+    fn test_image_placement_in_page_space() {
+        let (mut doc, page_id) = pdf_with_image();
+        // Página de 200x100 pontos; imagem 4x2 desenhada com 100x50 pontos a partir de (100, 0)
+        let content_id = doc.add_object(Stream::new(dictionary! {}, b"q 100 0 0 50 100 0 cm /Im1 Do Q".to_vec()));
+        let page = doc.get_object_mut(page_id).and_then(Object::as_dict_mut).unwrap();
+        page.set("Contents", content_id);
+        page.set("MediaBox", vec![0.into(), 0.into(), 200.into(), 100.into()]);
+
+        let placement = &page_images(&doc, page_id).images[0].placement;
+        // 4 pixels em 100 pontos: a página tem 8x4 pixels nessa resolução
+        assert_eq!((placement.page_width(), placement.page_height()), (8, 4));
+        // Pixel (0, 0) da imagem fica no quadrante inferior direito da página
+        assert_eq!(placement.to_page(0, 0, 1, 1), (4, 2, 1, 1));
+        assert_eq!(placement.to_page(0, 0, 4, 2), (4, 2, 4, 2));
+    }
+
+    #[test]
+    fn test_icc_based_and_unsupported_filter() {
+        let mut doc = Document::with_version("1.5");
+        let profile_id = doc.add_object(Stream::new(dictionary! { "N" => 3 }, vec![]));
+        let icc_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => 1,
+                "Height" => 1,
+                "ColorSpace" => vec![Object::Name(b"ICCBased".to_vec()), profile_id.into()],
+                "BitsPerComponent" => 8,
+            },
+            vec![255, 0, 0],
+        ));
+        let fax_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => 8,
+                "Height" => 1,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 1,
+                "Filter" => "CCITTFaxDecode",
+            },
+            vec![0],
+        ));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Resources" => dictionary! { "XObject" => dictionary! { "Im1" => icc_id, "Im2" => fax_id } },
+        });
+
+        let result = page_images(&doc, page_id);
+        assert_eq!(result.images.len(), 1);
+        assert_eq!(result.images[0].image.to_rgb8().into_raw(), vec![255, 0, 0]);
+        assert_eq!(result.skipped, vec!["Filtro não suportado: CCITTFaxDecode".to_string()]);
+    }
+
+    #[test]
+    fn test_bilevel_pixels() {
+        // 10 pixels por linha → 2 bytes por linha
+        let data = vec![0b1010_0000, 0b1100_0000, 0xFF, 0xFF];
+        let image = raw_pixels_to_image(data, 10, 2, Some("DeviceGray"), 1).unwrap().to_luma8();
+        assert_eq!(&image.as_raw()[..10], &[255, 0, 255, 0, 0, 0, 0, 0, 255, 255]);
+        assert!(image.as_raw()[10..].iter().all(|&p| p == 255));
+    }
+
+    #[test]
+    fn test_page_without_images() {
+        let mut doc = Document::with_version("1.5");
+        let page_id = doc.add_object(dictionary! { "Type" => "Page" });
+        assert!(page_images(&doc, page_id).images.is_empty());
+        assert!(raw_pixels_to_image(vec![0; 3], 2, 2, Some("DeviceGray"), 8).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+// EXECUÇÃO DO TESSERACT VIA LINHA DE COMANDO
+// Usa o tessdata empacotado com o app (tessdata/por.traineddata) quando encontrado e
+// lê a saída em TSV, que traz a confiança de cada palavra além do texto.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+static TESSDATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OcrWord {
+    pub text: String,
+    pub confidence: f32,
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecognizedText {
+    pub text: String,
+    // Média da confiança das palavras (0.0 - 1.0); None se nenhuma palavra foi reconhecida
+    pub confidence: Option<f32>,
+    pub words: Vec<OcrWord>,
+}
+
+/// Registrar o tessdata empacotado (diretório de recursos do Tauri), chamado no setup
+pub fn configure_tessdata_dir(dir: PathBuf) {
+    if dir.join("por.traineddata").exists() {
+        log::info!("📁 Tessdata empacotado em: {:?}", dir);
+        let _ = TESSDATA_DIR.set(dir);
+    }
+}
+
+// Tessdata empacotado ou, em desenvolvimento, o da pasta src-tauri/tessdata
+pub fn tessdata_dir() -> Option<PathBuf> {
+    if let Some(dir) = TESSDATA_DIR.get() {
+        return Some(dir.clone());
+    }
+
+    ["src-tauri/tessdata", "tessdata", "../tessdata"]
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.join("por.traineddata").exists())
+}
+
+// Português sempre; inglês junto quando disponível
+fn languages(tessdata: Option<&Path>) -> &'static str {
+    match tessdata {
+        Some(dir) if !dir.join("eng.traineddata").exists() => "por",
+        _ => "por+eng",
+    }
+}
+
+pub async fn is_available() -> bool {
+    match Command::new("tesseract").arg("--version").output().await {
+        Ok(output) => output.status.success(),
+        Err(_) => false,
+    }
+}
+
+/// Reconhecer o texto de uma imagem, com confiança por palavra
+pub async fn recognize(image_path: &Path) -> Result<RecognizedText, String> {
+    let tessdata = tessdata_dir();
+
+    let mut command = Command::new("tesseract");
+    command.arg(image_path).arg("stdout");
+    if let Some(dir) = &tessdata {
+        command.arg("--tessdata-dir").arg(dir);
+    }
+    command.arg("-l").arg(languages(tessdata.as_deref())).arg("tsv");
+
+    let output = command.output().await
+        .map_err(|e| format!("Erro ao executar Tesseract: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("Tesseract falhou: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Converter a saída TSV do Tesseract em texto (uma linha por linha reconhecida,
+/// linha em branco entre parágrafos) e na lista de palavras
+pub fn parse_tsv(tsv: &str) -> RecognizedText {
+    let mut text = String::new();
+    let mut words = Vec::new();
+    let mut current_line: Option<(u32, u32, u32)> = None;
+    let mut current_paragraph: Option<(u32, u32)> = None;
+    let mut line_number = 0;
+
+    for row in tsv.lines().skip(1) {
+        let columns: Vec<&str> = row.split('\t').collect();
+        // level page block par line word left top width height conf text
+        if columns.len() < 12 || columns[0] != "5" {
+            continue;
+        }
+
+        let word = columns[11..].join("\t").trim().to_string();
+        let confidence: f32 = columns[10].parse().unwrap_or(-1.0);
+        if word.is_empty() || confidence < 0.0 {
+            continue;
+        }
+
+        let number = |i: usize| columns[i].parse::<u32>().unwrap_or(0);
+        let paragraph = (number(2), number(3));
+        let line = (number(2), number(3), number(4));
+
+        if current_line != Some(line) {
+            if current_line.is_some() {
+                text.push('\n');
+                if current_paragraph != Some(paragraph) {
+                    text.push('\n');
+                }
+                line_number += 1;
+            }
+            current_line = Some(line);
+            current_paragraph = Some(paragraph);
+        } else {
+            text.push(' ');
+        }
+        text.push_str(&word);
+
+        words.push(OcrWord {
+            text: word,
+            confidence: confidence / 100.0,
+            left: number(6),
+            top: number(7),
+            width: number(8),
+            height: number(9),
+            line: line_number,
+        });
+    }
+
+    let confidence = if words.is_empty() {
+        None
+    } else {
+        Some(words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32)
+    };
+
+    RecognizedText { text, confidence, words }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tsv() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+1\t1\t0\t0\t0\t0\t0\t0\t1000\t800\t-1\t\n\
+5\t1\t1\t1\t1\t1\t10\t20\t80\t30\t96.5\tRECIBO\n\
+5\t1\t1\t1\t2\t1\t10\t60\t50\t30\t90\tValor:\n\
+5\t1\t1\t1\t2\t2\t70\t60\t40\t30\t80\tR$\n\
+5\t1\t1\t1\t2\t3\t120\t60\t60\t30\t-1\t \n\
+5\t1\t2\t1\t1\t1\t10\t200\t90\t30\t70.5\tAssinatura\n";
+
+        let result = parse_tsv(tsv);
+        assert_eq!(result.text, "RECIBO\nValor: R$\n\nAssinatura");
+        assert_eq!(result.words.len(), 4);
+        assert_eq!(result.words[2], OcrWord {
+            text: "R$".to_string(),
+            confidence: 0.8,
+            left: 70,
+            top: 60,
+            width: 40,
+            height: 30,
+            line: 1,
+        });
+        assert_eq!(result.words[3].line, 2);
+        assert!((result.confidence.unwrap() - 0.8425).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_parse_empty_tsv() {
+        let result = parse_tsv("level\tpage_num\n");
+        assert!(result.text.is_empty());
+        assert!(result.confidence.is_none());
+    }
+}
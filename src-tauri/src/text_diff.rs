@@ -0,0 +1,198 @@
+// DIFF DE TEXTO POR LINHA - COMPARAÇÃO ENTRE VERSÕES DE DOCUMENTOS
+// Usa a maior subsequência comum (LCS) após remover prefixo e sufixo iguais,
+// o que mantém o custo baixo nos casos comuns (poucas linhas alteradas).
+
+use serde::{Deserialize, Serialize};
+
+/// Acima deste tamanho de matriz (linhas antigas x novas) o diff vira "remove tudo / adiciona tudo"
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiffKind {
+    Equal,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// Diff linha a linha entre dois textos (números de linha começam em 1)
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let prefix = old_lines.iter()
+        .zip(new_lines.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..].iter().rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut result = Vec::with_capacity(old_lines.len().max(new_lines.len()));
+
+    for (i, line) in old_lines[..prefix].iter().enumerate() {
+        result.push(equal(line, i, i));
+    }
+
+    for (kind, old_idx, new_idx) in diff_middle(old_mid, new_mid) {
+        let (old_idx, new_idx) = (old_idx.map(|i| i + prefix), new_idx.map(|i| i + prefix));
+        let text = match kind {
+            DiffKind::Added => new_lines[new_idx.unwrap()],
+            _ => old_lines[old_idx.unwrap()],
+        };
+        result.push(DiffLine {
+            kind,
+            text: text.to_string(),
+            old_line: old_idx.map(|i| i + 1),
+            new_line: new_idx.map(|i| i + 1),
+        });
+    }
+
+    for k in 0..suffix {
+        let old_idx = old_lines.len() - suffix + k;
+        let new_idx = new_lines.len() - suffix + k;
+        result.push(equal(old_lines[old_idx], old_idx, new_idx));
+    }
+
+    result
+}
+
+/// Contagem de linhas adicionadas, removidas e inalteradas
+pub fn summarize(diff: &[DiffLine]) -> DiffSummary {
+    let mut summary = DiffSummary::default();
+    for line in diff {
+        match line.kind {
+            DiffKind::Added => summary.added += 1,
+            DiffKind::Removed => summary.removed += 1,
+            DiffKind::Equal => summary.unchanged += 1,
+        }
+    }
+    summary
+}
+
+fn equal(text: &str, old_idx: usize, new_idx: usize) -> DiffLine {
+    DiffLine {
+        kind: DiffKind::Equal,
+        text: text.to_string(),
+        old_line: Some(old_idx + 1),
+        new_line: Some(new_idx + 1),
+    }
+}
+
+// Diff do trecho central via tabela LCS; retorna (tipo, índice antigo, índice novo)
+fn diff_middle(old: &[&str], new: &[&str]) -> Vec<(DiffKind, Option<usize>, Option<usize>)> {
+    let (n, m) = (old.len(), new.len());
+
+    if n.saturating_mul(m) > MAX_LCS_CELLS {
+        log::warn!("⚠️ Diff muito grande ({} x {} linhas), comparação simplificada", n, m);
+        return (0..n).map(|i| (DiffKind::Removed, Some(i), None))
+            .chain((0..m).map(|j| (DiffKind::Added, None, Some(j))))
+            .collect();
+    }
+
+    // lcs[i][j] = tamanho da LCS entre old[i..] e new[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push((DiffKind::Equal, Some(i), Some(j)));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push((DiffKind::Removed, Some(i), None));
+            i += 1;
+        } else {
+            ops.push((DiffKind::Added, None, Some(j)));
+            j += 1;
+        }
+    }
+    ops.extend((i..n).map(|i| (DiffKind::Removed, Some(i), None)));
+    ops.extend((j..m).map(|j| (DiffKind::Added, None, Some(j))));
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(diff: &[DiffLine]) -> Vec<String> {
+        diff.iter()
+            .map(|l| {
+                let sign = match l.kind {
+                    DiffKind::Equal => ' ',
+                    DiffKind::Added => '+',
+                    DiffKind::Removed => '-',
+                };
+                format!("{}{}", sign, l.text)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_texts() {
+        let diff = diff_lines("a\nb\nc", "a\nb\nc");
+        assert_eq!(summarize(&diff), DiffSummary { added: 0, removed: 0, unchanged: 3 });
+        assert_eq!(diff[2].old_line, Some(3));
+        assert_eq!(diff[2].new_line, Some(3));
+    }
+
+    #[test]
+    fn test_changed_line_in_the_middle() {
+        let old = "CONTRATO\nValor: R$ 1.000,00\nVencimento: 10/01/2024";
+        let new = "CONTRATO\nValor: R$ 1.200,00\nVencimento: 10/01/2024";
+        let diff = diff_lines(old, new);
+
+        assert_eq!(render(&diff), vec![
+            " CONTRATO",
+            "-Valor: R$ 1.000,00",
+            "+Valor: R$ 1.200,00",
+            " Vencimento: 10/01/2024",
+        ]);
+        assert_eq!(diff[1].old_line, Some(2));
+        assert_eq!(diff[2].new_line, Some(2));
+        assert_eq!(diff[3].new_line, Some(3));
+    }
+
+    #[test]
+    fn test_insertions_and_deletions() {
+        let diff = diff_lines("a\nb\nc\nd", "a\nc\nd\ne");
+        assert_eq!(render(&diff), vec![" a", "-b", " c", " d", "+e"]);
+        assert_eq!(summarize(&diff), DiffSummary { added: 1, removed: 1, unchanged: 3 });
+    }
+
+    #[test]
+    fn test_empty_sides() {
+        assert_eq!(render(&diff_lines("", "x\ny")), vec!["+x", "+y"]);
+        assert_eq!(render(&diff_lines("x", "")), vec!["-x"]);
+        assert!(diff_lines("", "").is_empty());
+    }
+}
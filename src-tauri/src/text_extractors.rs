@@ -0,0 +1,559 @@
+// EXTRATORES DE TEXTO PARA DOCUMENTOS DE ESCRITÓRIO E TEXTO PURO
+// DOCX/ODT (zip + XML), RTF, TXT/MD, CSV e HTML. Todos devolvem texto simples,
+// que o SimpleOCRProcessor classifica e do qual extrai campos como nos demais formatos.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+// ================================
+// DOCX / ODT
+// ================================
+
+/// Texto do corpo de um DOCX (word/document.xml)
+pub fn extract_docx(path: &Path) -> io::Result<String> {
+    let xml = read_zip_entry(path, "word/document.xml")?;
+    Ok(office_xml_to_text(&xml, &["w:p"], &["w:tab"], &["w:br", "w:cr"], "w:t"))
+}
+
+/// Texto de um ODT (content.xml)
+pub fn extract_odt(path: &Path) -> io::Result<String> {
+    let xml = read_zip_entry(path, "content.xml")?;
+    Ok(office_xml_to_text(&xml, &["text:p", "text:h"], &["text:tab"], &["text:line-break"], ""))
+}
+
+fn read_zip_entry(path: &Path, entry_name: &str) -> io::Result<String> {
+    let file = fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Arquivo compactado inválido: {}", e)))?;
+    let mut entry = archive.by_name(entry_name)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("{} não encontrado no documento", entry_name)))?;
+
+    let mut content = String::new();
+    entry.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+// Converte XML de documento de escritório em texto: parágrafos viram quebras de linha,
+// tabulações e quebras manuais são preservadas. Se `text_tag` for informado, só o conteúdo
+// dessa tag é considerado texto (DOCX guarda tudo em <w:t>); caso contrário, todo o texto entre tags.
+fn office_xml_to_text(xml: &str, paragraph_tags: &[&str], tab_tags: &[&str], break_tags: &[&str], text_tag: &str) -> String {
+    let mut output = String::new();
+    let mut inside_text = text_tag.is_empty();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        if inside_text {
+            output.push_str(&decode_entities(&rest[..start]));
+        }
+
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag.trim_start_matches('/')
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or("");
+
+        // <text:s text:c="3"/> representa espaços repetidos no ODT
+        if name == "text:s" {
+            let count = attribute(tag, "text:c").and_then(|c| c.parse().ok()).unwrap_or(1);
+            output.push_str(&" ".repeat(count));
+        } else if tab_tags.contains(&name) && !closing {
+            output.push('\t');
+        } else if (break_tags.contains(&name) && !closing)
+            || (paragraph_tags.contains(&name) && (closing || tag.ends_with('/')))
+        {
+            output.push('\n');
+        } else if !text_tag.is_empty() && name == text_tag {
+            inside_text = !closing && !tag.ends_with('/');
+        }
+    }
+
+    normalize_lines(&output)
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=\"", name))? + name.len() + 2;
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+// ================================
+// RTF
+// ================================
+
+// Grupos de destino cujo conteúdo não é texto do documento
+const RTF_SKIP_DESTINATIONS: &[&str] = &[
+    "fonttbl", "colortbl", "stylesheet", "info", "pict", "header", "footer",
+    "headerl", "headerr", "footerl", "footerr", "object", "themedata", "datastore", "xmlnstbl",
+];
+
+/// Texto de um documento RTF (ignora tabelas de fontes, cores, imagens e metadados)
+pub fn extract_rtf(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut output = String::new();
+    // Pilha de grupos: (ignorar conteúdo, caracteres de fallback após \uN)
+    let mut stack: Vec<(bool, usize)> = vec![(false, 1)];
+    let mut skip_fallback = 0usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (skipping, uc) = *stack.last().unwrap_or(&(false, 1));
+        let c = chars[i];
+
+        match c {
+            '{' => {
+                stack.push((skipping, uc));
+                i += 1;
+            }
+            '}' => {
+                if stack.len() > 1 {
+                    stack.pop();
+                }
+                i += 1;
+            }
+            '\\' => {
+                i += 1;
+                let Some(&next) = chars.get(i) else { break };
+
+                if next == '\'' {
+                    // \'hh: caractere na página de código do documento (assume Windows-1252)
+                    let hex: String = chars.iter().skip(i + 1).take(2).collect();
+                    i += 3;
+                    if skip_fallback > 0 {
+                        skip_fallback -= 1;
+                    } else if !skipping {
+                        if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                            output.push(windows_1252_char(byte));
+                        }
+                    }
+                    continue;
+                }
+
+                if !next.is_ascii_alphabetic() {
+                    // Símbolos de controle: \\ \{ \} \~ \- \* ...
+                    i += 1;
+                    match next {
+                        '*' => {
+                            if let Some(top) = stack.last_mut() {
+                                top.0 = true;
+                            }
+                        }
+                        '~' if !skipping => output.push('\u{a0}'),
+                        '\\' | '{' | '}' if !skipping => output.push(next),
+                        '\n' | '\r' if !skipping => output.push('\n'),
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                let word_start = i;
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                let word: String = chars[word_start..i].iter().collect();
+
+                let param_start = i;
+                if i < chars.len() && chars[i] == '-' {
+                    i += 1;
+                }
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let param: Option<i32> = chars[param_start..i].iter().collect::<String>().parse().ok();
+                if i < chars.len() && chars[i] == ' ' {
+                    i += 1;
+                }
+
+                if RTF_SKIP_DESTINATIONS.contains(&word.as_str()) {
+                    if let Some(top) = stack.last_mut() {
+                        top.0 = true;
+                    }
+                    continue;
+                }
+
+                match word.as_str() {
+                    "uc" => {
+                        if let Some(top) = stack.last_mut() {
+                            top.1 = param.unwrap_or(1).max(0) as usize;
+                        }
+                    }
+                    "u" if !skipping => {
+                        if let Some(code) = param {
+                            let code = if code < 0 { code + 65536 } else { code } as u32;
+                            if let Some(ch) = char::from_u32(code) {
+                                output.push(ch);
+                            }
+                        }
+                        skip_fallback = uc;
+                    }
+                    "par" | "line" | "row" | "sect" | "page" if !skipping => output.push('\n'),
+                    "tab" | "cell" if !skipping => output.push('\t'),
+                    "emdash" if !skipping => output.push('—'),
+                    "endash" if !skipping => output.push('–'),
+                    "bullet" if !skipping => output.push('•'),
+                    "lquote" if !skipping => output.push('‘'),
+                    "rquote" if !skipping => output.push('’'),
+                    "ldblquote" if !skipping => output.push('“'),
+                    "rdblquote" if !skipping => output.push('”'),
+                    _ => {}
+                }
+            }
+            '\r' | '\n' => i += 1,
+            _ => {
+                if skip_fallback > 0 {
+                    skip_fallback -= 1;
+                } else if !skipping {
+                    output.push(c);
+                }
+                i += 1;
+            }
+        }
+    }
+
+    normalize_lines(&output)
+}
+
+fn windows_1252_char(byte: u8) -> char {
+    // Faixa 0x80-0x9F difere do Latin-1
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+        '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9f => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+// ================================
+// TEXTO PURO, CSV E HTML
+// ================================
+
+/// Decodifica bytes de arquivo texto: UTF-8 (com ou sem BOM), senão Windows-1252
+pub fn decode_text(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => decode_windows_1252(bytes),
+    }
+}
+
+pub fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| windows_1252_char(b)).collect()
+}
+
+/// CSV como linhas "valor | valor | valor" (mesmo formato das planilhas)
+pub fn extract_csv(content: &str) -> String {
+    let delimiter = detect_csv_delimiter(content);
+    let mut output = String::new();
+
+    for record in parse_csv_records(content, delimiter) {
+        let values: Vec<&str> = record.iter().map(|v| v.trim()).filter(|v| !v.is_empty()).collect();
+        if !values.is_empty() {
+            output.push_str(&values.join(" | "));
+            output.push('\n');
+        }
+    }
+
+    output.trim().to_string()
+}
+
+// Delimitador mais frequente na primeira linha (";" é comum em CSVs brasileiros)
+fn detect_csv_delimiter(content: &str) -> char {
+    let first_line = content.lines().next().unwrap_or("");
+    [';', ',', '\t', '|']
+        .into_iter()
+        .max_by_key(|d| first_line.matches(*d).count())
+        .filter(|d| first_line.contains(*d))
+        .unwrap_or(',')
+}
+
+fn parse_csv_records(content: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+        } else if c == '"' {
+            in_quotes = true;
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' {
+            record.push(std::mem::take(&mut field));
+            records.push(std::mem::take(&mut record));
+        } else if c != '\r' {
+            field.push(c);
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+const HTML_BLOCK_TAGS: &[&str] = &[
+    "p", "div", "br", "li", "tr", "h1", "h2", "h3", "h4", "h5", "h6",
+    "table", "ul", "ol", "section", "article", "header", "footer", "blockquote", "pre", "hr",
+];
+
+/// Texto visível de um HTML (sem scripts, estilos e comentários)
+pub fn extract_html(content: &str) -> String {
+    let mut output = String::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find('<') {
+        output.push_str(&decode_entities(&rest[..start]));
+        rest = &rest[start..];
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|end| &rest[end + 3..]).unwrap_or("");
+            continue;
+        }
+
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        let closing = tag.starts_with('/');
+        let name = tag.trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+
+        // Pular o conteúdo de <script> e <style> por inteiro
+        if !closing && (name == "script" || name == "style") {
+            let lower = rest.to_lowercase();
+            rest = lower.find(&format!("</{}", name))
+                .map(|pos| &rest[pos..])
+                .unwrap_or("");
+            continue;
+        }
+
+        if name == "td" || name == "th" {
+            if closing {
+                output.push('\t');
+            }
+        } else if HTML_BLOCK_TAGS.contains(&name.as_str()) {
+            output.push('\n');
+        }
+    }
+    output.push_str(&decode_entities(rest));
+
+    // Espaços e linhas em branco não são significativos em HTML
+    let lines: Vec<String> = output
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines.join("\n")
+}
+
+/// Entidades HTML/XML mais comuns, numéricas incluídas
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = match rest.find(';') {
+            Some(end) if end <= 10 => end,
+            _ => {
+                output.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "ordm" => Some('º'),
+            "ordf" => Some('ª'),
+            "sect" => Some('§'),
+            "euro" => Some('€'),
+            _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                u32::from_str_radix(&entity[2..], 16).ok().and_then(char::from_u32)
+            }
+            _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(char::from_u32),
+            _ => named_accent_entity(entity),
+        };
+
+        match decoded {
+            Some(c) => {
+                output.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+// &aacute; &Ccedil; &atilde; ... (acentos usados em português)
+fn named_accent_entity(entity: &str) -> Option<char> {
+    // Entidade vazia ou com letra fora do ASCII ("&;", "&ção;") não é acento conhecido
+    match entity.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() && entity.is_ascii() => {}
+        _ => return None,
+    }
+    let (base, accent) = entity.split_at(1);
+    let table: &[(&str, &str, &str)] = &[
+        ("acute", "aeiouAEIOU", "áéíóúÁÉÍÓÚ"),
+        ("grave", "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+        ("circ", "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+        ("tilde", "aonAON", "ãõñÃÕÑ"),
+        ("uml", "aeiouAEIOU", "äëïöüÄËÏÖÜ"),
+        ("cedil", "cC", "çÇ"),
+    ];
+
+    table.iter()
+        .find(|(name, _, _)| *name == accent)
+        .and_then(|(_, bases, accented)| {
+            bases.chars().position(|b| b.to_string() == base)
+                .and_then(|pos| accented.chars().nth(pos))
+        })
+}
+
+// Remove espaços no fim das linhas e colapsa mais de uma linha em branco
+fn normalize_lines(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut blank_run = 0;
+
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    output.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_docx_extraction() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contrato.docx");
+
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        zip.start_file("word/document.xml", zip::write::FileOptions::default()).unwrap();
+        zip.write_all(
+            br#"<?xml version="1.0"?><w:document><w:body>
+            <w:p><w:r><w:t>CONTRATO DE LOCA&#199;&#195;O</w:t></w:r></w:p>
+            <w:p><w:r><w:t xml:space="preserve">Valor: </w:t></w:r><w:r><w:tab/><w:t>R$ 1.500,00</w:t></w:r></w:p>
+            <w:p/>
+            <w:p><w:r><w:t>Locador &amp; Locat&#225;rio</w:t></w:r></w:p>
+            </w:body></w:document>"#,
+        ).unwrap();
+        zip.finish().unwrap();
+
+        assert_eq!(
+            extract_docx(&path).unwrap(),
+            "CONTRATO DE LOCAÇÃO\nValor: \tR$ 1.500,00\n\nLocador & Locatário"
+        );
+    }
+
+    #[test]
+    fn test_odt_xml() {
+        let xml = r#"<office:text><text:h>Recibo</text:h><text:p>Recebi de<text:s text:c="2"/>Maria</text:p><text:p>Data:<text:tab/>10/01/2024</text:p></office:text>"#;
+        assert_eq!(
+            office_xml_to_text(xml, &["text:p", "text:h"], &["text:tab"], &["text:line-break"], ""),
+            "Recibo\nRecebi de  Maria\nData:\t10/01/2024"
+        );
+    }
+
+    #[test]
+    fn test_rtf_extraction() {
+        let rtf = r"{\rtf1\ansi\ansicpg1252{\fonttbl{\f0 Arial;}}{\colortbl;\red0\green0\blue0;}
+{\*\generator Riched20;}\f0\fs24 Nota Fiscal n\'ba 123\par
+Descri\'e7\'e3o: servi\u231?o\tab R$ 50,00\par
+{\info{\author Fulano}}Fim}";
+        assert_eq!(extract_rtf(rtf), "Nota Fiscal nº 123\nDescrição: serviço\tR$ 50,00\nFim");
+    }
+
+    #[test]
+    fn test_csv_extraction() {
+        let csv = "Data;Descrição;Valor\n10/01/2024;\"Aluguel; janeiro\";\"1.500,00\"\n\n11/01/2024;Luz;120,50\n";
+        assert_eq!(
+            extract_csv(csv),
+            "Data | Descrição | Valor\n10/01/2024 | Aluguel; janeiro | 1.500,00\n11/01/2024 | Luz | 120,50"
+        );
+        assert_eq!(extract_csv("a,b\n1,2"), "a | b\n1 | 2");
+    }
+
+    #[test]
+    fn test_html_extraction() {
+        let html = r#"<html><head><title>Boleto</title><style>p { color: red; }</style>
+            <script>var x = "<p>não</p>";</script></head>
+            <body><h1>Boleto   Banc&aacute;rio</h1><!-- comentário -->
+            <table><tr><td>Vencimento</td><td>15/02/2024</td></tr></table>
+            <p>Valor &gt; R$&nbsp;100,00</p></body></html>"#;
+        assert_eq!(
+            extract_html(html),
+            "Boleto\nBoleto Bancário\nVencimento 15/02/2024\nValor > R$ 100,00"
+        );
+    }
+
+    #[test]
+    fn test_html_malformed_entities() {
+        assert_eq!(extract_html("<p>Tom &; Jerry</p>"), "Tom &; Jerry");
+        assert_eq!(extract_html("<p>P&ção;</p>"), "P&ção;");
+    }
+
+    #[test]
+    fn test_decode_text_fallback() {
+        assert_eq!(decode_text("\u{feff}olá".as_bytes()), "olá");
+        assert_eq!(decode_text(&[0x43, 0x61, 0x66, 0xe9, 0x20, 0x80]), "Café €");
+    }
+}
@@ -0,0 +1,550 @@
+// PASTAS MONITORADAS - IMPORTAÇÃO AUTOMÁTICA
+// Um serviço em segundo plano varre periodicamente as pastas configuradas e envia
+// arquivos novos pelo pipeline OCR → documento → índice de busca (ver ingest.rs).
+// Arquivos só são processados depois de ficarem estáveis (mesmo tamanho e data de
+// modificação por DEBOUNCE_SECS), para não pegar arquivos ainda sendo gravados pelo scanner.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use uuid::Uuid;
+
+use crate::database_sqlite::WatchFolder;
+use crate::{ingest, log_audit_event, ocr_simple, AppState};
+
+const POLL_INTERVAL_SECS: u64 = 5;
+const DEBOUNCE_SECS: u64 = 3;
+const DEFAULT_ARCHIVE_SUBFOLDER: &str = "Processados";
+
+/// Temporários de scanners, navegadores e do Office
+const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &[".*", "~$*", "*.tmp", "*.part", "*.partial", "*.crdownload"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchFolderEvent {
+    pub watch_folder_id: String,
+    pub file_path: String,
+    pub status: String, // "processed", "duplicate", "skipped" ou "failed"
+    pub document_id: Option<String>,
+    pub error: Option<String>,
+}
+
+// Arquivo aguardando estabilizar antes do processamento
+struct PendingFile {
+    size: u64,
+    modified: SystemTime,
+    stable_since: Instant,
+}
+
+// ================================
+// SERVIÇO EM SEGUNDO PLANO
+// ================================
+
+pub async fn run_watch_service(state: AppState, app: AppHandle) {
+    log::info!("👀 Serviço de pastas monitoradas iniciado");
+    let mut pending: HashMap<PathBuf, PendingFile> = HashMap::new();
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+
+        let folders = match state.db.list_enabled_watch_folders() {
+            Ok(folders) => folders,
+            Err(e) => {
+                log::warn!("⚠️ Erro ao carregar pastas monitoradas: {:?}", e);
+                continue;
+            }
+        };
+
+        for folder in &folders {
+            scan_folder(&state, &app, folder, &mut pending).await;
+        }
+
+        // Esquecer arquivos que sumiram antes de estabilizar
+        pending.retain(|path, _| path.exists());
+    }
+}
+
+async fn scan_folder(
+    state: &AppState,
+    app: &AppHandle,
+    folder: &WatchFolder,
+    pending: &mut HashMap<PathBuf, PendingFile>,
+) {
+    let root = Path::new(&folder.path);
+    if !root.is_dir() {
+        log::debug!("📂 Pasta monitorada indisponível: {}", folder.path);
+        return;
+    }
+
+    for path in list_candidate_files(root, folder) {
+        let metadata = match fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        let modified_key = DateTime::<Utc>::from(modified).to_rfc3339();
+        let path_str = path.to_string_lossy().to_string();
+
+        match state.db.is_watch_file_processed(&folder.id, &path_str, &modified_key) {
+            Ok(true) => continue,
+            Ok(false) => {}
+            Err(e) => {
+                log::warn!("⚠️ Erro ao consultar arquivo monitorado: {:?}", e);
+                continue;
+            }
+        }
+
+        // Debounce: só processar quando tamanho e data pararem de mudar
+        let entry = pending.entry(path.clone()).or_insert(PendingFile {
+            size: metadata.len(),
+            modified,
+            stable_since: Instant::now(),
+        });
+        if entry.size != metadata.len() || entry.modified != modified {
+            entry.size = metadata.len();
+            entry.modified = modified;
+            entry.stable_since = Instant::now();
+            continue;
+        }
+        if entry.stable_since.elapsed() < Duration::from_secs(DEBOUNCE_SECS) {
+            continue;
+        }
+        pending.remove(&path);
+
+        let event = process_file(state, folder, &path).await;
+
+        let _ = state.db.record_watch_file(
+            &folder.id,
+            &path_str,
+            &modified_key,
+            &event.status,
+            event.document_id.as_deref(),
+            event.error.as_deref(),
+        );
+
+        if let Err(e) = app.emit("watch-folder-event", event.clone()) {
+            log::warn!("⚠️ Erro ao emitir evento da pasta monitorada: {:?}", e);
+        }
+    }
+}
+
+async fn process_file(state: &AppState, folder: &WatchFolder, path: &Path) -> WatchFolderEvent {
+    let path_str = path.to_string_lossy().to_string();
+    let mut event = WatchFolderEvent {
+        watch_folder_id: folder.id.clone(),
+        file_path: path_str.clone(),
+        status: "failed".to_string(),
+        document_id: None,
+        error: None,
+    };
+
+    let user = match state.db.get_user_by_id(&folder.user_id) {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            event.error = Some("Usuário da pasta monitorada não encontrado".to_string());
+            return event;
+        }
+        Err(e) => {
+            event.error = Some(format!("Erro ao buscar usuário: {:?}", e));
+            return event;
+        }
+    };
+
+    // XML que não é NF-e fica registrado como ignorado: é lido uma vez só, sem falhar
+    if ocr_simple::is_non_nfe_xml(path) {
+        log::debug!("📂 XML ignorado (não é NF-e): {}", path_str);
+        event.status = "skipped".to_string();
+        event.error = Some("XML não é uma NF-e/NFC-e".to_string());
+        return event;
+    }
+
+    log::info!("👀 Novo arquivo na pasta monitorada: {}", path_str);
+
+    // Duplicatas exatas são vinculadas ao documento existente em vez de recusadas
+    match ingest::ingest_file(state, &user, &path_str, Some("link"), None).await {
+        Ok(document) => {
+            event.status = if document.duplicate_of.is_some() { "duplicate" } else { "processed" }.to_string();
+            event.document_id = Some(document.id);
+
+            if folder.archive_processed {
+                match archive_original(folder, path) {
+                    Ok(archived) => log::info!("📦 Original arquivado em {}", archived.display()),
+                    Err(e) => log::warn!("⚠️ Não foi possível arquivar {}: {:?}", path_str, e),
+                }
+            }
+        }
+        Err(e) => {
+            log::warn!("⚠️ Falha ao importar {}: {}", path_str, e);
+            event.error = Some(e);
+        }
+    }
+
+    event
+}
+
+// Arquivos da pasta (recursivo) que passam nos filtros, ignorando a subpasta de arquivamento
+fn list_candidate_files(root: &Path, folder: &WatchFolder) -> Vec<PathBuf> {
+    let archive_dir = root.join(&folder.archive_subfolder);
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::debug!("📂 Não foi possível ler {}: {:?}", dir.display(), e);
+                continue;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if path != archive_dir {
+                    dirs.push(path);
+                }
+                continue;
+            }
+
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            if matches_filters(&relative, &folder.include_patterns, &folder.exclude_patterns) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+// Mover o original para a subpasta de arquivamento, mantendo a estrutura relativa
+fn archive_original(folder: &WatchFolder, path: &Path) -> std::io::Result<PathBuf> {
+    let root = Path::new(&folder.path);
+    let relative = path.strip_prefix(root).unwrap_or_else(|_| Path::new(path.file_name().unwrap_or_default()));
+    let mut target = root.join(&folder.archive_subfolder).join(relative);
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Não sobrescrever arquivo já arquivado com o mesmo nome
+    if target.exists() {
+        let stem = target.file_stem().and_then(|s| s.to_str()).unwrap_or("arquivo").to_string();
+        let extension = target.extension().and_then(|e| e.to_str()).map(|e| format!(".{}", e)).unwrap_or_default();
+        target.set_file_name(format!("{}_{}{}", stem, Utc::now().format("%Y%m%d%H%M%S"), extension));
+    }
+
+    if fs::rename(path, &target).is_err() {
+        // Origem e destino em volumes diferentes
+        fs::copy(path, &target)?;
+        fs::remove_file(path)?;
+    }
+    Ok(target)
+}
+
+// ================================
+// FILTROS GLOB
+// ================================
+
+/// Padrões sem "/" valem para o nome do arquivo; com "/" valem para o caminho relativo
+pub fn matches_filters(relative_path: &str, include: &[String], exclude: &[String]) -> bool {
+    let file_name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+    let matches = |pattern: &String| {
+        if pattern.contains('/') {
+            glob_match(pattern, relative_path)
+        } else {
+            glob_match(pattern, file_name)
+        }
+    };
+
+    // Sem padrões de inclusão, aceitar os tipos suportados pelo OCR simplificado
+    let included = if include.is_empty() {
+        ocr_simple::is_supported_file(Path::new(file_name))
+    } else {
+        include.iter().any(matches)
+    };
+
+    included
+        && !exclude.iter().any(matches)
+        && !DEFAULT_EXCLUDE_PATTERNS.iter().any(|p| glob_match(p, file_name))
+}
+
+/// Glob simples sem diferenciar maiúsculas: `*` (não cruza "/"), `**` (cruza "/") e `?`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    glob_match_from(&pattern, &text)
+}
+
+fn glob_match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            // "**/" também casa com zero diretórios
+            let rest = &pattern[2..];
+            let rest_no_slash = rest.strip_prefix(&['/']).unwrap_or(rest);
+            glob_match_from(rest_no_slash, text)
+                || (0..=text.len()).any(|i| glob_match_from(rest, &text[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if glob_match_from(rest, &text[i..]) {
+                    return true;
+                }
+                if i < text.len() && text[i] == '/' {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => !text.is_empty() && text[0] != '/' && glob_match_from(&pattern[1..], &text[1..]),
+        Some(c) => text.first() == Some(c) && glob_match_from(&pattern[1..], &text[1..]),
+    }
+}
+
+// ================================
+// COMANDOS DE CONFIGURAÇÃO
+// ================================
+
+#[tauri::command]
+pub async fn list_watch_folders(
+    state: State<'_, AppState>,
+) -> Result<Vec<WatchFolder>, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        state.db.list_watch_folders(&user.id)
+            .map_err(|e| format!("Erro ao buscar pastas monitoradas: {:?}", e))
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn add_watch_folder(
+    path: String,
+    include_patterns: Option<Vec<String>>,
+    exclude_patterns: Option<Vec<String>>,
+    archive_processed: Option<bool>,
+    archive_subfolder: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<WatchFolder, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        if !Path::new(&path).is_dir() {
+            return Err(format!("Pasta não encontrada: {}", path));
+        }
+
+        let existing = state.db.list_watch_folders(&user.id)
+            .map_err(|e| format!("Erro ao buscar pastas monitoradas: {:?}", e))?;
+        if existing.iter().any(|f| f.path == path) {
+            return Err("Esta pasta já está sendo monitorada".to_string());
+        }
+
+        let folder = WatchFolder {
+            id: Uuid::new_v4().to_string(),
+            user_id: user.id.clone(),
+            path,
+            include_patterns: clean_patterns(include_patterns.unwrap_or_default()),
+            exclude_patterns: clean_patterns(exclude_patterns.unwrap_or_default()),
+            archive_processed: archive_processed.unwrap_or(false),
+            archive_subfolder: clean_archive_subfolder(archive_subfolder)?,
+            enabled: true,
+            created_at: Utc::now(),
+        };
+
+        state.db.save_watch_folder(&folder)
+            .map_err(|e| format!("Erro ao salvar pasta monitorada: {:?}", e))?;
+
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "WATCH_FOLDER_ADD",
+            "SYSTEM",
+            Some(folder.id.clone()),
+            Some(folder.path.clone()),
+            None,
+            Some(serde_json::json!({
+                "include_patterns": folder.include_patterns,
+                "exclude_patterns": folder.exclude_patterns,
+                "archive_processed": folder.archive_processed,
+            })),
+            true,
+        ).await;
+
+        log::info!("👀 Pasta monitorada adicionada: {}", folder.path);
+        Ok(folder)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn update_watch_folder(
+    watch_folder_id: String,
+    include_patterns: Option<Vec<String>>,
+    exclude_patterns: Option<Vec<String>>,
+    archive_processed: Option<bool>,
+    archive_subfolder: Option<String>,
+    enabled: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<WatchFolder, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let mut folder = state.db.list_watch_folders(&user.id)
+            .map_err(|e| format!("Erro ao buscar pastas monitoradas: {:?}", e))?
+            .into_iter()
+            .find(|f| f.id == watch_folder_id)
+            .ok_or_else(|| "Pasta monitorada não encontrada".to_string())?;
+
+        if let Some(patterns) = include_patterns {
+            folder.include_patterns = clean_patterns(patterns);
+        }
+        if let Some(patterns) = exclude_patterns {
+            folder.exclude_patterns = clean_patterns(patterns);
+        }
+        if let Some(archive) = archive_processed {
+            folder.archive_processed = archive;
+        }
+        if archive_subfolder.is_some() {
+            folder.archive_subfolder = clean_archive_subfolder(archive_subfolder)?;
+        }
+        if let Some(enabled) = enabled {
+            folder.enabled = enabled;
+        }
+
+        state.db.save_watch_folder(&folder)
+            .map_err(|e| format!("Erro ao salvar pasta monitorada: {:?}", e))?;
+
+        let _ = log_audit_event(
+            &state,
+            &user.id,
+            &user.username,
+            "WATCH_FOLDER_UPDATE",
+            "SYSTEM",
+            Some(folder.id.clone()),
+            Some(folder.path.clone()),
+            None,
+            Some(serde_json::to_value(&folder).unwrap_or_default()),
+            true,
+        ).await;
+
+        Ok(folder)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn remove_watch_folder(
+    watch_folder_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let authenticated_user = state.authenticated_user.lock().await;
+    if let Some(user) = authenticated_user.as_ref() {
+        let removed = state.db.delete_watch_folder(&user.id, &watch_folder_id)
+            .map_err(|e| format!("Erro ao remover pasta monitorada: {:?}", e))?;
+
+        if removed {
+            let _ = log_audit_event(
+                &state,
+                &user.id,
+                &user.username,
+                "WATCH_FOLDER_REMOVE",
+                "SYSTEM",
+                Some(watch_folder_id),
+                None,
+                None,
+                None,
+                true,
+            ).await;
+        }
+
+        Ok(removed)
+    } else {
+        Err("Usuário não autenticado".to_string())
+    }
+}
+
+fn clean_patterns(patterns: Vec<String>) -> Vec<String> {
+    patterns.into_iter()
+        .map(|p| p.trim().replace('\\', "/"))
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+fn clean_archive_subfolder(subfolder: Option<String>) -> Result<String, String> {
+    let subfolder = subfolder
+        .map(|s| s.trim().trim_matches(|c| c == '/' || c == '\\').to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT_ARCHIVE_SUBFOLDER.to_string());
+
+    if subfolder.split(['/', '\\']).any(|part| part == "..") {
+        return Err("Subpasta de arquivamento inválida".to_string());
+    }
+    Ok(subfolder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.pdf", "Nota.PDF"));
+        assert!(glob_match("nf-????.pdf", "NF-2024.pdf"));
+        assert!(!glob_match("*.pdf", "nota.pdf.tmp"));
+        assert!(!glob_match("*.pdf", "2024/nota.pdf"));
+        assert!(glob_match("**/*.pdf", "2024/01/nota.pdf"));
+        assert!(glob_match("**/*.pdf", "nota.pdf"));
+        assert!(glob_match("recibos/**", "recibos/2024/a.jpg"));
+        assert!(!glob_match("recibos/*", "recibos/2024/a.jpg"));
+    }
+
+    #[test]
+    fn test_matches_filters() {
+        let none: Vec<String> = vec![];
+        assert!(matches_filters("scan_001.pdf", &none, &none));
+        assert!(matches_filters("2024/scan.JPG", &none, &none));
+        assert!(matches_filters("notas.docx", &none, &none));
+        assert!(!matches_filters("instalador.exe", &none, &none));
+        assert!(!matches_filters("~$planilha.xlsx", &none, &none));
+        assert!(!matches_filters(".scan.pdf", &none, &none));
+
+        let include = vec!["*.pdf".to_string()];
+        let exclude = vec!["rascunhos/**".to_string(), "*_draft.pdf".to_string()];
+        assert!(matches_filters("contrato.pdf", &include, &exclude));
+        assert!(!matches_filters("foto.png", &include, &exclude));
+        assert!(!matches_filters("rascunhos/contrato.pdf", &include, &exclude));
+        assert!(!matches_filters("contrato_draft.pdf", &include, &exclude));
+    }
+
+    #[test]
+    fn test_candidate_files_and_nfe_sniffing() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("nota.xml"), "<?xml version=\"1.0\"?><nfeProc><NFe><infNFe Id=\"NFe1\"></infNFe></NFe></nfeProc>").unwrap();
+        fs::write(dir.path().join("pom.xml"), "<?xml version=\"1.0\"?><project></project>").unwrap();
+
+        let folder = WatchFolder {
+            id: "f1".to_string(),
+            user_id: "u1".to_string(),
+            path: dir.path().to_string_lossy().to_string(),
+            include_patterns: vec![],
+            exclude_patterns: vec![],
+            archive_processed: false,
+            archive_subfolder: "processados".to_string(),
+            enabled: true,
+            created_at: Utc::now(),
+        };
+        // A listagem só olha os nomes; o conteúdo é conferido uma vez, no processamento
+        let files = list_candidate_files(dir.path(), &folder);
+        assert_eq!(files, vec![dir.path().join("nota.xml"), dir.path().join("pom.xml")]);
+        assert!(!ocr_simple::is_non_nfe_xml(&files[0]));
+        assert!(ocr_simple::is_non_nfe_xml(&files[1]));
+    }
+}